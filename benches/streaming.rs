@@ -0,0 +1,67 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use schedulib::single_machine::*;
+use schedulib::ScheduleStats;
+use schedulib::generators::random_single_machine;
+
+
+/// Allocator recording the peak number of allocated bytes
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+		PEAK.fetch_max(current, Ordering::Relaxed);
+		System.alloc(layout)
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+		System.dealloc(ptr, layout)
+	}
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+/// Returns the peak memory allocated by `f` beyond what was allocated before.
+fn peak_bytes<F: FnOnce()>(f: F) -> usize {
+	let before = CURRENT.load(Ordering::Relaxed);
+	PEAK.store(before, Ordering::Relaxed);
+	f();
+	PEAK.load(Ordering::Relaxed) - before
+}
+
+
+fn benchmark_streaming(c: &mut Criterion) {
+	let instance = random_single_machine(1_000_000, 100, 1);
+	let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+	let collected = peak_bytes(|| {
+		black_box(schrage(p, r, d).max_lateness(d));
+	});
+	let streamed = peak_bytes(|| {
+		let mut stats = ScheduleStats::new(d);
+		schrage_streaming(p, r, d, |run| stats.record(run));
+		black_box(stats.max_lateness);
+	});
+	println!("peak memory of schrage on 1e6 jobs: {} bytes collected, {} bytes streamed", collected, streamed);
+	let mut group = c.benchmark_group("schrage 1e6");
+	group.sample_size(10);
+	group.bench_function("collected", |b| b.iter(|| schrage(black_box(p), r, d).max_lateness(d)));
+	group.bench_function("streamed", |b| b.iter(|| {
+		let mut stats = ScheduleStats::new(d);
+		schrage_streaming(black_box(p), r, d, |run| stats.record(run));
+		stats.max_lateness
+	}));
+	group.finish();
+}
+
+criterion_group!(benches, benchmark_streaming);
+
+
+criterion_main!(benches);
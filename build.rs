@@ -0,0 +1,19 @@
+fn main() {
+	println!("cargo:rerun-if-changed=build.rs");
+	#[cfg(feature = "ffi")]
+	{
+		println!("cargo:rerun-if-changed=src/ffi.rs");
+		println!("cargo:rerun-if-changed=cbindgen.toml");
+		let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+		let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+		// OUT_DIR is <target>/<profile>/build/<package>/out, and the library is placed in <target>/<profile>
+		let profile_dir = out_dir.ancestors().nth(3).unwrap();
+		let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+		cbindgen::Builder::new()
+			.with_crate(&crate_dir)
+			.with_config(config)
+			.generate()
+			.expect("Unable to generate the C header")
+			.write_to_file(profile_dir.join("schedulib.h"));
+	}
+}
@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use schedulib::{Time, Job, MachineSchedule, MultiMachineSchedule, validate_ptimes};
+use schedulib::flow_shop;
+use schedulib::single_machine::{self, CarlierSearch};
+
+
+/// A run as returned to Python: (start, job, duration)
+type Run = (Time, Job, Time);
+
+/// The number of subproblems carlier explores between checks of the time limit
+const NODES_PER_STEP: usize = 64;
+
+
+fn runs(schedule: &MachineSchedule) -> Vec<Run> {
+	schedule.schedule.iter().map(|run| (run.time, run.job, run.duration)).collect()
+}
+
+/// Checks that the vectors have the same length and the processing times are not negative.
+fn validate(ptimes: &[Time], others: &[&[Time]]) -> PyResult<()> {
+	if others.iter().any(|v| v.len() != ptimes.len()) {
+		return Err(PyValueError::new_err("All lists must have one entry per job"));
+	}
+	validate_ptimes(ptimes).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+
+/// Schrage's heuristic for 1|r_j|L_max.
+#[pyfunction]
+fn schrage(py: Python<'_>, p: Vec<Time>, r: Vec<Time>, d: Vec<Time>) -> PyResult<Vec<Run>> {
+	validate(&p, &[&r, &d])?;
+	Ok(py.detach(|| runs(&single_machine::schrage(&p, &r, &d))))
+}
+
+/// Carlier's branch and bound for 1|r_j|L_max.
+/// The GIL is released during the search. If `time_limit` in seconds is given and reached,
+/// the best schedule found so far is returned, which need not be optimal.
+#[pyfunction]
+#[pyo3(signature = (p, r, d, time_limit=None))]
+fn carlier(py: Python<'_>, p: Vec<Time>, r: Vec<Time>, d: Vec<Time>, time_limit: Option<f64>) -> PyResult<Vec<Run>> {
+	validate(&p, &[&r, &d])?;
+	let limit = time_limit
+		.map(|seconds| Duration::try_from_secs_f64(seconds).map_err(|_| PyValueError::new_err("Invalid time limit")))
+		.transpose()?;
+	Ok(py.detach(|| {
+		let start = Instant::now();
+		let mut search = CarlierSearch::new(&p, &r, &d);
+		// the first step finds a schedule, so there is always one to return
+		while !search.step(NODES_PER_STEP) && limit.is_none_or(|limit| start.elapsed() < limit) {}
+		runs(search.best_schedule().unwrap())
+	}))
+}
+
+/// The preemptive EDD rule, optimal for 1|r_j,pmtn|L_max.
+#[pyfunction]
+fn edd_preemptive(py: Python<'_>, p: Vec<Time>, r: Vec<Time>, d: Vec<Time>) -> PyResult<Vec<Run>> {
+	validate(&p, &[&r, &d])?;
+	Ok(py.detach(|| runs(&single_machine::edd_preemptive(p, &r, &d))))
+}
+
+/// Johnson's algorithm, optimal for F2||C_max. `ptimes[i][j]` is the time of job j on machine i.
+/// Returns the runs of each machine.
+#[pyfunction]
+fn johnson(py: Python<'_>, ptimes: Vec<Vec<Time>>) -> PyResult<Vec<Vec<Run>>> {
+	if ptimes.len() != 2 {
+		return Err(PyValueError::new_err("Instance must have exactly 2 machines"));
+	}
+	validate(&ptimes[0], &[])?;
+	validate(&ptimes[1], &[&ptimes[0]])?;
+	Ok(py.detach(|| {
+		let order = flow_shop::johnson(&ptimes);
+		let schedule = MultiMachineSchedule::from_order_ptimes(&order, &ptimes);
+		schedule.machine_schedules.iter().map(runs).collect()
+	}))
+}
+
+/// Hodgson's algorithm, optimal for 1||sum U_j.
+#[pyfunction]
+fn hodgson(py: Python<'_>, p: Vec<Time>, d: Vec<Time>) -> PyResult<Vec<Run>> {
+	validate(&p, &[&d])?;
+	Ok(py.detach(|| runs(&single_machine::schedule_hodgson(&p, &d))))
+}
+
+
+/// Scheduling algorithms of schedulib.
+/// Schedules are lists of runs `(start, job, duration)`, where jobs are indices into the input lists.
+#[pymodule]
+#[pyo3(name = "schedulib")]
+fn schedulib_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add_function(wrap_pyfunction!(schrage, m)?)?;
+	m.add_function(wrap_pyfunction!(carlier, m)?)?;
+	m.add_function(wrap_pyfunction!(edd_preemptive, m)?)?;
+	m.add_function(wrap_pyfunction!(johnson, m)?)?;
+	m.add_function(wrap_pyfunction!(hodgson, m)?)?;
+	Ok(())
+}
@@ -0,0 +1,53 @@
+import pytest
+
+import schedulib
+
+
+def max_lateness(runs, d):
+    return max(start + duration - d[job] for start, job, duration in runs)
+
+
+def test_schrage_and_carlier():
+    p, r, d = [3, 2, 4], [0, 1, 1], [5, 4, 9]
+    assert schedulib.schrage(p, r, d) == [(0, 0, 3), (3, 1, 2), (5, 2, 4)]
+    assert max_lateness(schedulib.carlier(p, r, d), d) == 1
+    assert max_lateness(schedulib.carlier(p, r, d, time_limit=1.0), d) == 1
+    assert schedulib.carlier([], [], []) == []
+
+
+def test_carlier_time_limit():
+    n = 200
+    p = [1 + (7 * j) % 13 for j in range(n)]
+    r = [(31 * j) % 500 for j in range(n)]
+    d = [r[j] + (17 * j) % 300 for j in range(n)]
+    runs = schedulib.carlier(p, r, d, time_limit=0.05)
+    assert sorted(job for _, job, _ in runs) == list(range(n))
+
+
+def test_edd_preemptive():
+    assert schedulib.edd_preemptive([4, 1], [0, 1], [10, 2]) == [(0, 0, 1), (1, 1, 1), (2, 0, 3)]
+
+
+def test_johnson():
+    machines = schedulib.johnson([[3, 1], [2, 4]])
+    assert machines == [[(0, 1, 1), (1, 0, 3)], [(1, 1, 4), (5, 0, 2)]]
+
+
+def test_hodgson():
+    runs = schedulib.hodgson([2, 3, 2], [2, 4, 4])
+    on_time = [job for start, job, duration in runs if start + duration <= [2, 4, 4][job]]
+    assert len(on_time) == 2
+
+
+@pytest.mark.parametrize("call", [
+    lambda: schedulib.schrage([1, -1], [0, 0], [0, 0]),
+    lambda: schedulib.carlier([1, 2], [0], [0, 0]),
+    lambda: schedulib.edd_preemptive([1], [0], []),
+    lambda: schedulib.johnson([[1, 2]]),
+    lambda: schedulib.johnson([[1, 2], [3]]),
+    lambda: schedulib.hodgson([-1], [0]),
+    lambda: schedulib.carlier([1], [0], [0], time_limit=-1.0),
+])
+def test_value_errors(call):
+    with pytest.raises(ValueError):
+        call()
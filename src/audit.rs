@@ -0,0 +1,360 @@
+use std::fmt;
+
+use crate::{Time, Job, Machine, MultiMachineSchedule};
+
+
+/// The data of an instance that a schedule is audited against.
+/// Constraints that are None are not checked.
+#[derive(Debug, Clone)]
+pub struct AuditInstance<'a> {
+	/// `ptimes[i]` is the total processing time of job `i`, which may be split into several runs
+	pub ptimes: &'a [Time],
+	/// `release_times[i]` is the earliest start time of every run of job `i`
+	pub release_times: Option<&'a [Time]>,
+	/// `deadlines[i]` is the latest completion time of job `i`
+	pub deadlines: Option<&'a [Time]>,
+	/// `predecessors[i]` are the jobs that need to be completed before job `i` can be started
+	pub predecessors: Option<&'a [Vec<Job>]>,
+}
+
+impl<'a> AuditInstance<'a> {
+	/// Returns an instance with the given processing times and no further constraints.
+	pub fn new(ptimes: &'a [Time]) -> AuditInstance<'a> {
+		AuditInstance{ ptimes, release_times: None, deadlines: None, predecessors: None }
+	}
+}
+
+
+/// A single violation found by `report`.
+/// Runs are identified by their machine and their index in the schedule of that machine.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Violation {
+	/// A run references a job that is not part of the instance
+	UnknownJob {
+		machine: Machine,
+		run: usize,
+		job: Job,
+	},
+	/// Two runs on the same machine are processed at the same time
+	Overlap {
+		machine: Machine,
+		first: usize,
+		second: usize,
+	},
+	/// A run starts before the release time of its job
+	EarlyStart {
+		job: Job,
+		start: Time,
+		release_time: Time,
+	},
+	/// A job starts before one of its predecessors is completed
+	Precedence {
+		predecessor: Job,
+		successor: Job,
+		completion: Time,
+		start: Time,
+	},
+	/// A job is not scheduled at all
+	MissingJob {
+		job: Job,
+	},
+	/// The runs of a job do not add up to its processing time
+	WrongProcessingTime {
+		job: Job,
+		processed: Time,
+		ptime: Time,
+	},
+	/// A job completes after its deadline
+	MissedDeadline {
+		job: Job,
+		completion: Time,
+		deadline: Time,
+	},
+}
+
+/// The categories of violations, in the order in which `AuditReport` lists them.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ViolationKind {
+	UnknownJob,
+	Overlap,
+	EarlyStart,
+	Precedence,
+	MissingJob,
+	WrongProcessingTime,
+	MissedDeadline,
+}
+
+impl ViolationKind {
+	const ALL: [ViolationKind; 7] = [
+		ViolationKind::UnknownJob,
+		ViolationKind::Overlap,
+		ViolationKind::EarlyStart,
+		ViolationKind::Precedence,
+		ViolationKind::MissingJob,
+		ViolationKind::WrongProcessingTime,
+		ViolationKind::MissedDeadline,
+	];
+
+	fn title(self) -> &'static str {
+		match self {
+			ViolationKind::UnknownJob => "Unknown jobs",
+			ViolationKind::Overlap => "Overlapping runs",
+			ViolationKind::EarlyStart => "Starts before release",
+			ViolationKind::Precedence => "Precedence violations",
+			ViolationKind::MissingJob => "Missing jobs",
+			ViolationKind::WrongProcessingTime => "Wrong processing times",
+			ViolationKind::MissedDeadline => "Missed deadlines",
+		}
+	}
+}
+
+impl Violation {
+	/// Returns the category of this violation.
+	pub fn kind(&self) -> ViolationKind {
+		match self {
+			Violation::UnknownJob{ .. } => ViolationKind::UnknownJob,
+			Violation::Overlap{ .. } => ViolationKind::Overlap,
+			Violation::EarlyStart{ .. } => ViolationKind::EarlyStart,
+			Violation::Precedence{ .. } => ViolationKind::Precedence,
+			Violation::MissingJob{ .. } => ViolationKind::MissingJob,
+			Violation::WrongProcessingTime{ .. } => ViolationKind::WrongProcessingTime,
+			Violation::MissedDeadline{ .. } => ViolationKind::MissedDeadline,
+		}
+	}
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Violation::UnknownJob{ machine, run, job } => write!(f,
+				"Run {} on machine {} processes unknown job #{}", run, machine, job
+			),
+			Violation::Overlap{ machine, first, second } => write!(f,
+				"Runs {} and {} on machine {} overlap", first, second, machine
+			),
+			Violation::EarlyStart{ job, start, release_time } => write!(f,
+				"Job #{} starts at {} before its release time {}", job, start, release_time
+			),
+			Violation::Precedence{ predecessor, successor, completion, start } => write!(f,
+				"Job #{} starts at {} before its predecessor #{} is completed at {}",
+				successor, start, predecessor, completion
+			),
+			Violation::MissingJob{ job } => write!(f, "Job #{} is not scheduled", job),
+			Violation::WrongProcessingTime{ job, processed, ptime } => write!(f,
+				"Job #{} is processed for {} instead of {} time units ({:+})", job, processed, ptime, processed - ptime
+			),
+			Violation::MissedDeadline{ job, completion, deadline } => write!(f,
+				"Job #{} completes at {} after its deadline {}", job, completion, deadline
+			),
+		}
+	}
+}
+
+
+/// All violations of a schedule found by `report`.
+/// `Display` lists them grouped by category.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AuditReport {
+	/// The violations in the order in which they were found
+	pub violations: Vec<Violation>,
+}
+
+impl AuditReport {
+	/// Returns whether the schedule has no violations.
+	pub fn is_valid(&self) -> bool {
+		self.violations.is_empty()
+	}
+
+	/// Returns the number of violations of the given category.
+	pub fn count(&self, kind: ViolationKind) -> usize {
+		self.violations.iter().filter(|v| v.kind() == kind).count()
+	}
+}
+
+impl fmt::Display for AuditReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_valid() {
+			return write!(f, "No violations");
+		}
+		let mut first = true;
+		for kind in ViolationKind::ALL {
+			let count = self.count(kind);
+			if count == 0 {
+				continue;
+			}
+			if !first {
+				writeln!(f)?;
+			}
+			first = false;
+			write!(f, "{} ({}):", kind.title(), count)?;
+			for violation in self.violations.iter().filter(|v| v.kind() == kind) {
+				write!(f, "\n  {}", violation)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+
+/// Checks a schedule against an instance and collects every violation instead of stopping at the first one,
+/// e.g. to tell the author of a hand-edited schedule everything that needs to be fixed.
+/// Jobs may be preempted and may run on any machine.
+///
+/// The start time of a job is the start of its first run and its completion time is the end of its last run.
+/// Runs occupy the half-open intervals [time, time + duration), so touching runs do not overlap.
+/// Every run that overlaps an earlier run on its machine is reported once,
+/// together with the earlier run that ends last.
+/// Precedence constraints involving unscheduled jobs are not checked, since these jobs are reported as missing.
+pub fn report(schedule: &MultiMachineSchedule, instance: &AuditInstance) -> AuditReport {
+	let n = instance.ptimes.len();
+	let mut violations = Vec::new();
+	let mut processed = vec![0; n];
+	let mut start: Vec<Option<Time>> = vec![None; n];
+	let mut completion = vec![Time::MIN; n];
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		// sweep over the runs by start time, remembering the run that ends last
+		let mut order: Vec<usize> = (0..s.schedule.len()).collect();
+		order.sort_by_key(|&i| (s.schedule[i].time, s.schedule[i].duration));
+		let mut latest: Option<(usize, Time)> = None;
+		for i in order {
+			let run = s.schedule[i];
+			let end = run.time + run.duration;
+			match latest {
+				Some((first, latest_end)) if run.time < latest_end => {
+					violations.push(Violation::Overlap{ machine, first: first.min(i), second: first.max(i) });
+					if end > latest_end {
+						latest = Some((i, end));
+					}
+				},
+				Some((_, latest_end)) if end <= latest_end => {},
+				_ => latest = Some((i, end)),
+			}
+		}
+		for (i, run) in s.schedule.iter().enumerate() {
+			if run.job >= n {
+				violations.push(Violation::UnknownJob{ machine, run: i, job: run.job });
+				continue;
+			}
+			if let Some(release_time) = instance.release_times.map(|r| r[run.job]) {
+				if run.time < release_time {
+					violations.push(Violation::EarlyStart{ job: run.job, start: run.time, release_time });
+				}
+			}
+			processed[run.job] += run.duration;
+			start[run.job] = Some(start[run.job].map_or(run.time, |t| t.min(run.time)));
+			completion[run.job] = completion[run.job].max(run.time + run.duration);
+		}
+	}
+	if let Some(predecessors) = instance.predecessors {
+		for (successor, preds) in predecessors.iter().enumerate() {
+			let Some(successor_start) = start[successor] else {
+				continue;
+			};
+			for &predecessor in preds {
+				if start[predecessor].is_some() && successor_start < completion[predecessor] {
+					violations.push(Violation::Precedence{
+						predecessor, successor, completion: completion[predecessor], start: successor_start
+					});
+				}
+			}
+		}
+	}
+	for job in 0..n {
+		if start[job].is_none() {
+			violations.push(Violation::MissingJob{ job });
+			continue;
+		}
+		if processed[job] != instance.ptimes[job] {
+			violations.push(Violation::WrongProcessingTime{ job, processed: processed[job], ptime: instance.ptimes[job] });
+		}
+		if let Some(deadline) = instance.deadlines.map(|d| d[job]) {
+			if completion[job] > deadline {
+				violations.push(Violation::MissedDeadline{ job, completion: completion[job], deadline });
+			}
+		}
+	}
+	AuditReport{ violations }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{JobRun, MachineSchedule};
+	use crate::identical_machines::edd_parallel;
+
+	fn run(time: Time, job: Job, duration: Time) -> JobRun {
+		JobRun{ time, job, duration }
+	}
+
+	#[test]
+	fn test_one_violation_of_each_kind() {
+		//                 0  1  2  3  4  5
+		let ptimes =     [3, 2, 4, 2, 1, 5];
+		let releases =   [0, 0, 2, 0, 0, 0];
+		let deadlines =  [9, 9, 9, 9, 9, 9];
+		let predecessors = vec![vec![], vec![], vec![], vec![0], vec![], vec![]];
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![
+				run(0, 0, 3),
+				// starts before job 0 is completed on the same machine
+				run(2, 3, 2),
+				// runs for 2 instead of 4, after its deadline
+				run(8, 2, 2),
+			]},
+			MachineSchedule{ schedule: vec![
+				// touching runs do not overlap
+				run(0, 1, 2),
+				run(2, 4, 1),
+				run(3, 7, 1),
+			]},
+		]};
+		let instance = AuditInstance{
+			ptimes: &ptimes,
+			release_times: Some(&releases),
+			deadlines: Some(&deadlines),
+			predecessors: Some(&predecessors),
+		};
+		let report = report(&schedule, &instance);
+		// the early start is checked separately below, since moving job 2 earlier also fixes its other violations
+		assert_eq!(report.violations, vec![
+			Violation::Overlap{ machine: 0, first: 0, second: 1 },
+			Violation::UnknownJob{ machine: 1, run: 2, job: 7 },
+			Violation::Precedence{ predecessor: 0, successor: 3, completion: 3, start: 2 },
+			Violation::WrongProcessingTime{ job: 2, processed: 2, ptime: 4 },
+			Violation::MissedDeadline{ job: 2, completion: 10, deadline: 9 },
+			Violation::MissingJob{ job: 5 },
+		]);
+		assert_eq!(report.count(ViolationKind::Overlap), 1);
+		assert_eq!(report.count(ViolationKind::EarlyStart), 0);
+		assert!(!report.is_valid());
+
+		let mut early = schedule.clone();
+		early.machine_schedules[0].schedule[2] = run(1, 2, 4);
+		let report = super::report(&early, &instance);
+		assert_eq!(report.count(ViolationKind::EarlyStart), 1);
+		assert!(report.violations.contains(&Violation::EarlyStart{ job: 2, start: 1, release_time: 2 }));
+		assert_eq!(report.count(ViolationKind::WrongProcessingTime), 0);
+		assert_eq!(report.count(ViolationKind::MissedDeadline), 0);
+	}
+
+	#[test]
+	fn test_display_groups_violations() {
+		let ptimes = [3, 2];
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![run(0, 0, 3), run(1, 1, 3), run(2, 0, 1)] },
+		]};
+		let report = report(&schedule, &AuditInstance::new(&ptimes));
+		assert_eq!(report.to_string(), concat!(
+			"Overlapping runs (2):\n",
+			"  Runs 0 and 1 on machine 0 overlap\n",
+			"  Runs 1 and 2 on machine 0 overlap\n",
+			"Wrong processing times (2):\n",
+			"  Job #0 is processed for 4 instead of 3 time units (+1)\n",
+			"  Job #1 is processed for 3 instead of 2 time units (+1)",
+		));
+		let valid = edd_parallel(&[4, 2, 3], &[5, 1, 3], 2);
+		assert!(super::report(&valid, &AuditInstance::new(&[4, 2, 3])).is_valid());
+		assert_eq!(AuditReport::default().to_string(), "No violations");
+	}
+}
@@ -0,0 +1,122 @@
+use crate::{Time, JobShopInstance};
+use crate::instances::{benchmark_200a, from_json, ft06};
+
+
+/// The names of the 1|r_j|L_max instances of `single_machine`, in order of size:
+/// * `small_50`: 50 jobs, optimal maximum lateness -4
+/// * `medium_100`: 100 jobs, optimal maximum lateness -21
+/// * `hard_200`: 200 jobs, optimal maximum lateness 1415, stored as `instances::benchmark_200a`
+/// * `large_1000`: 1000 jobs, maximum lateness 18 by `schrage` and -23 after 2000 nodes of `carlier`,
+///   with the lower bound -24 by `edd_preemptive`
+///
+/// The instances except `hard_200` were generated with `generators::random_single_machine`
+/// and fixed seeds, and are embedded so that they do not change with the generator.
+pub const SINGLE_MACHINE: [&str; 4] = ["small_50", "medium_100", "hard_200", "large_1000"];
+
+/// The names of the permutation flow shop instances of `flow_shop`, with processing times from 1 to 99:
+/// * `f2_500`: 2 machines and 500 jobs, solved by `flow_shop::johnson`
+/// * `f3_10`: 3 machines and 10 jobs, solved by `flow_shop::exact_bitmask`
+/// * `f5_12`: 5 machines and 12 jobs, solved by `flow_shop::exact_bitmask`
+pub const FLOW_SHOP: [&str; 3] = ["f2_500", "f3_10", "f5_12"];
+
+/// The names of the job shop instances of `job_shop`.
+pub const JOB_SHOP: [&str; 1] = ["ft06"];
+
+
+/// Returns a single machine instance for benchmarks, see `SINGLE_MACHINE` for the names.
+///
+/// # Returns
+/// Processing times, release times and due times.
+///
+/// # Panics
+/// If there is no instance with the given name.
+pub fn single_machine(name: &str) -> (Vec<Time>, Vec<Time>, Vec<Time>) {
+	let text = match name {
+		"hard_200" => return benchmark_200a(),
+		"small_50" => include_str!("../benchdata/small_50.json"),
+		"medium_100" => include_str!("../benchdata/medium_100.json"),
+		"large_1000" => include_str!("../benchdata/large_1000.json"),
+		_ => panic!("Unknown single machine instance \"{}\", expected one of {:?}", name, SINGLE_MACHINE),
+	};
+	let instance = from_json(text).expect("Embedded instances are valid");
+	(instance.ptimes, instance.release_times, instance.due_times)
+}
+
+/// Returns a permutation flow shop instance for benchmarks, see `FLOW_SHOP` for the names.
+///
+/// # Returns
+/// The processing times, where `ptimes[i][j]` is the time of job j on machine i, and the optimal makespan.
+///
+/// # Panics
+/// If there is no instance with the given name.
+pub fn flow_shop(name: &str) -> (Vec<Vec<Time>>, Time) {
+	let (text, optimum) = match name {
+		"f2_500" => (include_str!("../benchdata/f2_500.txt"), 26647),
+		"f3_10" => (include_str!("../benchdata/f3_10.txt"), 549),
+		"f5_12" => (include_str!("../benchdata/f5_12.txt"), 882),
+		_ => panic!("Unknown flow shop instance \"{}\", expected one of {:?}", name, FLOW_SHOP),
+	};
+	// the first line holds the number of machines and jobs, followed by one line per machine
+	let ptimes = text.lines().skip(1)
+		.map(|line| line.split_whitespace().map(|t| t.parse().expect("Embedded instances are valid")).collect())
+		.collect();
+	(ptimes, optimum)
+}
+
+/// Returns a job shop instance for benchmarks, see `JOB_SHOP` for the names.
+///
+/// # Panics
+/// If there is no instance with the given name.
+pub fn job_shop(name: &str) -> JobShopInstance {
+	match name {
+		"ft06" => ft06(),
+		_ => panic!("Unknown job shop instance \"{}\", expected one of {:?}", name, JOB_SHOP),
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::MultiMachineSchedule;
+	use crate::single_machine::{carlier, schrage, edd_preemptive};
+	use crate::flow_shop::{exact_bitmask, johnson};
+
+	#[test]
+	fn test_single_machine() {
+		for (name, n) in SINGLE_MACHINE.iter().zip([50, 100, 200, 1000]) {
+			let (p, r, d) = single_machine(name);
+			assert_eq!((p.len(), r.len(), d.len()), (n, n, n));
+		}
+		for (name, optimum) in [("small_50", -4), ("medium_100", -21)] {
+			let (p, r, d) = single_machine(name);
+			assert_eq!(carlier(&p, &r, &d).max_lateness(&d), optimum);
+		}
+		let (p, r, d) = single_machine("large_1000");
+		// the preemptive optimum is a lower bound
+		assert_eq!(edd_preemptive(p.clone(), &r, &d).max_lateness(&d), -24);
+		assert_eq!(schrage(&p, &r, &d).max_lateness(&d), 18);
+	}
+
+	#[test]
+	fn test_flow_shop() {
+		for (name, shape) in FLOW_SHOP.iter().zip([(2, 500), (3, 10), (5, 12)]) {
+			let (ptimes, optimum) = flow_shop(name);
+			assert_eq!(ptimes.len(), shape.0);
+			assert!(ptimes.iter().all(|row| row.len() == shape.1 && row.iter().all(|&t| (1..100).contains(&t))));
+			let makespan = if ptimes.len() == 2 {
+				MultiMachineSchedule::from_order_ptimes(&johnson(&ptimes), &ptimes).makespan()
+			} else {
+				exact_bitmask(&ptimes).unwrap().1
+			};
+			assert_eq!(makespan, optimum);
+		}
+		assert_eq!(job_shop("ft06").num_jobs(), 6);
+	}
+
+	#[test]
+	#[should_panic(expected = "Unknown single machine instance")]
+	fn test_unknown_name() {
+		single_machine("hard_2000");
+	}
+}
@@ -0,0 +1,228 @@
+use std::cmp::{max, min};
+
+use crate::Time;
+
+
+/// The working time of a machine, given by working windows that recur every `cycle` time units,
+/// e.g. the shifts of a week, and exceptions during which the machine does not work, e.g. holidays.
+/// A time t is a working time if t modulo the cycle lies in a window and t does not lie in an exception.
+/// Windows and exceptions are half-open intervals [start, end).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Calendar {
+	cycle: Time,
+	/// Sorted, disjoint and non-adjacent windows within [0, cycle]
+	windows: Vec<(Time, Time)>,
+	/// Sorted, disjoint and non-adjacent non-working intervals
+	exceptions: Vec<(Time, Time)>,
+}
+
+impl Calendar {
+	/// Returns a calendar with the given working windows recurring every `cycle` time units.
+	/// Overlapping and adjacent windows are merged, and empty windows are ignored.
+	///
+	/// # Panics
+	/// If `cycle` is not positive or a window does not lie within [0, cycle].
+	pub fn new(cycle: Time, windows: &[(Time, Time)]) -> Calendar {
+		assert!(cycle > 0, "Cycle length {} is not positive", cycle);
+		for &(start, end) in windows {
+			assert!(0 <= start && start <= end && end <= cycle,
+				"Window [{}, {}) does not lie within the cycle [0, {})", start, end, cycle
+			);
+		}
+		Calendar{ cycle, windows: merge_intervals(windows.to_vec()), exceptions: Vec::new() }
+	}
+
+	/// Returns a calendar in which every time is a working time,
+	/// with which schedules are the same as without a calendar.
+	pub fn always() -> Calendar {
+		Calendar::new(1, &[(0, 1)])
+	}
+
+	/// Returns this calendar with the additional non-working interval [start, end).
+	///
+	/// # Panics
+	/// If `end` is before `start`.
+	pub fn with_exception(mut self, start: Time, end: Time) -> Calendar {
+		assert!(start <= end, "Exception ends at {} before it starts at {}", end, start);
+		self.exceptions.push((start, end));
+		self.exceptions = merge_intervals(std::mem::take(&mut self.exceptions));
+		self
+	}
+
+	/// Returns whether `t` is a working time.
+	pub fn is_working(&self, t: Time) -> bool {
+		let offset = t.rem_euclid(self.cycle);
+		self.windows.iter().any(|&(start, end)| start <= offset && offset < end)
+			&& !self.exceptions.iter().any(|&(start, end)| start <= t && t < end)
+	}
+
+	/// Returns the earliest working time at or after `t`.
+	///
+	/// # Panics
+	/// If the calendar has no working time.
+	pub fn next_working_time(&self, t: Time) -> Time {
+		self.next_interval(t).0
+	}
+
+	/// Returns the time at which `work` units of working time beginning at `start` are completed,
+	/// or `start` if `work` is 0.
+	///
+	/// # Panics
+	/// If `work` is negative, or it is positive and the calendar has no working time.
+	pub fn advance(&self, start: Time, work: Time) -> Time {
+		self.working_intervals(start, work).last().map_or(start, |&(_, end)| end)
+	}
+
+	/// Returns the maximal intervals of working time in which `work` units of working time
+	/// beginning at `start` are processed, in order of time.
+	/// The result is empty if `work` is 0.
+	///
+	/// # Panics
+	/// If `work` is negative, or it is positive and the calendar has no working time.
+	pub fn working_intervals(&self, start: Time, work: Time) -> Vec<(Time, Time)> {
+		assert!(work >= 0, "Work {} is negative", work);
+		let mut result: Vec<(Time, Time)> = Vec::new();
+		let mut t = start;
+		let mut remaining = work;
+		while remaining > 0 {
+			let (a, b) = self.next_interval(t);
+			let end = min(b, a.saturating_add(remaining));
+			match result.last_mut() {
+				Some(last) if last.1 == a => last.1 = end,
+				_ => result.push((a, end)),
+			}
+			remaining -= end - a;
+			t = end;
+		}
+		result
+	}
+
+	/// Returns the earliest interval [a, b) of working time with a >= t,
+	/// which ends at the end of a window or the start of an exception.
+	fn next_interval(&self, mut t: Time) -> (Time, Time) {
+		assert!(!self.windows.is_empty(), "Calendar has no working time");
+		loop {
+			let (a, b) = self.next_window(t);
+			let i = self.exceptions.partition_point(|&(_, end)| end <= a);
+			match self.exceptions.get(i) {
+				Some(&(start, end)) if start <= a => t = end,
+				Some(&(start, _)) if start < b => return (a, start),
+				_ => return (a, b),
+			}
+		}
+	}
+
+	/// Like `next_interval`, but ignoring the exceptions.
+	fn next_window(&self, t: Time) -> (Time, Time) {
+		if self.windows == [(0, self.cycle)] {
+			return (t, Time::MAX);
+		}
+		let base = t - t.rem_euclid(self.cycle);
+		let offset = t - base;
+		let i = self.windows.partition_point(|&(_, end)| end <= offset);
+		match self.windows.get(i) {
+			Some(&(start, end)) => (base + max(start, offset), base + end),
+			None => (base + self.cycle + self.windows[0].0, base + self.cycle + self.windows[0].1),
+		}
+	}
+}
+
+
+/// Sorts the intervals and merges overlapping and adjacent ones, dropping empty intervals.
+fn merge_intervals(mut intervals: Vec<(Time, Time)>) -> Vec<(Time, Time)> {
+	intervals.retain(|&(start, end)| start < end);
+	intervals.sort_unstable();
+	let mut result: Vec<(Time, Time)> = Vec::with_capacity(intervals.len());
+	for (start, end) in intervals {
+		match result.last_mut() {
+			Some(last) if last.1 >= start => last.1 = max(last.1, end),
+			_ => result.push((start, end)),
+		}
+	}
+	result
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	const DAY: Time = 24 * 60;
+
+	/// Monday to Saturday from 06:00 to 22:00 in minutes, with the week starting on Monday 00:00
+	fn shifts() -> Calendar {
+		let windows: Vec<(Time, Time)> = (0..6).map(|day| (day * DAY + 6 * 60, day * DAY + 22 * 60)).collect();
+		Calendar::new(7 * DAY, &windows)
+	}
+
+	#[test]
+	fn test_shifts() {
+		let calendar = shifts();
+		// 5 minutes on Monday evening, the rest on Tuesday morning
+		let start = 22 * 60 - 5;
+		assert_eq!(calendar.working_intervals(start, 30), vec![(start, 22 * 60), (DAY + 6 * 60, DAY + 6 * 60 + 25)]);
+		assert_eq!(calendar.advance(start, 30), DAY + 6 * 60 + 25);
+		assert_eq!(calendar.advance(start, 5), 22 * 60);
+		// released at night, starting in the morning
+		assert_eq!(calendar.next_working_time(23 * 60), DAY + 6 * 60);
+		assert!(!calendar.is_working(23 * 60));
+		// no work on Sunday
+		assert_eq!(calendar.next_working_time(5 * DAY + 22 * 60), 7 * DAY + 6 * 60);
+		assert_eq!(calendar.next_working_time(-DAY), 6 * 60);
+		// a holiday on Tuesday
+		let calendar = calendar.with_exception(DAY, 2 * DAY);
+		assert_eq!(calendar.advance(start, 30), 2 * DAY + 6 * 60 + 25);
+		assert!(!calendar.is_working(DAY + 12 * 60));
+		assert_eq!(calendar.advance(start, 0), start);
+	}
+
+	#[test]
+	fn test_always() {
+		let calendar = Calendar::always();
+		assert_eq!(calendar.working_intervals(-5, 100), vec![(-5, 95)]);
+		assert_eq!(calendar.advance(Time::MAX - 3, 3), Time::MAX);
+		let calendar = calendar.with_exception(10, 20).with_exception(15, 30);
+		assert_eq!(calendar.working_intervals(0, 20), vec![(0, 10), (30, 40)]);
+		// windows touching at the end of the cycle form one interval
+		let calendar = Calendar::new(10, &[(0, 2), (8, 10), (1, 3)]);
+		assert_eq!(calendar.working_intervals(8, 6), vec![(8, 13), (18, 19)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "Calendar has no working time")]
+	fn test_no_working_time() {
+		Calendar::new(5, &[(2, 2)]).advance(0, 1);
+	}
+
+	proptest! {
+		#[test]
+		fn test_working_intervals(
+			windows in proptest::collection::vec((0..12 as Time, 0..12 as Time), 1..4),
+			exceptions in proptest::collection::vec((-10..40 as Time, 0..10 as Time), 0..3),
+			start in -15..15 as Time,
+			work in 0..20 as Time
+		) {
+			let windows: Vec<(Time, Time)> = windows.into_iter().map(|(a, b)| (a.min(b), a.max(b) + 1)).collect();
+			let mut calendar = Calendar::new(13, &windows);
+			for (s, l) in exceptions {
+				calendar = calendar.with_exception(s, s + l);
+			}
+			// compare with scanning every time unit
+			let mut expected = Vec::new();
+			let mut t = start;
+			while (expected.len() as Time) < work {
+				if calendar.is_working(t) {
+					expected.push(t);
+				}
+				t += 1;
+			}
+			let intervals = calendar.working_intervals(start, work);
+			let covered: Vec<Time> = intervals.iter().flat_map(|&(a, b)| a..b).collect();
+			prop_assert_eq!(covered, expected);
+			for pair in intervals.windows(2) {
+				prop_assert!(pair[0].1 < pair[1].0);
+			}
+		}
+	}
+}
@@ -0,0 +1,132 @@
+use std::cmp::max;
+
+use crate::{Time, MultiMachineSchedule};
+
+
+/// Monetary costs of a schedule:
+/// every machine costs a rate per busy time unit, every job costs a rate per time unit it is late,
+/// and a holding rate per time unit it completes before its due time.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CostModel {
+	/// `machine_rates[i]` is the cost of machine i per time unit it processes a job.
+	pub machine_rates: Vec<Time>,
+	/// `tardiness_rates[j]` is the cost of job j per time unit it completes after its due time.
+	pub tardiness_rates: Vec<Time>,
+	/// `holding_rates[j]` is the cost of job j per time unit it completes before its due time.
+	pub holding_rates: Vec<Time>,
+}
+
+
+/// The cost of a schedule under a `CostModel`, decomposed by category and by machine and job.
+/// `total` is the sum of the three categories, `machine_cost` the sum of `per_machine`,
+/// and `tardiness_cost + holding_cost` the sum of `per_job`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CostBreakdown {
+	pub total: Time,
+	pub machine_cost: Time,
+	pub tardiness_cost: Time,
+	pub holding_cost: Time,
+	/// `per_machine[i]` is the cost of running machine i.
+	pub per_machine: Vec<Time>,
+	/// `per_job[j]` is the tardiness and holding cost of job j, 0 if it is not scheduled.
+	pub per_job: Vec<Time>,
+}
+
+
+impl CostModel {
+	/// Returns a model in which everything is free.
+	pub fn zero(num_machines: usize, num_jobs: usize) -> CostModel {
+		CostModel{
+			machine_rates: vec![0; num_machines],
+			tardiness_rates: vec![0; num_jobs],
+			holding_rates: vec![0; num_jobs],
+		}
+	}
+
+	/// Returns the cost of the schedule.
+	/// Jobs complete with their last run on any machine.
+	///
+	/// # Arguments
+	/// * `schedule`: The schedule
+	/// * `due_times`: `due_times[j]` is the due time of job j.
+	///
+	/// # Panics
+	/// If the schedule has a different number of machines than the model.
+	pub fn evaluate(&self, schedule: &MultiMachineSchedule, due_times: &[Time]) -> CostBreakdown {
+		assert_eq!(schedule.machine_schedules.len(), self.machine_rates.len(), "Every machine needs a rate");
+		let per_machine: Vec<Time> = schedule.machine_schedules.iter().zip(self.machine_rates.iter())
+			.map(|(s, &rate)| rate * s.schedule.iter().map(|run| run.duration).sum::<Time>())
+			.collect();
+		let mut per_job = vec![0; self.tardiness_rates.len()];
+		let mut tardiness_cost = 0;
+		let mut holding_cost = 0;
+		for (job, c) in schedule.completion_times() {
+			let tardiness = self.tardiness_rates[job] * max(0, c - due_times[job]);
+			let holding = self.holding_rates[job] * max(0, due_times[job] - c);
+			per_job[job] = tardiness + holding;
+			tardiness_cost += tardiness;
+			holding_cost += holding;
+		}
+		let machine_cost = per_machine.iter().sum();
+		CostBreakdown{
+			total: machine_cost + tardiness_cost + holding_cost,
+			machine_cost,
+			tardiness_cost,
+			holding_cost,
+			per_machine,
+			per_job,
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MachineSchedule, JobRun};
+	use crate::testing::flow_shop_ptimes;
+	use proptest::prelude::*;
+
+	#[test]
+	fn test_evaluate() {
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![JobRun{ time: 0, job: 0, duration: 4 }, JobRun{ time: 4, job: 2, duration: 3 }] },
+			MachineSchedule{ schedule: vec![JobRun{ time: 1, job: 1, duration: 5 }] },
+		]};
+		let model = CostModel{
+			machine_rates: vec![2, 3],
+			tardiness_rates: vec![10, 10, 1],
+			holding_rates: vec![1, 1, 1],
+		};
+		// completions are 4, 6, 7
+		let cost = model.evaluate(&schedule, &[6, 5, 5]);
+		assert_eq!(cost.per_machine, vec![2 * 7, 3 * 5]);
+		assert_eq!(cost.per_job, vec![2, 10, 2]);
+		assert_eq!((cost.machine_cost, cost.tardiness_cost, cost.holding_cost), (29, 12, 2));
+		assert_eq!(cost.total, 43);
+	}
+
+	proptest! {
+		#[test]
+		fn test_breakdown_sums_to_total(
+			ptimes in flow_shop_ptimes(1..=3, 1..=6, 10),
+			rates in proptest::collection::vec(0..5 as Time, 15),
+			due_times in proptest::collection::vec(0..30 as Time, 6)
+		) {
+			let (m, n) = (ptimes.len(), ptimes[0].len());
+			let schedule = MultiMachineSchedule::from_order_ptimes(&(0..n).collect::<Vec<_>>(), &ptimes);
+			let model = CostModel{
+				machine_rates: rates[..m].to_vec(),
+				tardiness_rates: rates[3..3 + n].to_vec(),
+				holding_rates: rates[9..9 + n].to_vec(),
+			};
+			let cost = model.evaluate(&schedule, &due_times);
+			prop_assert_eq!(cost.per_machine.iter().sum::<Time>(), cost.machine_cost);
+			prop_assert_eq!(cost.per_job.iter().sum::<Time>(), cost.tardiness_cost + cost.holding_cost);
+			prop_assert_eq!(cost.machine_cost + cost.tardiness_cost + cost.holding_cost, cost.total);
+			let zero = CostModel::zero(m, n).evaluate(&schedule, &due_times);
+			prop_assert_eq!(zero.total, 0);
+			prop_assert!(zero.per_machine.iter().chain(zero.per_job.iter()).all(|&c| c == 0));
+		}
+	}
+}
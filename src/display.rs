@@ -0,0 +1,158 @@
+use std::fmt::{self, Write};
+
+use crate::{Time, JobRun};
+
+
+/// Options for formatting schedules as text.
+/// The default options yield the output of `Display`.
+#[derive(Debug, Clone)]
+pub struct DisplayOptions<'a> {
+	/// Show idle time between two runs on the same machine as an `-- idle --` row.
+	pub show_idle: bool,
+	/// If given, append the lateness of each run with respect to these due times,
+	/// where `due_times[i]` is the due time of job `i`.
+	pub due_times: Option<&'a [Time]>,
+	/// Print times relative to the earliest start time in the schedule instead of absolute times.
+	/// This also applies to the makespans printed by `MultiMachineSchedule::format`.
+	pub relative_times: bool,
+	/// If given, print `job_labels[i]` next to the id of job `i`.
+	pub job_labels: Option<&'a [String]>,
+	/// Right-align all times to a common width (default). Otherwise times are printed without padding.
+	pub align: bool,
+	/// Print the duration of each run (as `start+duration`) instead of its end time (as `start-end`).
+	pub show_duration: bool,
+	/// How times are printed.
+	pub time_format: TimeFormat,
+}
+
+impl Default for DisplayOptions<'_> {
+	fn default() -> Self {
+		DisplayOptions {
+			show_idle: false,
+			due_times: None,
+			relative_times: false,
+			job_labels: None,
+			align: true,
+			show_duration: false,
+			time_format: TimeFormat::Integer,
+		}
+	}
+}
+
+
+/// How times are printed by `DisplayOptions`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum TimeFormat {
+	/// As integers, e.g. `-5` and `5`
+	#[default]
+	Integer,
+	/// As integers with explicit sign, e.g. `-5` and `+5`
+	Signed,
+	/// As decimal numbers, where a time of `scale` is printed as `1`
+	/// with `precision` digits after the decimal point,
+	/// e.g. `1.250` for time 1250 with scale 1000 and precision 3.
+	/// The scale must be positive.
+	Decimal {
+		scale: Time,
+		precision: usize,
+	},
+}
+
+impl TimeFormat {
+	/// Returns the given time formatted according to this format.
+	///
+	/// # Panics
+	/// If this is a `Decimal` format with a scale that is not positive.
+	pub fn format(&self, t: Time) -> String {
+		match *self {
+			TimeFormat::Integer => t.to_string(),
+			TimeFormat::Signed => format!("{:+}", t),
+			TimeFormat::Decimal{ scale, precision } => {
+				assert!(scale > 0, "The scale of a decimal time format must be positive, got {}", scale);
+				format!("{:.*}", precision, t as f64 / scale as f64)
+			},
+		}
+	}
+}
+
+
+/// Formats the runs of one or more machines as rows,
+/// using a common time origin and column width.
+pub(crate) struct RowFormatter<'a> {
+	opts: &'a DisplayOptions<'a>,
+	origin: Time,
+	width: usize,
+}
+
+impl<'a> RowFormatter<'a> {
+	/// Creates a formatter for the given runs, which may belong to several machines.
+	pub fn new<'b, I>(opts: &'a DisplayOptions<'a>, runs: I) -> RowFormatter<'a>
+	where I: Iterator<Item = &'b JobRun> + Clone
+	{
+		let origin = if opts.relative_times {
+			runs.clone().map(|run| run.time).min().unwrap_or(0)
+		} else {
+			0
+		};
+		let width = if opts.align {
+			runs
+				.flat_map(|run| {
+					let second = if opts.show_duration { run.duration } else { run.time + run.duration - origin };
+					[run.time - origin, second]
+				})
+				.map(|t| opts.time_format.format(t).len())
+				.max()
+				.unwrap_or(0)
+		} else {
+			0
+		};
+		RowFormatter{ opts, origin, width }
+	}
+
+	/// Returns the given time formatted relative to the common time origin.
+	pub fn format_time(&self, t: Time) -> String {
+		self.opts.time_format.format(t - self.origin)
+	}
+
+	/// Writes one row per run of a single machine, plus idle rows if requested.
+	pub fn write_runs<W: Write>(&self, w: &mut W, runs: &[JobRun]) -> fmt::Result {
+		let mut previous_end = None;
+		for run in runs.iter() {
+			match previous_end {
+				Some(end) if self.opts.show_idle && run.time > end => {
+					self.write_interval(w, end, run.time)?;
+					writeln!(w, "-- idle --")?;
+				},
+				_ => {},
+			}
+			self.write_interval(w, run.time, run.time + run.duration)?;
+			write!(w, "Job #{}", run.job)?;
+			if let Some(labels) = self.opts.job_labels {
+				write!(w, " ({})", labels[run.job])?;
+			}
+			if let Some(due_times) = self.opts.due_times {
+				let lateness = run.time + run.duration - due_times[run.job];
+				write!(w, ", lateness {}", self.opts.time_format.format(lateness))?;
+			}
+			writeln!(w)?;
+			previous_end = Some(run.time + run.duration);
+		}
+		Ok(())
+	}
+
+	fn write_interval<W: Write>(&self, w: &mut W, start: Time, end: Time) -> fmt::Result {
+		let format = &self.opts.time_format;
+		let (separator, second) = if self.opts.show_duration {
+			('+', end - start)
+		} else {
+			('-', end - self.origin)
+		};
+		write!(w,
+			"{:>len$}{}{:>len$}: ",
+			format.format(start - self.origin),
+			separator,
+			format.format(second),
+			len = self.width
+		)
+	}
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use crate::{Time, Job, Machine};
+
+
+/// Errors reported by the algorithms of this crate
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScheduleError {
+	/// The instance has more jobs than the algorithm accepts
+	TooManyJobs {
+		num_jobs: usize,
+		max_jobs: usize,
+	},
+	/// A job has a negative processing time
+	NegativeDuration {
+		job: Job,
+		duration: Time,
+	},
+	/// The time lags between jobs cannot be satisfied,
+	/// because the given jobs form a cycle of constraints with positive total length
+	InfeasibleLags {
+		cycle: Vec<Job>,
+	},
+	/// Fixing the job sequence of the machine would create a cycle in the disjunctive graph
+	CyclicMachineSequence {
+		machine: Machine,
+	},
+	/// Transforming the given time overflows `Time`
+	TimeOverflow {
+		time: Time,
+	},
+	/// A job is due before it is released
+	DueBeforeRelease {
+		job: Job,
+		release_time: Time,
+		due_time: Time,
+	},
+	/// The machine index is not smaller than the number of machines of the schedule
+	MachineOutOfRange {
+		machine: Machine,
+		num_machines: usize,
+	},
+	/// A run would start before the previous run on the same machine completes
+	RunOutOfOrder {
+		machine: Machine,
+		time: Time,
+		previous_end: Time,
+	},
+	/// A JSON document does not match the exchange format
+	InvalidJson {
+		message: String,
+	},
+}
+
+impl fmt::Display for ScheduleError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ScheduleError::TooManyJobs{ num_jobs, max_jobs } => write!(f,
+				"Instance has {} jobs, but at most {} are supported", num_jobs, max_jobs
+			),
+			ScheduleError::NegativeDuration{ job, duration } => write!(f,
+				"Job #{} has negative processing time {}", job, duration
+			),
+			ScheduleError::InfeasibleLags{ cycle } => {
+				write!(f, "Time lags cannot be satisfied, violated by cycle ")?;
+				for job in cycle.iter() {
+					write!(f, "#{} -> ", job)?;
+				}
+				write!(f, "#{}", cycle[0])
+			},
+			ScheduleError::CyclicMachineSequence{ machine } => write!(f,
+				"The job sequence of machine {} creates a cycle", machine
+			),
+			ScheduleError::TimeOverflow{ time } => write!(f,
+				"Transforming time {} overflows", time
+			),
+			ScheduleError::DueBeforeRelease{ job, release_time, due_time } => write!(f,
+				"Job #{} is due at {} before it is released at {}", job, due_time, release_time
+			),
+			ScheduleError::MachineOutOfRange{ machine, num_machines } => write!(f,
+				"Machine {} does not exist, the schedule has {} machines", machine, num_machines
+			),
+			ScheduleError::RunOutOfOrder{ machine, time, previous_end } => write!(f,
+				"Run starting at {} on machine {} overlaps the previous run, which ends at {}", time, machine, previous_end
+			),
+			ScheduleError::InvalidJson{ message } => write!(f, "Invalid JSON: {}", message),
+		}
+	}
+}
+
+impl std::error::Error for ScheduleError {}
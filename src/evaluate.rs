@@ -0,0 +1,196 @@
+use std::cmp::{max, min};
+
+use crate::{Time, Job, Machine, MultiMachineSchedule};
+use crate::audit::AuditInstance;
+
+
+/// The penalty per time unit of every class of soft constraint violations, see `penalized_cost`.
+/// Weights larger than 1 make removing a violation worth more than the increase of the makespan
+/// caused by delaying a run for it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PenaltyWeights {
+	/// Per time unit that a run starts before the release time of its job
+	pub release: Time,
+	/// Per time unit that a run starts before a predecessor of its job is completed
+	pub precedence: Time,
+	/// Per time unit that a job completes after its deadline
+	pub deadline: Time,
+	/// Per time unit that two runs on the same machine overlap
+	pub overlap: Time,
+}
+
+impl PenaltyWeights {
+	/// Returns weights that penalize every class of violations with `weight` per time unit.
+	pub fn uniform(weight: Time) -> PenaltyWeights {
+		PenaltyWeights{ release: weight, precedence: weight, deadline: weight, overlap: weight }
+	}
+}
+
+impl Default for PenaltyWeights {
+	fn default() -> PenaltyWeights {
+		PenaltyWeights::uniform(1)
+	}
+}
+
+
+/// The classes of soft constraint violations penalized by `penalized_cost`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PenaltyKind {
+	/// The run starts before the release time of its job
+	Release,
+	/// The run starts before the predecessor `predecessor` of its job is completed
+	Precedence { predecessor: Job },
+	/// The run is the last run of its job, which completes after its deadline
+	Deadline,
+	/// The run overlaps the run with index `other` on the same machine, which starts earlier
+	Overlap { other: usize },
+}
+
+
+/// A penalty for a violation of one run.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PenaltyTerm {
+	/// The machine of the run
+	pub machine: Machine,
+	/// The index of the run in the schedule of its machine
+	pub run: usize,
+	/// The kind of violation
+	pub kind: PenaltyKind,
+	/// The number of time units by which the constraint is violated
+	pub amount: Time,
+	/// The amount multiplied with the weight of the kind of violation
+	pub penalty: Time,
+}
+
+
+/// The terms of the cost returned by `penalized_cost`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PenaltyBreakdown {
+	/// The makespan of the schedule
+	pub makespan: Time,
+	/// Every violation of every run, grouped by machine and run
+	pub terms: Vec<PenaltyTerm>,
+}
+
+impl PenaltyBreakdown {
+	/// Returns the sum of all penalties.
+	pub fn total_penalty(&self) -> Time {
+		self.terms.iter().map(|term| term.penalty).sum()
+	}
+}
+
+
+/// Evaluates a schedule that may violate the constraints of an instance as its makespan
+/// plus weighted penalties for the violations, so that imperfect schedules can be ranked and repaired.
+/// Every run is penalized for the time by which it starts before its release time
+/// and before the completion of each predecessor of its job,
+/// and for the time by which it overlaps each earlier run on its machine.
+/// The last run of every job is penalized for the time by which the job completes after its deadline.
+/// A feasible schedule has no penalty, so its cost is its makespan.
+///
+/// Missing jobs and wrong processing times are not penalized, see `audit::report` to check for them.
+/// Runs occupy the half-open intervals [time, time + duration), so touching runs do not overlap.
+/// Takes O(k^2 + e) time for k runs and e precedence constraints.
+///
+/// # Returns
+/// The total cost and its terms.
+///
+/// # Panics
+/// If a run processes a job that is not part of the instance.
+pub fn penalized_cost(
+	schedule: &MultiMachineSchedule,
+	instance: &AuditInstance,
+	penalties: &PenaltyWeights
+) -> (Time, PenaltyBreakdown)
+{
+	let n = instance.ptimes.len();
+	// the completion time of every job and its last run as (machine, index)
+	let mut completion: Vec<Option<(Time, Machine, usize)>> = vec![None; n];
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		for (i, run) in s.schedule.iter().enumerate() {
+			assert!(run.job < n, "Run {} on machine {} processes unknown job #{}", i, machine, run.job);
+			let end = run.time + run.duration;
+			if completion[run.job].is_none_or(|(c, _, _)| end > c) {
+				completion[run.job] = Some((end, machine, i));
+			}
+		}
+	}
+	let mut terms = Vec::new();
+	let mut add = |machine, run, kind, amount: Time, weight: Time| {
+		if amount > 0 {
+			terms.push(PenaltyTerm{ machine, run, kind, amount, penalty: amount * weight });
+		}
+	};
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		for (i, run) in s.schedule.iter().enumerate() {
+			if let Some(release_times) = instance.release_times {
+				add(machine, i, PenaltyKind::Release, release_times[run.job] - run.time, penalties.release);
+			}
+			if let Some(predecessors) = instance.predecessors {
+				for &predecessor in predecessors[run.job].iter() {
+					if let Some((c, _, _)) = completion[predecessor] {
+						add(machine, i, PenaltyKind::Precedence{ predecessor }, c - run.time, penalties.precedence);
+					}
+				}
+			}
+			if let (Some(deadlines), Some((c, m, last))) = (instance.deadlines, completion[run.job]) {
+				if (m, last) == (machine, i) {
+					add(machine, i, PenaltyKind::Deadline, c - deadlines[run.job], penalties.deadline);
+				}
+			}
+			for (other, earlier) in s.schedule.iter().enumerate() {
+				if (earlier.time, other) < (run.time, i) {
+					let overlap = min(earlier.time + earlier.duration, run.time + run.duration) - max(earlier.time, run.time);
+					add(machine, i, PenaltyKind::Overlap{ other }, overlap, penalties.overlap);
+				}
+			}
+		}
+	}
+	let breakdown = PenaltyBreakdown{ makespan: schedule.makespan(), terms };
+	(breakdown.makespan + breakdown.total_penalty(), breakdown)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{JobRun, MachineSchedule};
+	use crate::identical_machines::edd_parallel;
+
+	fn run(time: Time, job: Job, duration: Time) -> JobRun {
+		JobRun{ time, job, duration }
+	}
+
+	#[test]
+	fn test_penalized_cost() {
+		let ptimes = [3, 2, 2];
+		let releases = [0, 0, 4];
+		let deadlines = [9, 9, 5];
+		let predecessors = vec![vec![], vec![0], vec![]];
+		let instance = AuditInstance{
+			ptimes: &ptimes,
+			release_times: Some(&releases),
+			deadlines: Some(&deadlines),
+			predecessors: Some(&predecessors),
+		};
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![run(0, 1, 2), run(1, 0, 3), run(3, 2, 2)] },
+		]};
+		let (cost, breakdown) = penalized_cost(&schedule, &instance, &PenaltyWeights::uniform(10));
+		let term = |run, kind, amount| PenaltyTerm{ machine: 0, run, kind, amount, penalty: 10 * amount };
+		assert_eq!(breakdown.terms, vec![
+			// job 1 starts at 0, but job 0 completes at 4
+			term(0, PenaltyKind::Precedence{ predecessor: 0 }, 4),
+			term(1, PenaltyKind::Overlap{ other: 0 }, 1),
+			term(2, PenaltyKind::Release, 1),
+			term(2, PenaltyKind::Overlap{ other: 1 }, 1),
+		]);
+		assert_eq!(breakdown.makespan, 5);
+		assert_eq!(cost, 5 + 70);
+		// a feasible schedule is only charged its makespan
+		let feasible = edd_parallel(&[4, 2, 3], &[5, 1, 3], 2);
+		let (cost, breakdown) = penalized_cost(&feasible, &AuditInstance::new(&[4, 2, 3]), &PenaltyWeights::default());
+		assert!(breakdown.terms.is_empty());
+		assert_eq!(cost, feasible.makespan());
+	}
+}
@@ -0,0 +1,131 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Time, Job};
+
+
+/// The clock of an event-driven list scheduler, which keeps the release and completion events of jobs
+/// and advances the current time from event to event.
+///
+/// A scheduler typically collects the released jobs with `pop_release`, starts jobs and registers their
+/// completions with `add_completion`, and then advances to `next_event_time`.
+/// Events are never lost by advancing: events at or before the current time stay pending
+/// until they are popped.
+#[derive(Debug, Clone, Default)]
+pub struct EventClock {
+	time: Time,
+	releases: BinaryHeap<Reverse<(Time, Job)>>,
+	completions: BinaryHeap<Reverse<(Time, Job)>>,
+}
+
+impl EventClock {
+	/// Returns a clock at time `start` without any events.
+	pub fn new(start: Time) -> EventClock {
+		EventClock{ time: start, ..EventClock::default() }
+	}
+
+	/// Returns a clock at time `start` with a release event for every job,
+	/// where `release_times[j]` is the release time of job j.
+	pub fn with_releases(start: Time, release_times: &[Time]) -> EventClock {
+		let releases = release_times.iter().enumerate().map(|(job, &t)| Reverse((t, job))).collect();
+		EventClock{ time: start, releases, completions: BinaryHeap::new() }
+	}
+
+	/// Returns the current time.
+	pub fn time(&self) -> Time {
+		self.time
+	}
+
+	/// Adds an event releasing `job` at time `t`.
+	pub fn add_release(&mut self, job: Job, t: Time) {
+		self.releases.push(Reverse((t, job)));
+	}
+
+	/// Adds an event completing `job` at time `t`.
+	pub fn add_completion(&mut self, job: Job, t: Time) {
+		self.completions.push(Reverse((t, job)));
+	}
+
+	/// Returns whether there are no pending events.
+	pub fn is_empty(&self) -> bool {
+		self.releases.is_empty() && self.completions.is_empty()
+	}
+
+	/// Returns the time of the earliest pending event, or None if there are no pending events.
+	/// This may be the current time or earlier if events at these times have not been popped yet.
+	pub fn next_event_time(&self) -> Option<Time> {
+		let release = self.releases.peek().map(|Reverse((t, _))| *t);
+		let completion = self.completions.peek().map(|Reverse((t, _))| *t);
+		match (release, completion) {
+			(Some(r), Some(c)) => Some(r.min(c)),
+			(r, c) => r.or(c),
+		}
+	}
+
+	/// Sets the current time to `t`.
+	///
+	/// # Panics
+	/// If `t` is before the current time.
+	pub fn advance_to(&mut self, t: Time) {
+		assert!(t >= self.time, "Cannot advance the clock from {} back to {}", self.time, t);
+		self.time = t;
+	}
+
+	/// Removes and returns a job released at or before the current time,
+	/// the one with the earliest release time and the smallest index first.
+	pub fn pop_release(&mut self) -> Option<Job> {
+		match self.releases.peek() {
+			Some(&Reverse((t, job))) if t <= self.time => {
+				self.releases.pop();
+				Some(job)
+			},
+			_ => None,
+		}
+	}
+
+	/// Removes and returns the jobs completing at or before `t`, ordered by completion time and index.
+	/// `t` may differ from the current time.
+	pub fn completions_until(&mut self, t: Time) -> Vec<Job> {
+		let mut result = Vec::new();
+		while let Some(&Reverse((completion, job))) = self.completions.peek() {
+			if completion > t {
+				break;
+			}
+			self.completions.pop();
+			result.push(job);
+		}
+		result
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_events() {
+		let mut clock = EventClock::with_releases(0, &[3, -1, 3]);
+		assert_eq!(clock.next_event_time(), Some(-1));
+		assert_eq!(clock.pop_release(), Some(1));
+		assert_eq!(clock.pop_release(), None);
+		clock.add_completion(1, 2);
+		clock.add_completion(7, 2);
+		assert_eq!(clock.next_event_time(), Some(2));
+		clock.advance_to(2);
+		assert_eq!(clock.completions_until(clock.time()), vec![1, 7]);
+		assert_eq!(clock.next_event_time(), Some(3));
+		clock.advance_to(5);
+		assert_eq!((clock.pop_release(), clock.pop_release(), clock.pop_release()), (Some(0), Some(2), None));
+		assert!(clock.is_empty());
+		assert_eq!(clock.next_event_time(), None);
+		assert!(clock.completions_until(Time::MAX).is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_advance_backwards() {
+		let mut clock = EventClock::new(4);
+		clock.advance_to(3);
+	}
+}
@@ -0,0 +1,388 @@
+use std::fmt;
+
+use crate::{Time, MachineSchedule, SingleMachineInstance};
+use crate::objective::{Objective, Makespan, MaxLateness, TotalTardiness, NumTardy, TotalCompletionTime};
+use crate::reference::brute_force_single_machine;
+
+
+/// A named family of seeded single machine instances.
+pub struct InstanceGen {
+	/// Name of the family in reports
+	pub name: String,
+	/// One instance is generated for each seed
+	pub seeds: Vec<u64>,
+	/// Generates the instance for a seed, must be deterministic
+	pub generate: Box<dyn Fn(u64) -> SingleMachineInstance>,
+}
+
+impl InstanceGen {
+	/// Creates a generator producing `generate(seed)` for every seed in `seeds`.
+	pub fn new<F>(name: &str, seeds: impl IntoIterator<Item = u64>, generate: F) -> InstanceGen
+	where
+		F: Fn(u64) -> SingleMachineInstance + 'static
+	{
+		InstanceGen{ name: name.to_string(), seeds: seeds.into_iter().collect(), generate: Box::new(generate) }
+	}
+}
+
+
+/// A named single machine algorithm.
+pub struct NamedAlgorithm {
+	/// Name of the algorithm in reports
+	pub name: String,
+	/// Computes a schedule for an instance
+	pub run: Box<dyn Fn(&SingleMachineInstance) -> MachineSchedule>,
+}
+
+impl NamedAlgorithm {
+	/// Creates an algorithm with the given name.
+	pub fn new<F>(name: &str, run: F) -> NamedAlgorithm
+	where
+		F: Fn(&SingleMachineInstance) -> MachineSchedule + 'static
+	{
+		NamedAlgorithm{ name: name.to_string(), run: Box::new(run) }
+	}
+}
+
+
+/// The objective by which `compare` measures schedules. Smaller values are better.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ObjectiveKind {
+	Makespan,
+	MaxLateness,
+	TotalTardiness,
+	NumTardy,
+	TotalCompletionTime,
+}
+
+impl ObjectiveKind {
+	/// Returns the objective value of the schedule for the given due times.
+	pub fn evaluate(&self, schedule: &MachineSchedule, due_times: &[Time]) -> Time {
+		match self {
+			ObjectiveKind::Makespan => Makespan.evaluate_schedule(schedule, due_times),
+			ObjectiveKind::MaxLateness => MaxLateness.evaluate_schedule(schedule, due_times),
+			ObjectiveKind::TotalTardiness => TotalTardiness.evaluate_schedule(schedule, due_times),
+			ObjectiveKind::NumTardy => NumTardy.evaluate_schedule(schedule, due_times),
+			ObjectiveKind::TotalCompletionTime => TotalCompletionTime.evaluate_schedule(schedule, due_times),
+		}
+	}
+}
+
+
+/// The result of one algorithm on one instance.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RunResult {
+	pub generator: String,
+	pub seed: u64,
+	pub algorithm: String,
+	/// The objective value of the algorithm's schedule
+	pub value: Time,
+	/// `value` minus the value of the reference, i.e. the best algorithm or the exact reference
+	pub gap: Time,
+}
+
+
+/// Summary statistics of the gaps of one algorithm on the instances of one generator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+	pub generator: String,
+	pub algorithm: String,
+	pub mean_gap: f64,
+	pub median_gap: f64,
+	pub max_gap: Time,
+	/// Number of instances on which no other algorithm is strictly better
+	pub wins: usize,
+}
+
+
+/// The results of `compare`, with one `Summary` per generator and algorithm
+/// in the order in which they were given.
+/// `Display` prints the summaries as an aligned table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonReport {
+	pub results: Vec<RunResult>,
+	pub summaries: Vec<Summary>,
+}
+
+impl ComparisonReport {
+	/// Returns the raw results as CSV with a header line
+	/// and the columns generator, seed, algorithm, value and gap.
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from("generator,seed,algorithm,value,gap\n");
+		for r in self.results.iter() {
+			csv += &format!("{},{},{},{},{}\n", csv_field(&r.generator), r.seed, csv_field(&r.algorithm), r.value, r.gap);
+		}
+		csv
+	}
+}
+
+impl fmt::Display for ComparisonReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let header = ["generator", "algorithm", "mean gap", "median gap", "max gap", "wins"];
+		let rows: Vec<[String; 6]> = self.summaries.iter().map(|s| [
+			s.generator.clone(),
+			s.algorithm.clone(),
+			format!("{:.2}", s.mean_gap),
+			format!("{:.2}", s.median_gap),
+			s.max_gap.to_string(),
+			s.wins.to_string(),
+		]).collect();
+		let mut widths = header.map(|h| h.len());
+		for row in rows.iter() {
+			for (w, cell) in widths.iter_mut().zip(row.iter()) {
+				*w = (*w).max(cell.len());
+			}
+		}
+		let header = header.map(|h| h.to_string());
+		for row in std::iter::once(&header).chain(rows.iter()) {
+			// names are left-aligned, numbers right-aligned
+			write!(f, "{:<w0$}  {:<w1$}", row[0], row[1], w0 = widths[0], w1 = widths[1])?;
+			for (cell, &w) in row[2..].iter().zip(widths[2..].iter()) {
+				write!(f, "  {:>w$}", cell, w = w)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+fn csv_field(s: &str) -> String {
+	if s.contains([',', '"', '\n']) {
+		format!("\"{}\"", s.replace('"', "\"\""))
+	} else {
+		s.to_string()
+	}
+}
+
+
+/// Runs every algorithm on every instance of every generator and compares the objective values.
+/// The gap of an algorithm on an instance is its value minus the best value of all algorithms on that instance.
+/// The result is deterministic if the generators and algorithms are.
+///
+/// # Arguments
+/// * `generators`: The instance families
+/// * `algorithms`: The algorithms to compare
+/// * `metric`: The objective
+///
+/// # Returns
+/// The raw results and summary statistics.
+pub fn compare(generators: &[InstanceGen], algorithms: &[NamedAlgorithm], metric: ObjectiveKind) -> ComparisonReport {
+	compare_with_reference(generators, algorithms, metric, None)
+}
+
+
+/// Like `compare`, but if `reference` is given, gaps are measured against its value,
+/// e.g. the optimum computed by an exact algorithm.
+/// Wins are still counted among `algorithms`.
+pub fn compare_with_reference(
+	generators: &[InstanceGen],
+	algorithms: &[NamedAlgorithm],
+	metric: ObjectiveKind,
+	reference: Option<&NamedAlgorithm>
+) -> ComparisonReport
+{
+	let mut results = Vec::new();
+	let mut summaries = Vec::new();
+	for generator in generators.iter() {
+		// values[a][i] is the value of algorithm a on instance i
+		let mut values = vec![Vec::with_capacity(generator.seeds.len()); algorithms.len()];
+		let mut baselines = Vec::with_capacity(generator.seeds.len());
+		for &seed in generator.seeds.iter() {
+			let instance = (generator.generate)(seed);
+			let evaluate = |algorithm: &NamedAlgorithm| metric.evaluate(&(algorithm.run)(&instance), &instance.due_times);
+			for (a, algorithm) in algorithms.iter().enumerate() {
+				values[a].push(evaluate(algorithm));
+			}
+			let best = values.iter().filter_map(|v| v.last().copied()).min();
+			let baseline = reference.map(evaluate).or(best).unwrap_or(0);
+			baselines.push(baseline);
+			for (a, algorithm) in algorithms.iter().enumerate() {
+				let value = *values[a].last().unwrap();
+				results.push(RunResult{
+					generator: generator.name.clone(),
+					seed,
+					algorithm: algorithm.name.clone(),
+					value,
+					gap: value - baseline,
+				});
+			}
+		}
+		let num_instances = generator.seeds.len();
+		for (a, algorithm) in algorithms.iter().enumerate() {
+			let mut gaps: Vec<Time> = values[a].iter().zip(baselines.iter()).map(|(v, b)| v - b).collect();
+			gaps.sort_unstable();
+			let wins = (0..num_instances).filter(|&i| values.iter().all(|v| v[i] >= values[a][i])).count();
+			summaries.push(Summary{
+				generator: generator.name.clone(),
+				algorithm: algorithm.name.clone(),
+				mean_gap: if gaps.is_empty() { 0.0 } else { gaps.iter().sum::<Time>() as f64 / gaps.len() as f64 },
+				median_gap: median(&gaps),
+				max_gap: gaps.last().copied().unwrap_or(0),
+				wins,
+			});
+		}
+	}
+	ComparisonReport{ results, summaries }
+}
+
+/// A summary of the quality of one single machine schedule, see `report`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScheduleReport {
+	/// The value of the objective passed to `report`
+	pub value: Time,
+	pub makespan: Time,
+	/// The maximum lateness, `Time::MIN` for an empty schedule
+	pub max_lateness: Time,
+	pub total_tardiness: Time,
+	pub num_tardy: usize,
+	/// The optimal value of the objective, if the instance is small enough to be solved exactly
+	pub optimum: Option<Time>,
+}
+
+impl ScheduleReport {
+	/// Returns `value` minus the optimal value, if it is known.
+	pub fn gap(&self) -> Option<Time> {
+		self.optimum.map(|optimum| self.value - optimum)
+	}
+}
+
+impl fmt::Display for ScheduleReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "objective:       {}", self.value)?;
+		writeln!(f, "makespan:        {}", self.makespan)?;
+		if self.max_lateness == Time::MIN {
+			writeln!(f, "max lateness:    -")?;
+		} else {
+			writeln!(f, "max lateness:    {}", self.max_lateness)?;
+		}
+		writeln!(f, "total tardiness: {}", self.total_tardiness)?;
+		writeln!(f, "tardy jobs:      {}", self.num_tardy)?;
+		match (self.optimum, self.gap()) {
+			(Some(optimum), Some(gap)) => writeln!(f, "optimum:         {} (gap {})", optimum, gap),
+			_ => writeln!(f, "optimum:         unknown"),
+		}
+	}
+}
+
+
+/// Evaluates a schedule of a single machine instance by the given objective and the common regular objectives.
+/// If the instance has at most `reference::MAX_BRUTE_FORCE_JOBS` jobs, the optimal value of the objective
+/// among non-preemptive schedules is computed with `reference::brute_force_single_machine`,
+/// so that the gap to the optimum is known. The gap of a preemptive schedule can be negative.
+///
+/// # Arguments
+/// * `instance`: The instance the schedule belongs to
+/// * `schedule`: A schedule of the jobs of `instance`
+/// * `objective`: The objective of the problem, minimized by the exact solver
+pub fn report(instance: &SingleMachineInstance, schedule: &MachineSchedule, objective: &dyn Objective) -> ScheduleReport {
+	let due_times = &instance.due_times;
+	let optimum = brute_force_single_machine(&instance.ptimes, &instance.release_times, due_times, objective)
+		.ok()
+		.map(|(_, value)| value);
+	ScheduleReport{
+		value: objective.evaluate_schedule(schedule, due_times),
+		makespan: Makespan.evaluate_schedule(schedule, due_times),
+		max_lateness: MaxLateness.evaluate_schedule(schedule, due_times),
+		total_tardiness: TotalTardiness.evaluate_schedule(schedule, due_times),
+		num_tardy: schedule.num_tardy(due_times),
+		optimum,
+	}
+}
+
+/// Median of sorted values, or 0 if there are none.
+fn median(sorted: &[Time]) -> f64 {
+	let n = sorted.len();
+	match n {
+		0 => 0.0,
+		_ if n % 2 == 1 => sorted[n / 2] as f64,
+		_ => (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0,
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::generators::random_single_machine;
+	use crate::single_machine::{schrage, carlier};
+
+	fn algorithms() -> Vec<NamedAlgorithm> {
+		vec![
+			NamedAlgorithm::new("schrage", |i| schrage(&i.ptimes, &i.release_times, &i.due_times)),
+			NamedAlgorithm::new("carlier", |i| carlier(&i.ptimes, &i.release_times, &i.due_times)),
+		]
+	}
+
+	#[test]
+	fn test_compare_schrage_carlier() {
+		let generators = vec![InstanceGen::new("n=8", 0..20, |seed| random_single_machine(8, 10, seed))];
+		let report = compare(&generators, &algorithms(), ObjectiveKind::MaxLateness);
+		assert_eq!(report.results.len(), 40);
+		// carlier is exact, so it always has gap 0
+		for pair in report.results.chunks(2) {
+			assert_eq!((pair[0].seed, pair[0].algorithm.as_str(), pair[1].algorithm.as_str()), (pair[1].seed, "schrage", "carlier"));
+			assert!(pair[1].gap <= pair[0].gap);
+			assert_eq!(pair[1].gap, 0);
+		}
+		assert_eq!(report.summaries[1].wins, 20);
+		assert_eq!(report, compare(&generators, &algorithms(), ObjectiveKind::MaxLateness));
+	}
+
+	#[test]
+	fn test_report_output() {
+		let generators = vec![InstanceGen::new("fixed", [1, 2], |seed| SingleMachineInstance::new(
+			&[3, 2], &[0, 0], &[2, seed as Time]
+		).unwrap())];
+		let algorithms = vec![
+			NamedAlgorithm::new("in order", |i| MachineSchedule::from_ptimes(&i.ptimes)),
+			NamedAlgorithm::new("reversed", |i| MachineSchedule::from_order_ptimes([1, 0].into_iter(), &i.ptimes)),
+		];
+		let report = compare(&generators, &algorithms, ObjectiveKind::MaxLateness);
+		// in order: L_max is 5 - seed, reversed: 3
+		assert_eq!(report.to_csv(), concat!(
+			"generator,seed,algorithm,value,gap\n",
+			"fixed,1,in order,4,1\n",
+			"fixed,1,reversed,3,0\n",
+			"fixed,2,in order,3,0\n",
+			"fixed,2,reversed,3,0\n",
+		));
+		assert_eq!(report.to_string(), concat!(
+			"generator  algorithm  mean gap  median gap  max gap  wins\n",
+			"fixed      in order       0.50        0.50        1     1\n",
+			"fixed      reversed       0.00        0.00        0     2\n",
+		));
+		let with_reference = compare_with_reference(&generators, &algorithms, ObjectiveKind::MaxLateness, Some(&algorithms[0]));
+		// reversed is better than the reference on the first instance
+		assert_eq!((with_reference.summaries[1].mean_gap, with_reference.summaries[1].max_gap), (-0.5, 0));
+	}
+
+	#[test]
+	fn test_schedule_report() {
+		let instance = SingleMachineInstance::new(&[3, 2, 4], &[0, 1, 1], &[5, 4, 9]).unwrap();
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes([2, 1, 0].into_iter(), &instance.ptimes, &instance.release_times);
+		let result = report(&instance, &schedule, &MaxLateness);
+		assert_eq!(result, ScheduleReport{
+			value: 5,
+			makespan: 10,
+			max_lateness: 5,
+			total_tardiness: 8,
+			num_tardy: 2,
+			optimum: Some(1),
+		});
+		assert_eq!(result.gap(), Some(4));
+		assert_eq!(result.to_string(), concat!(
+			"objective:       5\n",
+			"makespan:        10\n",
+			"max lateness:    5\n",
+			"total tardiness: 8\n",
+			"tardy jobs:      2\n",
+			"optimum:         1 (gap 4)\n",
+		));
+		// too many jobs to solve exactly
+		let instance = random_single_machine(12, 10, 0);
+		let schedule = schrage(&instance.ptimes, &instance.release_times, &instance.due_times);
+		let result = report(&instance, &schedule, &TotalTardiness);
+		assert_eq!((result.optimum, result.gap()), (None, None));
+		assert_eq!(result.value, result.total_tardiness);
+	}
+}
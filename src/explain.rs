@@ -0,0 +1,160 @@
+use std::fmt;
+
+use crate::{Time, Job, MachineSchedule};
+use crate::single_machine::critical_path;
+
+
+/// Why the job of maximum lateness of a single machine schedule is late, see `lateness`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LatenessExplanation {
+	/// The job of maximum lateness, completing the critical block
+	pub critical_job: Job,
+	/// The maximum lateness of the schedule
+	pub lateness: Time,
+	/// The jobs processed without idle time up to the critical job, in order
+	pub block: Vec<Job>,
+	/// The start of the critical block
+	pub block_start: Time,
+	/// The jobs at the end of the block that give the largest lower bound on the maximum lateness, in order
+	pub binding_jobs: Vec<Job>,
+	/// The total processing time of `binding_jobs`
+	pub binding_processing: Time,
+	/// The lower bound of `binding_jobs`: their earliest release time plus their total processing time
+	/// minus their latest due time
+	pub bound: Time,
+	/// The jobs of `binding_jobs` released at their earliest release time
+	pub anchors: Vec<Job>,
+	/// The earliest release time of `binding_jobs`
+	pub anchor_release: Time,
+}
+
+impl LatenessExplanation {
+	/// The part of the lateness that no schedule can avoid, the lower bound of the binding jobs.
+	pub fn structural(&self) -> Time {
+		self.bound
+	}
+
+	/// The part of the lateness above the lower bound, caused by the sequence of the jobs.
+	pub fn sequencing(&self) -> Time {
+		self.lateness - self.bound
+	}
+}
+
+impl fmt::Display for LatenessExplanation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "Job {} has the maximum lateness {}.", self.critical_job, self.lateness)?;
+		writeln!(f, "It completes the block {:?}, processed without idle time from {}.", self.block, self.block_start)?;
+		writeln!(f,
+			"The jobs {:?} are released from {} on (jobs {:?}) and need {} time units, so one of them is at least {} late.",
+			self.binding_jobs, self.anchor_release, self.anchors, self.binding_processing, self.bound
+		)?;
+		writeln!(f, "Structural lateness: {}, sequencing lateness: {}.", self.structural(), self.sequencing())
+	}
+}
+
+
+/// Explains the maximum lateness of a non-preemptive single machine schedule, e.g. of `single_machine::carlier`.
+/// The critical block of the schedule is found with `single_machine::critical_path`.
+/// Among the sets of jobs at the end of the block, the binding one maximizes the lower bound
+/// min r_j + sum p_j - max d_j over its jobs j, which holds for every schedule.
+/// Its bound is the structural part of the lateness, and the rest is the sequencing part,
+/// which a better order of the jobs may remove.
+/// If the sequencing part is 0, the schedule is optimal, but an optimal schedule may have a positive
+/// sequencing part, as the bound ignores the jobs before the binding ones.
+/// Takes O(k) time for k runs.
+///
+/// # Arguments
+/// * `schedule`: The schedule, whose runs are sorted by start time and respect the release times
+/// * `p`: The processing times of the jobs
+/// * `r`: The release times of the jobs
+/// * `d`: The due times of the jobs
+///
+/// # Panics
+/// If the schedule is empty.
+pub fn lateness(schedule: &MachineSchedule, p: &[Time], r: &[Time], d: &[Time]) -> LatenessExplanation {
+	let (a, last) = critical_path(schedule, d);
+	let runs = &schedule.schedule[a..=last];
+	let critical_job = runs[runs.len() - 1].job;
+	// the bound of every suffix of the block, keeping the longest one in case of ties
+	let mut best: Option<(Time, usize, Time, Time)> = None;
+	let (mut release, mut total, mut due) = (Time::MAX, 0, Time::MIN);
+	for (i, run) in runs.iter().enumerate().rev() {
+		release = release.min(r[run.job]);
+		total += p[run.job];
+		due = due.max(d[run.job]);
+		let bound = release + total - due;
+		if best.is_none_or(|(b, _, _, _)| bound >= b) {
+			best = Some((bound, i, release, total));
+		}
+	}
+	let (bound, first, anchor_release, binding_processing) = best.unwrap();
+	let binding_jobs: Vec<Job> = runs[first..].iter().map(|run| run.job).collect();
+	LatenessExplanation{
+		critical_job,
+		lateness: runs[runs.len() - 1].time + runs[runs.len() - 1].duration - d[critical_job],
+		block: runs.iter().map(|run| run.job).collect(),
+		block_start: runs[0].time,
+		anchors: binding_jobs.iter().copied().filter(|&job| r[job] == anchor_release).collect(),
+		binding_jobs,
+		binding_processing,
+		bound,
+		anchor_release,
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::single_machine::{carlier, schrage};
+
+	fn example_2() -> (Vec<Time>, Vec<Time>, Vec<Time>) {
+		(
+			vec![ 5,   6,   7,   4,  3,   6,  2],
+			vec![10,  13,  11,  20, 30,   0, 30],
+			vec![-7, -26, -24, -21, -8, -17,  0]
+		)
+	}
+
+	fn example_3() -> (Vec<Time>, Vec<Time>, Vec<Time>) {
+		(
+			vec![ 4,   2,   5,   6,   3,   9,   2,   4,   1,   3],
+			vec![20,  25,  38,  12,  24,   4,  21,   6,  37,  20],
+			vec![35,  34,  44,  32,  27,  25,  29,  31,  40,  44]
+		)
+	}
+
+	#[test]
+	fn test_lateness_example_2() {
+		let (p, r, d) = example_2();
+		let explanation = lateness(&schrage(&p, &r, &d), &p, &r, &d);
+		// job 3 completes at 32 with due time -21
+		assert_eq!((explanation.critical_job, explanation.lateness), (3, 53));
+		assert_eq!((explanation.block.clone(), explanation.block_start), (vec![0, 1, 2, 3], 10));
+		// jobs 1, 2 and 3 are released from 11 on, need 17 time units and are due at -21 at the latest
+		assert_eq!(explanation.binding_jobs, vec![1, 2, 3]);
+		assert_eq!((explanation.anchor_release, explanation.anchors.clone()), (11, vec![2]));
+		assert_eq!(explanation.binding_processing, 17);
+		assert_eq!((explanation.structural(), explanation.sequencing()), (49, 4));
+		assert_eq!(explanation.to_string(), "\
+Job 3 has the maximum lateness 53.
+It completes the block [0, 1, 2, 3], processed without idle time from 10.
+The jobs [1, 2, 3] are released from 11 on (jobs [2]) and need 17 time units, so one of them is at least 49 late.
+Structural lateness: 49, sequencing lateness: 4.
+");
+		// the optimum 50 exceeds the bound 11 + 13 + 24 of jobs 2 and 1
+		let explanation = lateness(&carlier(&p, &r, &d), &p, &r, &d);
+		assert_eq!(explanation.binding_jobs, vec![2, 1]);
+		assert_eq!((explanation.lateness, explanation.structural(), explanation.sequencing()), (50, 48, 2));
+	}
+
+	#[test]
+	fn test_lateness_example_3() {
+		let (p, r, d) = example_3();
+		let explanation = lateness(&schrage(&p, &r, &d), &p, &r, &d);
+		assert_eq!(explanation.block, vec![5, 7, 3, 6, 4]);
+		// job 4 alone is released at 24 and due at 27, so it can complete on time
+		assert_eq!((explanation.critical_job, explanation.binding_jobs.clone()), (4, vec![4]));
+		assert_eq!((explanation.lateness, explanation.structural(), explanation.sequencing()), (1, 0, 1));
+	}
+}
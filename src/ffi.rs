@@ -0,0 +1,205 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{Time, MachineSchedule};
+use crate::single_machine::{carlier, edd_preemptive};
+
+
+/// The call succeeded.
+pub const SCHEDULIB_OK: i32 = 0;
+/// An input array or `out_len` is null.
+pub const SCHEDULIB_ERROR_NULL_POINTER: i32 = 1;
+/// A processing time is negative.
+pub const SCHEDULIB_ERROR_NEGATIVE_PROCESSING_TIME: i32 = 2;
+/// A time cannot be represented on this platform, or a time of the schedule overflows.
+pub const SCHEDULIB_ERROR_TIME_OUT_OF_RANGE: i32 = 3;
+/// The buffer cannot hold all runs. `*out_len` is set to the number of runs.
+pub const SCHEDULIB_ERROR_BUFFER_TOO_SMALL: i32 = 4;
+/// The solver failed unexpectedly.
+pub const SCHEDULIB_ERROR_INTERNAL: i32 = 5;
+
+
+/// A run of a schedule, see `JobRun`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct CRun {
+	/// The time at which the run starts
+	pub start: i64,
+	/// The index of the job in the input arrays
+	pub job: usize,
+	/// The duration of the run
+	pub duration: i64,
+}
+
+
+/// Solves 1|r_j|L_max with `single_machine::carlier`.
+/// The library never allocates memory that the caller has to free:
+/// the caller passes a buffer for the runs together with its capacity in `*out_len`,
+/// and the function writes the number of runs of the schedule to `*out_len`.
+/// Passing a null buffer queries the number of runs without writing any.
+///
+/// # Arguments
+/// * `n`: The number of jobs
+/// * `p`, `r`, `d`: The processing, release and due times of the jobs, arrays of length `n`
+/// * `out_runs`: A buffer for the runs of the schedule, or null to query their number
+/// * `out_len`: The capacity of `out_runs` on input, the number of runs of the schedule on output
+///
+/// # Returns
+/// `SCHEDULIB_OK` or one of the `SCHEDULIB_ERROR_*` codes.
+///
+/// # Safety
+/// `p`, `r` and `d` must be valid for reading `n` values unless `n` is 0,
+/// `out_len` must be valid for reading and writing,
+/// and `out_runs` must be null or valid for writing `*out_len` runs.
+#[no_mangle]
+pub unsafe extern "C" fn schedulib_carlier(
+	n: usize,
+	p: *const i64,
+	r: *const i64,
+	d: *const i64,
+	out_runs: *mut CRun,
+	out_len: *mut usize
+) -> i32
+{
+	solve(n, p, r, d, out_runs, out_len, |p, r, d| carlier(&p, &r, &d))
+}
+
+/// Solves 1|r_j,pmtn|L_max with `single_machine::edd_preemptive`.
+/// A job may have several runs, so the number of runs can exceed `n`.
+/// The arguments, result and safety requirements are the same as for `schedulib_carlier`.
+///
+/// # Safety
+/// See `schedulib_carlier`.
+#[no_mangle]
+pub unsafe extern "C" fn schedulib_edd_preemptive(
+	n: usize,
+	p: *const i64,
+	r: *const i64,
+	d: *const i64,
+	out_runs: *mut CRun,
+	out_len: *mut usize
+) -> i32
+{
+	solve(n, p, r, d, out_runs, out_len, |p, r, d| edd_preemptive(p, &r, &d))
+}
+
+
+/// Validates and converts the input, runs `solver` and writes the schedule to `out_runs`.
+///
+/// # Safety
+/// See `schedulib_carlier`.
+unsafe fn solve<F>(
+	n: usize,
+	p: *const i64,
+	r: *const i64,
+	d: *const i64,
+	out_runs: *mut CRun,
+	out_len: *mut usize,
+	solver: F
+) -> i32
+where
+	F: FnOnce(Vec<Time>, Vec<Time>, Vec<Time>) -> MachineSchedule
+{
+	if out_len.is_null() || (n > 0 && (p.is_null() || r.is_null() || d.is_null())) {
+		return SCHEDULIB_ERROR_NULL_POINTER;
+	}
+	let read = |values: *const i64| -> Option<Vec<Time>> {
+		if n == 0 {
+			return Some(Vec::new());
+		}
+		std::slice::from_raw_parts(values, n).iter().map(|&v| Time::try_from(v).ok()).collect()
+	};
+	let (Some(p), Some(r), Some(d)) = (read(p), read(r), read(d)) else {
+		return SCHEDULIB_ERROR_TIME_OUT_OF_RANGE;
+	};
+	if p.iter().any(|&t| t < 0) {
+		return SCHEDULIB_ERROR_NEGATIVE_PROCESSING_TIME;
+	}
+	let Ok(schedule) = catch_unwind(AssertUnwindSafe(|| solver(p, r, d))) else {
+		return SCHEDULIB_ERROR_INTERNAL;
+	};
+	let runs: Option<Vec<CRun>> = schedule.schedule.iter().map(|run| Some(CRun{
+		start: i64::try_from(run.time).ok()?,
+		job: run.job,
+		duration: i64::try_from(run.duration).ok()?,
+	})).collect();
+	let Some(runs) = runs else {
+		return SCHEDULIB_ERROR_TIME_OUT_OF_RANGE;
+	};
+	let capacity = *out_len;
+	*out_len = runs.len();
+	if out_runs.is_null() {
+		return SCHEDULIB_OK;
+	}
+	if capacity < runs.len() {
+		return SCHEDULIB_ERROR_BUFFER_TOO_SMALL;
+	}
+	std::ptr::copy_nonoverlapping(runs.as_ptr(), out_runs, runs.len());
+	SCHEDULIB_OK
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::ptr::{null, null_mut};
+
+	/// Calls `f` like a C caller: queries the number of runs, then solves into a buffer of that size.
+	fn call(
+		f: unsafe extern "C" fn(usize, *const i64, *const i64, *const i64, *mut CRun, *mut usize) -> i32,
+		p: &[i64],
+		r: &[i64],
+		d: &[i64]
+	) -> Result<Vec<CRun>, i32>
+	{
+		let mut len = 0;
+		let code = unsafe { f(p.len(), p.as_ptr(), r.as_ptr(), d.as_ptr(), null_mut(), &mut len) };
+		if code != SCHEDULIB_OK {
+			return Err(code);
+		}
+		let mut runs = vec![CRun{ start: 0, job: 0, duration: 0 }; len];
+		let code = unsafe { f(p.len(), p.as_ptr(), r.as_ptr(), d.as_ptr(), runs.as_mut_ptr(), &mut len) };
+		if code != SCHEDULIB_OK {
+			return Err(code);
+		}
+		assert_eq!(len, runs.len());
+		Ok(runs)
+	}
+
+	#[test]
+	fn test_carlier() {
+		let (p, r, d) = ([3, 2, 4], [0, 1, 1], [5, 4, 9]);
+		let runs = call(schedulib_carlier, &p, &r, &d).unwrap();
+		let expected = carlier(&[3, 2, 4], &[0, 1, 1], &[5, 4, 9]);
+		let expected: Vec<CRun> = expected.schedule.iter()
+			.map(|run| CRun{ start: run.time as i64, job: run.job, duration: run.duration as i64 })
+			.collect();
+		assert_eq!(runs, expected);
+		assert_eq!(call(schedulib_carlier, &[], &[], &[]), Ok(vec![]));
+	}
+
+	#[test]
+	fn test_edd_preemptive() {
+		// job 1 preempts job 0
+		let runs = call(schedulib_edd_preemptive, &[4, 1], &[0, 1], &[10, 2]).unwrap();
+		assert_eq!(runs, vec![
+			CRun{ start: 0, job: 0, duration: 1 },
+			CRun{ start: 1, job: 1, duration: 1 },
+			CRun{ start: 2, job: 0, duration: 3 },
+		]);
+		// a buffer for fewer runs is rejected, reporting the required size
+		let mut runs = vec![CRun{ start: 0, job: 0, duration: 0 }; 2];
+		let mut len = runs.len();
+		let code = unsafe { schedulib_edd_preemptive(2, [4, 1].as_ptr(), [0, 1].as_ptr(), [10, 2].as_ptr(), runs.as_mut_ptr(), &mut len) };
+		assert_eq!((code, len), (SCHEDULIB_ERROR_BUFFER_TOO_SMALL, 3));
+	}
+
+	#[test]
+	fn test_errors() {
+		assert_eq!(call(schedulib_carlier, &[1, -1], &[0, 0], &[0, 0]), Err(SCHEDULIB_ERROR_NEGATIVE_PROCESSING_TIME));
+		let mut len = 0;
+		let code = unsafe { schedulib_carlier(1, null(), null(), null(), null_mut(), &mut len) };
+		assert_eq!(code, SCHEDULIB_ERROR_NULL_POINTER);
+		let code = unsafe { schedulib_carlier(0, null(), null(), null(), null_mut(), null_mut()) };
+		assert_eq!(code, SCHEDULIB_ERROR_NULL_POINTER);
+	}
+}
@@ -0,0 +1,52 @@
+/// 64-bit FNV-1a hash over little-endian encodings of integers, for fingerprints that are stable
+/// across platforms and Rust versions (unlike `std::collections::hash_map::DefaultHasher`).
+///
+/// Every value is written as the 8 bytes of its little-endian two's complement representation,
+/// so the result does not depend on the width of `isize` and `usize` for values that fit into 64 bits.
+#[derive(Debug, Clone)]
+pub(crate) struct Fingerprint {
+	state: u64,
+}
+
+impl Fingerprint {
+	const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+	const PRIME: u64 = 0x100000001b3;
+
+	pub(crate) fn new() -> Fingerprint {
+		Fingerprint{ state: Fingerprint::OFFSET_BASIS }
+	}
+
+	pub(crate) fn write_i64(&mut self, x: i64) {
+		for byte in x.to_le_bytes() {
+			self.state ^= byte as u64;
+			self.state = self.state.wrapping_mul(Fingerprint::PRIME);
+		}
+	}
+
+	/// Writes the length of a sequence and then its elements.
+	pub(crate) fn write_seq<I: ExactSizeIterator<Item = i64>>(&mut self, values: I) {
+		self.write_i64(values.len() as i64);
+		for x in values {
+			self.write_i64(x);
+		}
+	}
+
+	pub(crate) fn finish(&self) -> u64 {
+		self.state
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_known_values() {
+		// FNV-1a of the bytes 01 00 00 00 00 00 00 00
+		let mut f = Fingerprint::new();
+		f.write_i64(1);
+		assert_eq!(f.finish(), 0x89cd31291d2aefa4);
+		assert_eq!(Fingerprint::new().finish(), 0xcbf29ce484222325);
+	}
+}
@@ -0,0 +1,116 @@
+use crate::Time;
+
+
+/// Minimum cost flow by successive shortest paths, for small networks built by the algorithms of this crate.
+/// Shortest paths are computed with Bellman-Ford, so edge costs may be negative
+/// as long as the network has no cycle of negative cost.
+#[derive(Debug, Clone)]
+pub(crate) struct MinCostFlow {
+	edges: Vec<Edge>,
+	/// The outgoing edges of every node, including reverse edges
+	adjacent: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+	to: usize,
+	capacity: Time,
+	cost: Time,
+	flow: Time,
+}
+
+impl MinCostFlow {
+	pub(crate) fn new(num_nodes: usize) -> MinCostFlow {
+		MinCostFlow{ edges: Vec::new(), adjacent: vec![Vec::new(); num_nodes] }
+	}
+
+	/// Adds an edge and returns its index for `flow`.
+	pub(crate) fn add_edge(&mut self, from: usize, to: usize, capacity: Time, cost: Time) -> usize {
+		let index = self.edges.len();
+		// the reverse edge is stored at index ^ 1
+		self.edges.push(Edge{ to, capacity, cost, flow: 0 });
+		self.edges.push(Edge{ to: from, capacity: 0, cost: -cost, flow: 0 });
+		self.adjacent[from].push(index);
+		self.adjacent[to].push(index + 1);
+		index
+	}
+
+	/// Returns the flow on an edge returned by `add_edge`.
+	pub(crate) fn flow(&self, edge: usize) -> Time {
+		self.edges[edge].flow
+	}
+
+	/// Sends up to `max_flow` units from `source` to `sink` at minimum cost,
+	/// augmenting along cheapest paths as long as there are any.
+	///
+	/// # Returns
+	/// The amount of flow sent and its total cost.
+	pub(crate) fn run(&mut self, source: usize, sink: usize, max_flow: Time) -> (Time, Time) {
+		let n = self.adjacent.len();
+		let mut total_flow = 0;
+		let mut total_cost = 0;
+		while total_flow < max_flow {
+			// Bellman-Ford in the residual network, remembering the edge to each node
+			let mut distance = vec![Time::MAX; n];
+			let mut via: Vec<Option<usize>> = vec![None; n];
+			distance[source] = 0;
+			for _ in 0..n {
+				let mut changed = false;
+				for node in 0..n {
+					if distance[node] == Time::MAX {
+						continue;
+					}
+					for &e in self.adjacent[node].iter() {
+						let edge = &self.edges[e];
+						if edge.flow < edge.capacity && distance[node] + edge.cost < distance[edge.to] {
+							distance[edge.to] = distance[node] + edge.cost;
+							via[edge.to] = Some(e);
+							changed = true;
+						}
+					}
+				}
+				if !changed {
+					break;
+				}
+			}
+			if distance[sink] == Time::MAX {
+				break;
+			}
+			let mut amount = max_flow - total_flow;
+			let mut node = sink;
+			while let Some(e) = via[node] {
+				amount = amount.min(self.edges[e].capacity - self.edges[e].flow);
+				node = self.edges[e ^ 1].to;
+			}
+			let mut node = sink;
+			while let Some(e) = via[node] {
+				self.edges[e].flow += amount;
+				self.edges[e ^ 1].flow -= amount;
+				node = self.edges[e ^ 1].to;
+			}
+			total_flow += amount;
+			total_cost += amount * distance[sink];
+		}
+		(total_flow, total_cost)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_negative_costs() {
+		// two paths from 0 to 3, the cheaper one has a negative edge but only capacity 1
+		let mut network = MinCostFlow::new(4);
+		let cheap = network.add_edge(0, 1, 1, -5);
+		network.add_edge(1, 3, 2, 1);
+		let expensive = network.add_edge(0, 2, 3, 2);
+		network.add_edge(2, 3, 3, 0);
+		assert_eq!(network.run(0, 3, 3), (3, -4 + 2 + 2));
+		assert_eq!((network.flow(cheap), network.flow(expensive)), (1, 2));
+		let mut network = MinCostFlow::new(2);
+		assert_eq!(network.run(0, 1, 5), (0, 0));
+	}
+}
@@ -1,6 +1,13 @@
-use crate::{Time, Job};
+use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, JobRun, ScheduleError};
+use std::cmp::max;
+use std::fmt;
+use crate::schedule::assert_valid_ptimes;
+use crate::single_machine::SearchLimits;
 
 
+/// Maximum number of jobs accepted by `exact_bitmask`.
+pub const MAX_BITMASK_JOBS: usize = 16;
+
 /// Optimally schedules jobs in a 2-machine flow shop to minimize makespan.
 /// This function uses Johnson's algorithm and takes O(n log n) time.
 /// See Johnson: "Optimal two- and three-stage production schedules with setup times included", 1954.
@@ -11,8 +18,12 @@ use crate::{Time, Job};
 /// # Returns
 /// A permutation of the jobs (i.e. of 0..n-1) such that scheduling the jobs in this order on both machines
 /// is an optimal solution ot the given F2||C_max instance.
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or a processing time is negative.
 pub fn johnson(ptimes: &[Vec<Time>]) -> Vec<Job> {
 	assert!(ptimes.len() == 2, "Instance must have exactly 2 machines");
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
 	let n = ptimes[0].len();
 	let mut result : Vec<Job> = (0..n).collect();
 	let num1 = partition_in_place(&mut result, 
@@ -24,31 +35,193 @@ pub fn johnson(ptimes: &[Vec<Time>]) -> Vec<Job> {
 }
 
 
+
+/// Optimally schedules jobs in a 2-machine flow shop with time lags to minimize makespan,
+/// i.e. solves F2|l_j,prmu|C_max, where job j can start on machine 2 at least `lags[j]` time units
+/// after its completion on machine 1.
+/// Uses Mitten's extension of Johnson's algorithm, which applies Johnson's rule to the times
+/// `p_1j + l_j` and `p_2j + l_j`, and takes O(n log n) time.
+/// Use `MultiMachineSchedule::from_order_ptimes_with_lags` to evaluate the resulting order.
+/// See Mitten: "Sequencing n jobs on two machines with arbitrary time lags", 1959.
+///
+/// # Arguments
+/// * ptimes: The processing times, where ptimes[i][j] is the time taken by machine i for job j
+/// * lags: lags[j] is the minimum delay of job j between the machines
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1) such that scheduling the jobs in this order on both machines
+/// is an optimal permutation schedule.
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or a processing time or lag is negative.
+pub fn johnson_with_lags(ptimes: &[Vec<Time>], lags: &[Time]) -> Vec<Job> {
+	assert!(ptimes.len() == 2, "Instance must have exactly 2 machines");
+	assert!(lags.iter().all(|&l| l >= 0), "Lags must not be negative");
+	let with_lags: Vec<Vec<Time>> = ptimes.iter().map(|p| {
+		p.iter().zip(lags.iter()).map(|(&p, &l)| p + l).collect()
+	}).collect();
+	johnson(&with_lags)
+}
+
+
+
+/// A job order for a permutation flow shop together with its schedule and makespan.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FlowShopSolution {
+	/// The order in which every machine processes the jobs
+	pub order: Vec<Job>,
+	/// The schedule resulting from `order`
+	pub schedule: MultiMachineSchedule,
+	/// The makespan of `schedule`
+	pub makespan: Time,
+}
+
+impl FlowShopSolution {
+	/// Evaluates the given job order with `MultiMachineSchedule::from_order_ptimes`.
+	///
+	/// # Panics
+	/// If a processing time is negative.
+	pub fn from_order(order: Vec<Job>, ptimes: &[Vec<Time>]) -> FlowShopSolution {
+		let schedule = MultiMachineSchedule::from_order_ptimes(&order, ptimes);
+		let makespan = schedule.makespan();
+		FlowShopSolution{ order, schedule, makespan }
+	}
+
+	/// Returns by how much the makespan exceeds the given lower bound of the optimal makespan.
+	pub fn gap_to(&self, lower_bound: Time) -> Time {
+		self.makespan - lower_bound
+	}
+}
+
+impl fmt::Display for FlowShopSolution {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.schedule.fmt(f)
+	}
+}
+
+
+/// Like `johnson`, but returns the resulting schedule and makespan along with the job order.
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or a processing time is negative.
+pub fn solve_johnson(ptimes: &[Vec<Time>]) -> FlowShopSolution {
+	FlowShopSolution::from_order(johnson(ptimes), ptimes)
+}
+
+
+/// Like `dannenbring`, but returns the resulting schedule and makespan along with the job order.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn solve_dannenbring(ptimes: &[Vec<Time>]) -> FlowShopSolution {
+	FlowShopSolution::from_order(dannenbring(ptimes), ptimes)
+}
+
+/// Returns whether the permutation flow shop instance is known to be solved optimally by Johnson's rule,
+/// which holds for at most 2 machines, and for 3 machines if the middle machine is dominated,
+/// i.e. if no job takes longer on machine 2 than any job on machine 1 or than any job on machine 3.
+/// In the latter case, Johnson's rule is applied to the times `p_1j + p_2j` and `p_2j + p_3j`.
+/// F3||C_max and larger instances are NP-hard in general, so other instances need a heuristic
+/// or an exact search like `exact_bitmask`.
+/// See Johnson: "Optimal two- and three-stage production schedules with setup times included", 1954.
+///
+/// # Arguments
+/// * ptimes: The processing times, where ptimes[i][j] is the time taken by machine i for job j
+pub fn is_johnson_optimal(ptimes: &[Vec<Time>]) -> bool {
+	match ptimes.len() {
+		0..=2 => true,
+		3 => {
+			let max_middle = ptimes[1].iter().copied().max().unwrap_or(0);
+			let dominates = |p: &[Time]| p.iter().all(|&t| t >= max_middle);
+			dominates(&ptimes[0]) || dominates(&ptimes[2])
+		},
+		_ => false,
+	}
+}
+
+
+/// Returns an optimal job order if `is_johnson_optimal` holds for the instance, or None otherwise.
+///
+/// # Arguments
+/// * ptimes: The processing times, where ptimes[i][j] is the time taken by machine i for job j
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1) with minimum makespan, or None if the instance needs a heuristic.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn solve_flow_shop_exact_if_possible(ptimes: &[Vec<Time>]) -> Option<Vec<Job>> {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	if !is_johnson_optimal(ptimes) {
+		return None;
+	}
+	let n = ptimes.first().map_or(0, |p| p.len());
+	Some(match ptimes.len() {
+		0 | 1 => (0..n).collect(),
+		2 => johnson(ptimes),
+		_ => {
+			let sum = |a: &[Time], b: &[Time]| a.iter().zip(b.iter()).map(|(x, y)| x + y).collect();
+			johnson(&[sum(&ptimes[0], &ptimes[1]), sum(&ptimes[1], &ptimes[2])])
+		},
+	})
+}
+
+
+/// Lower bound on the makespan of every permutation schedule of a flow shop instance.
+/// For every pair of machines u < v, the machines in between are relaxed to time lags,
+/// the machines before u and after v to the smallest head and tail of any job,
+/// and the resulting two-machine problem is solved optimally with `johnson_with_lags`.
+/// Single machines give the bound of the smallest head, the total load and the smallest tail.
+/// The result is the largest of these bounds, which is exact for at most 2 machines.
+/// Takes O(m^2 n log n) time for n jobs and m machines.
+/// See Lageweg, Lenstra, Rinnooy Kan: "A general bounding scheme for the permutation flow-shop problem", 1978.
+///
+/// # Arguments
+/// * ptimes: The processing times, where ptimes[i][j] is the time taken by machine i for job j
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn johnson_lower_bound(ptimes: &[Vec<Time>]) -> Time {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	let m = ptimes.len();
+	let n = ptimes.first().map_or(0, |p| p.len());
+	if n == 0 {
+		return 0;
+	}
+	// the smallest total processing time of a job on the machines before u, or after v
+	let min_head = |u: usize| (0..n).map(|j| ptimes[..u].iter().map(|p| p[j]).sum::<Time>()).min().unwrap();
+	let min_tail = |v: usize| (0..n).map(|j| ptimes[v + 1..].iter().map(|p| p[j]).sum::<Time>()).min().unwrap();
+	let mut bound = 0;
+	for u in 0..m {
+		bound = max(bound, min_head(u) + ptimes[u].iter().sum::<Time>() + min_tail(u));
+		for v in u + 1..m {
+			let pair = [ptimes[u].clone(), ptimes[v].clone()];
+			let lags: Vec<Time> = (0..n).map(|j| ptimes[u + 1..v].iter().map(|p| p[j]).sum()).collect();
+			let order = johnson_with_lags(&pair, &lags);
+			let makespan = MultiMachineSchedule::from_order_ptimes_with_lags(&order, &pair, &lags).makespan();
+			bound = max(bound, min_head(u) + makespan + min_tail(v));
+		}
+	}
+	bound
+}
+
 /// Reorders a vector in place according to a predicate function,
 /// such that all items satisfying the predicate come before any other item.
 ///
 /// # Returns
 /// The number of items that satisfy the predicate
-fn partition_in_place<T, F>(container: &mut Vec<T>, mut predicate: F) -> usize
+fn partition_in_place<T, F>(container: &mut [T], mut predicate: F) -> usize
 where
 	F: FnMut(&T) -> bool,
 {
-	let mut i1 = 0;
-	let mut i2 = container.len() - 1;
-	loop {
-		while i1 < container.len() - 1 && predicate(&container[i1]) {
-			i1 += 1;
-		}
-		while i2 > 0 && !predicate(&container[i2]) {
-			i2 -= 1;
-		}
-		if i1 < i2 {
-			container.swap(i1, i2);
-		}
-		else {
-			return i1;
+	let mut num_satisfied = 0;
+	for i in 0..container.len() {
+		if predicate(&container[i]) {
+			container.swap(i, num_satisfied);
+			num_satisfied += 1;
 		}
 	}
+	num_satisfied
 }
 
 
@@ -60,13 +233,18 @@ where
 /// * ptimes: The processing times where `ptimes[i][j]` is the time needed by machine i for job j.
 ///
 /// # Returns
-/// A permutation of the jobs (i.e. of 0..n-1) such that scheduling the jobs in this order on both machines yields the proposed schedule.
+/// A permutation of the jobs (i.e. of 0..n-1) such that scheduling the jobs in this order on all machines yields the proposed schedule.
+/// With a single machine, the makespan does not depend on the order, and the jobs are returned in order 0..n-1.
+/// Without machines, the result is empty.
 pub fn dannenbring(ptimes: &[Vec<Time>]) -> Vec<Job> {
 	let m = ptimes.len(); // number of machines
 	if m == 0 {
 		return Vec::new()
 	}
 	let n = ptimes[0].len(); // number of jobs
+	if m == 1 || n <= 1 {
+		return (0..n).collect();
+	}
 	let weights1 : Vec<_> = (0..n).map(
 		|j| (0..m).map( |i| ((m-i) as isize)*ptimes[i][j] ).sum()
 	).collect();
@@ -77,10 +255,623 @@ pub fn dannenbring(ptimes: &[Vec<Time>]) -> Vec<Job> {
 }
 
 
+/// Dannenbring's heuristic for the permutation flow shop with release times F|r_j,prmu|C_max.
+/// The order of `dannenbring` ignores release times, so this function also considers
+/// the same order stably sorted by release times, and returns whichever of both orders
+/// has the smaller makespan according to `MultiMachineSchedule::from_order_ptimes_releasetimes`
+/// (the order of `dannenbring` in case of ties).
+///
+/// # Arguments
+/// * ptimes: The processing times where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * release_times: release_times[j] is the earliest time at which job j can start on machine 0.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1).
+pub fn dannenbring_releasetimes(ptimes: &[Vec<Time>], release_times: &[Time]) -> Vec<Job> {
+	let order = dannenbring(ptimes);
+	let mut by_release = order.clone();
+	by_release.sort_by_key(|&j| release_times[j]);
+	let makespan = |order: &[Job]| {
+		MultiMachineSchedule::from_order_ptimes_releasetimes(order, ptimes, release_times).makespan()
+	};
+	if makespan(&by_release) < makespan(&order) {
+		by_release
+	} else {
+		order
+	}
+}
+
+
+/// The NEH heuristic by Nawaz, Enscore and Ham for the permutation flow shop F|prmu|C_max.
+/// Jobs are considered in order of decreasing total processing time,
+/// and each job is inserted into the partial order at the position that minimizes the makespan
+/// (the earliest such position in case of ties).
+/// Takes O(n^3 m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1).
+pub fn neh(ptimes: &[Vec<Time>]) -> Vec<Job> {
+	let n = ptimes.first().map_or(0, |p| p.len());
+	neh_releasetimes(ptimes, &vec![0; n])
+}
+
+
+/// The NEH heuristic for the permutation flow shop with release times F|r_j,prmu|C_max.
+/// Works like `neh`, but evaluates the makespan of each insertion with
+/// `MultiMachineSchedule::from_order_ptimes_releasetimes`.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * release_times: release_times[j] is the earliest time at which job j can start on machine 0.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1).
+pub fn neh_releasetimes(ptimes: &[Vec<Time>], release_times: &[Time]) -> Vec<Job> {
+	let mut jobs = jobs_by_total_ptime(ptimes);
+	jobs.reverse();
+	insertion_heuristic(ptimes, release_times, &jobs, |schedule| schedule.makespan())
+}
+
+
+/// Heuristic for the two-machine flow shop with release times F2|r_j|C_max.
+/// Starts from the order of Johnson's rule, with jobs that the rule does not distinguish ordered by release time,
+/// or from this order stably sorted by release time if that has a smaller makespan.
+/// The order is then improved by swapping pairs of jobs as long as a swap reduces the makespan.
+/// Makespans are evaluated with `MultiMachineSchedule::from_order_ptimes_releasetimes`.
+/// Each round of swaps takes O(n^3) time for n jobs.
+///
+/// # Arguments
+/// * ptimes: The processing times, where ptimes[i][j] is the time taken by machine i for job j
+/// * release_times: release_times[j] is the earliest time at which job j can start on machine 0.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1).
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or a processing time is negative.
+pub fn f2_release_heuristic(ptimes: &[Vec<Time>], release_times: &[Time]) -> Vec<Job> {
+	assert!(ptimes.len() == 2, "Instance must have exactly 2 machines");
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	let n = ptimes[0].len();
+	let makespan = |order: &[Job]| {
+		MultiMachineSchedule::from_order_ptimes_releasetimes(order, ptimes, release_times).makespan()
+	};
+	let mut order: Vec<Job> = (0..n).collect();
+	order.sort_by_key(|&j| {
+		let johnson_key = if ptimes[0][j] <= ptimes[1][j] { (0, ptimes[0][j]) } else { (1, -ptimes[1][j]) };
+		(johnson_key, release_times[j])
+	});
+	let mut by_release = order.clone();
+	by_release.sort_by_key(|&j| release_times[j]);
+	let mut best = makespan(&order);
+	if makespan(&by_release) < best {
+		order = by_release;
+		best = makespan(&order);
+	}
+	let mut improved = true;
+	while improved {
+		improved = false;
+		for i in 0..n {
+			for k in i + 1..n {
+				order.swap(i, k);
+				let value = makespan(&order);
+				if value < best {
+					best = value;
+					improved = true;
+				} else {
+					order.swap(i, k);
+				}
+			}
+		}
+	}
+	order
+}
+
+
+/// Solves the two-machine flow shop with release times F2|r_j|C_max by depth-first branch and bound,
+/// appending one job at a time to a partial order, starting from the order of `f2_release_heuristic`.
+/// A partial order with completion times c_1 and c_2 on the machines is pruned if it cannot improve
+/// on the best order found so far according to the larger of two lower bounds:
+/// the remaining jobs processed on machine 1 in order of release time after c_1,
+/// followed by the shortest remaining time on machine 2,
+/// and the earliest time at which any remaining job can reach machine 2 after c_2,
+/// followed by all remaining times on machine 2.
+/// Permutation schedules are optimal for two machines, so the result is optimal unless the search is stopped early.
+/// Takes exponential time in the worst case.
+///
+/// # Arguments
+/// * ptimes: The processing times, where ptimes[i][j] is the time taken by machine i for job j
+/// * release_times: release_times[j] is the earliest time at which job j can start on machine 0.
+/// * limits: `limits.max_nodes` is the maximum number of partial orders to expand.
+///
+/// # Returns
+/// The best order found, its makespan according to `MultiMachineSchedule::from_order_ptimes_releasetimes`,
+/// and whether the search was completed, i.e. whether the order is optimal.
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or a processing time is negative.
+pub fn f2_release_bnb(
+	ptimes: &[Vec<Time>],
+	release_times: &[Time],
+	limits: &SearchLimits
+) -> (Vec<Job>, Time, bool)
+{
+	let initial = f2_release_heuristic(ptimes, release_times);
+	let best_makespan = MultiMachineSchedule::from_order_ptimes_releasetimes(&initial, ptimes, release_times).makespan();
+	let mut by_release = initial.clone();
+	by_release.sort_by_key(|&j| release_times[j]);
+	let mut search = F2ReleaseSearch{
+		ptimes,
+		release_times,
+		by_release,
+		scheduled: vec![false; initial.len()],
+		prefix: Vec::with_capacity(initial.len()),
+		nodes_left: limits.max_nodes,
+		completed: true,
+		best_makespan,
+		best_order: initial.clone(),
+		candidates: initial,
+	};
+	search.visit(0, 0);
+	(search.best_order, search.best_makespan, search.completed)
+}
+
+
+/// The state of `f2_release_bnb`.
+struct F2ReleaseSearch<'a> {
+	ptimes: &'a [Vec<Time>],
+	release_times: &'a [Time],
+	/// The jobs in the order in which children are explored
+	candidates: Vec<Job>,
+	/// The jobs by release time, for the machine 1 bound
+	by_release: Vec<Job>,
+	scheduled: Vec<bool>,
+	prefix: Vec<Job>,
+	nodes_left: usize,
+	completed: bool,
+	best_makespan: Time,
+	best_order: Vec<Job>,
+}
+
+impl F2ReleaseSearch<'_> {
+	fn visit(&mut self, c1: Time, c2: Time) {
+		if self.prefix.len() == self.candidates.len() {
+			if c2 < self.best_makespan {
+				self.best_makespan = c2;
+				self.best_order = self.prefix.clone();
+			}
+			return;
+		}
+		if self.lower_bound(c1, c2) >= self.best_makespan {
+			return;
+		}
+		if self.nodes_left == 0 {
+			self.completed = false;
+			return;
+		}
+		self.nodes_left -= 1;
+		for i in 0..self.candidates.len() {
+			let job = self.candidates[i];
+			if self.scheduled[job] {
+				continue;
+			}
+			let next_c1 = max(c1, self.release_times[job]) + self.ptimes[0][job];
+			let next_c2 = max(c2, next_c1) + self.ptimes[1][job];
+			self.scheduled[job] = true;
+			self.prefix.push(job);
+			self.visit(next_c1, next_c2);
+			self.prefix.pop();
+			self.scheduled[job] = false;
+		}
+	}
+
+	/// Lower bound on the makespan of every completion of the current partial order.
+	fn lower_bound(&self, c1: Time, c2: Time) -> Time {
+		let mut machine1 = c1;
+		let mut min_tail = Time::MAX;
+		let mut earliest_head = Time::MAX;
+		let mut total_tail = 0;
+		for &job in self.by_release.iter().filter(|&&job| !self.scheduled[job]) {
+			machine1 = max(machine1, self.release_times[job]) + self.ptimes[0][job];
+			min_tail = min_tail.min(self.ptimes[1][job]);
+			earliest_head = earliest_head.min(max(c1, self.release_times[job]) + self.ptimes[0][job]);
+			total_tail += self.ptimes[1][job];
+		}
+		max(machine1 + min_tail, max(c2, earliest_head) + total_tail)
+	}
+}
+
+
+/// Heuristic for the permutation flow shop minimizing the total completion time F|prmu|sum C_j.
+/// This is the NEH insertion heuristic with the total completion time as insertion criterion,
+/// where jobs are considered in order of increasing total processing time.
+/// Takes O(n^3 m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1).
+pub fn total_completion_heuristic(ptimes: &[Vec<Time>]) -> Vec<Job> {
+	let jobs = jobs_by_total_ptime(ptimes);
+	insertion_heuristic(ptimes, &vec![0; jobs.len()], &jobs, |schedule| schedule.total_completion_time())
+}
+
+
+/// Returns the maximum lateness of the permutation flow shop schedule in the given order,
+/// i.e. of `MultiMachineSchedule::from_order_ptimes(order, ptimes)`, without building the schedule.
+/// The lateness of a job is its completion time on the last machine minus its due time.
+/// Runs in O(n m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * order: Order in which jobs are processed by each machine
+/// * due_times: due_times[j] is the due time of job j.
+///
+/// # Panics
+/// If a processing time is negative or `order` is empty.
+pub fn max_lateness_of_order(ptimes: &[Vec<Time>], order: &[Job], due_times: &[Time]) -> Time {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	// completion[i] is the completion time of the previous job on machine i
+	let mut completion = vec![0; ptimes.len()];
+	order.iter().map(|&job| {
+		let mut time = 0;
+		for (c, p) in completion.iter_mut().zip(ptimes.iter()) {
+			time = max(time, *c) + p[job];
+			*c = time;
+		}
+		time - due_times[job]
+	}).max().expect("Order is empty")
+}
+
+
+/// Heuristic for the permutation flow shop with due times F|prmu|L_max.
+/// Works like `neh`, but inserts the jobs in order of due time (ties by job id)
+/// at the position minimizing the maximum lateness.
+/// For a single machine, the result is optimal.
+/// Takes O(n^3 m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * due_times: due_times[j] is the due time of job j.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1).
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn edd_neh(ptimes: &[Vec<Time>], due_times: &[Time]) -> Vec<Job> {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	let mut jobs: Vec<Job> = (0..due_times.len()).collect();
+	jobs.sort_by_key(|&j| due_times[j]);
+	insertion_heuristic(ptimes, &vec![0; jobs.len()], &jobs, |schedule| schedule.max_lateness(due_times))
+}
+
+
+/// Returns the jobs sorted by increasing processing time on all machines, ties by job id.
+fn jobs_by_total_ptime(ptimes: &[Vec<Time>]) -> Vec<Job> {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let mut jobs: Vec<Job> = (0..n).collect();
+	jobs.sort_by_key(|&j| ptimes.iter().map(|p| p[j]).sum::<Time>());
+	jobs
+}
+
+
+/// Builds an order by inserting the given jobs one after another
+/// at the position minimizing the given objective.
+fn insertion_heuristic<F>(ptimes: &[Vec<Time>], release_times: &[Time], jobs: &[Job], objective: F) -> Vec<Job>
+where F: Fn(&MultiMachineSchedule) -> Time
+{
+	let mut order: Vec<Job> = Vec::with_capacity(jobs.len());
+	for &job in jobs {
+		let mut best: Option<(usize, Time)> = None;
+		for position in 0..=order.len() {
+			order.insert(position, job);
+			let value = objective(&MultiMachineSchedule::from_order_ptimes_releasetimes(&order, ptimes, release_times));
+			if best.is_none_or(|(_, v)| value < v) {
+				best = Some((position, value));
+			}
+			order.remove(position);
+		}
+		order.insert(best.unwrap().0, job);
+	}
+	order
+}
+
+
+
+/// A setup of a machine between jobs of two different families.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SetupRun {
+	/// Start time of the setup
+	pub time: Time,
+	/// Family of the job processed before the setup
+	pub from_family: usize,
+	/// Family of the job processed after the setup
+	pub to_family: usize,
+	/// Duration of the setup
+	pub duration: Time,
+}
+
+
+/// Flow shop schedule for the given job order with sequence-dependent family setups.
+/// Whenever two consecutive jobs on a machine belong to different families,
+/// the machine is set up between them. The setup starts as soon as the machine has completed
+/// the previous job, i.e. possibly before the next job is completed on the previous machine.
+/// The machines are set up for the family of the first job at the start, so there is no initial setup.
+/// With all setup times 0, the schedule is the same as from `MultiMachineSchedule::from_order_ptimes`.
+///
+/// # Arguments
+/// * order: Order in which jobs are processed by each machine
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * family: family[j] is the family of job j.
+/// * setup: setup[i][f][g] is the time needed to set up machine i for family g after processing family f.
+///
+/// # Returns
+/// The schedule and, for each machine, the setups in order of time.
+///
+/// # Panics
+/// If a processing time or setup time is negative, or `setup` does not have one entry per machine.
+pub fn group_schedule(
+	order: &[Job],
+	ptimes: &[Vec<Time>],
+	family: &[usize],
+	setup: &[Vec<Vec<Time>>]
+) -> (MultiMachineSchedule, Vec<Vec<SetupRun>>)
+{
+	assert_eq!(setup.len(), ptimes.len(), "There must be setup times for every machine");
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	assert!(setup.iter().flatten().flatten().all(|&s| s >= 0), "Setup times must not be negative");
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let mut ready_times = vec![0; n]; // time when each job is ready to be processed further
+	let mut schedules = Vec::with_capacity(ptimes.len());
+	let mut setups = Vec::with_capacity(ptimes.len());
+	for (machine_ptimes, machine_setup) in ptimes.iter().zip(setup.iter()) {
+		let mut time = 0;
+		let mut schedule = MachineSchedule{ schedule: Vec::with_capacity(n) };
+		let mut machine_setups = Vec::new();
+		let mut previous_family: Option<usize> = None;
+		for &j in order {
+			match previous_family {
+				Some(f) if f != family[j] => {
+					let duration = machine_setup[f][family[j]];
+					machine_setups.push(SetupRun{ time, from_family: f, to_family: family[j], duration });
+					time += duration;
+				},
+				_ => {},
+			}
+			previous_family = Some(family[j]);
+			let start = max(time, ready_times[j]);
+			schedule.schedule.push(JobRun{
+				time: start,
+				job: j,
+				duration: machine_ptimes[j],
+			});
+			time = start + machine_ptimes[j];
+			ready_times[j] = time;
+		}
+		schedules.push(schedule);
+		setups.push(machine_setups);
+	}
+	(MultiMachineSchedule{ machine_schedules: schedules }, setups)
+}
+
+
+/// Two-level heuristic for the permutation flow shop with sequence-dependent family setups
+/// F|prmu,s_fg|C_max, where the jobs of each family are processed consecutively.
+/// Within each family, the jobs are ordered by `johnson` for 2 machines and by `neh` otherwise.
+/// The families are then ordered by a procedure similar to the heuristic by Campbell, Dudek and Smith (CDS):
+/// Each family gets the total processing time of its jobs on each machine plus the smallest setup
+/// into the family on that machine. For every k in 1..m, Johnson's algorithm is applied to the
+/// 2-machine instance of the aggregated times of the first k and the last k machines,
+/// and the resulting order with the smallest makespan according to `group_schedule` is chosen.
+/// With a single family, the result is the same as `johnson` or `neh`, respectively.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * family: family[j] is the family of job j.
+/// * setup: setup[i][f][g] is the time needed to set up machine i for family g after processing family f.
+///
+/// # Returns
+/// A permutation of the jobs (i.e. of 0..n-1) in which the jobs of each family are consecutive.
+/// Without machines, the result is empty.
+///
+/// # Panics
+/// If `setup` does not have one entry per machine.
+pub fn group_scheduling(ptimes: &[Vec<Time>], family: &[usize], setup: &[Vec<Vec<Time>>]) -> Vec<Job> {
+	assert_eq!(setup.len(), ptimes.len(), "There must be setup times for every machine");
+	let m = ptimes.len();
+	if m == 0 {
+		return Vec::new();
+	}
+	let num_families = family.iter().map(|&f| f + 1).max().unwrap_or(0);
+	// order the jobs within each family
+	let family_orders: Vec<Vec<Job>> = (0..num_families).map(|f| {
+		let jobs: Vec<Job> = (0..family.len()).filter(|&j| family[j] == f).collect();
+		let sub_ptimes: Vec<Vec<Time>> = ptimes.iter()
+			.map(|p| jobs.iter().map(|&j| p[j]).collect())
+			.collect();
+		let sub_order = if m == 2 { johnson(&sub_ptimes) } else { neh(&sub_ptimes) };
+		sub_order.into_iter().map(|k| jobs[k]).collect()
+	}).collect();
+	let families: Vec<usize> = (0..num_families).filter(|&f| !family_orders[f].is_empty()).collect();
+	// aggregated times of the families on each machine
+	let aggregated: Vec<Vec<Time>> = (0..m).map(|i| families.iter().map(|&f| {
+		let min_setup = families.iter()
+			.filter(|&&g| g != f)
+			.map(|&g| setup[i][g][f])
+			.min()
+			.unwrap_or(0);
+		family_orders[f].iter().map(|&j| ptimes[i][j]).sum::<Time>() + min_setup
+	}).collect()).collect();
+	let mut best: Option<(Vec<Job>, Time)> = None;
+	for k in 1..=max(m, 2) - 1 {
+		let first: Vec<Time> = (0..families.len()).map(|f| (0..k).map(|i| aggregated[i][f]).sum()).collect();
+		let last: Vec<Time> = (0..families.len()).map(|f| (m - k..m).map(|i| aggregated[i][f]).sum()).collect();
+		let order: Vec<Job> = johnson(&[first, last]).into_iter()
+			.flat_map(|f| family_orders[families[f]].iter().copied())
+			.collect();
+		let makespan = group_schedule(&order, ptimes, family, setup).0.makespan();
+		if best.as_ref().is_none_or(|(_, v)| makespan < *v) {
+			best = Some((order, makespan));
+		}
+	}
+	best.map_or_else(Vec::new, |(order, _)| order)
+}
+
+
+/// Solves the permutation flow shop problem F|prmu|C_max optimally
+/// by dynamic programming over the sets of jobs that are scheduled first.
+/// For each set, all non-dominated vectors of machine completion times are kept,
+/// where a vector is dominated if another one is at most as large on every machine.
+/// Takes O(2^n n m K) time and O(2^n m K) memory for n jobs, m machines
+/// and at most K non-dominated vectors per set, so it is only suitable for small instances.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// An optimal job order and its makespan,
+/// or an error if there are more than `MAX_BITMASK_JOBS` jobs.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn exact_bitmask(ptimes: &[Vec<Time>]) -> Result<(Vec<Job>, Time), ScheduleError> {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	let m = ptimes.len();
+	let n = ptimes.first().map_or(0, |p| p.len());
+	if n > MAX_BITMASK_JOBS {
+		return Err(ScheduleError::TooManyJobs{ num_jobs: n, max_jobs: MAX_BITMASK_JOBS });
+	}
+	if m == 0 {
+		return Ok(((0..n).collect(), 0));
+	}
+	/// A non-dominated frontier of a set of jobs, with the job scheduled last
+	/// and the index of the frontier of the remaining jobs it was reached from.
+	struct Frontier {
+		completion: Vec<Time>,
+		last: Option<(Job, usize)>,
+	}
+	let full = (1usize << n) - 1;
+	let mut frontiers: Vec<Vec<Frontier>> = (0..=full).map(|_| Vec::new()).collect();
+	frontiers[0].push(Frontier{ completion: vec![0; m], last: None });
+	// every set is reached from smaller sets only
+	for set in 0..full {
+		for (index, frontier) in std::mem::take(&mut frontiers[set]).into_iter().enumerate() {
+			for job in (0..n).filter(|&j| set & (1 << j) == 0) {
+				let mut time = 0;
+				let completion: Vec<Time> = frontier.completion.iter().zip(ptimes.iter()).map(|(&c, p)| {
+					time = max(time, c) + p[job];
+					time
+				}).collect();
+				let next = &mut frontiers[set | (1 << job)];
+				let dominates = |a: &[Time], b: &[Time]| a.iter().zip(b.iter()).all(|(x, y)| x <= y);
+				if next.iter().any(|f| dominates(&f.completion, &completion)) {
+					continue;
+				}
+				next.retain(|f| !dominates(&completion, &f.completion));
+				next.push(Frontier{ completion, last: Some((job, index)) });
+			}
+			// keep the frontier for the reconstruction
+			frontiers[set].push(frontier);
+		}
+	}
+	let (mut index, makespan) = frontiers[full].iter()
+		.enumerate()
+		.map(|(index, f)| (index, f.completion[m - 1]))
+		.min_by_key(|&(_, c)| c)
+		.unwrap();
+	let mut order = Vec::with_capacity(n);
+	let mut set = full;
+	while let Some((job, previous)) = frontiers[set][index].last {
+		order.push(job);
+		set &= !(1 << job);
+		index = previous;
+	}
+	order.reverse();
+	Ok((order, makespan))
+}
+
+
+/// Returns the reverse of a flow shop instance, in which the jobs visit the machines in reverse order.
+/// Processing a job order on the original instance takes as long as processing the reversed order
+/// on the reversed instance, i.e. for every `order`,
+/// `MultiMachineSchedule::from_order_ptimes(order, ptimes).makespan()` equals the makespan of
+/// `MultiMachineSchedule::from_order_ptimes(reversed_order, &reverse_instance(ptimes))`.
+/// In particular, both instances have the same optimal makespan,
+/// and a heuristic can be applied to both to keep the better result.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// The processing times of the reversed instance, where `result[i]` are the processing times on machine `m-1-i`.
+pub fn reverse_instance(ptimes: &[Vec<Time>]) -> Vec<Vec<Time>> {
+	ptimes.iter().rev().cloned().collect()
+}
+
+
+/// Translates a job order of a reduced instance back to the job ids of the original instance.
+///
+/// # Arguments
+/// * `order`: A job order of the reduced instance
+/// * `mapping`: `mapping[j]` is the original id of job j of the reduced instance,
+///   as returned by `filter_jobs` or `remove_zero_jobs`.
+///
+/// # Returns
+/// The order with every job replaced by its original id.
+pub fn apply_mapping(order: &[Job], mapping: &[Job]) -> Vec<Job> {
+	order.iter().map(|&j| mapping[j]).collect()
+}
+
+
+/// Reduces a flow shop instance to the jobs for which `keep` returns true, e.g. to remove ineligible jobs.
+/// The remaining jobs are renumbered from 0 in their original order.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * keep: Whether a job (by its original id) stays in the instance
+///
+/// # Returns
+/// The processing times of the reduced instance and the mapping from its job ids to the original ones,
+/// see `apply_mapping`.
+pub fn filter_jobs<F>(ptimes: &[Vec<Time>], keep: F) -> (Vec<Vec<Time>>, Vec<Job>)
+where
+	F: Fn(Job) -> bool
+{
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let mapping: Vec<Job> = (0..n).filter(|&j| keep(j)).collect();
+	let reduced = ptimes.iter().map(|p| mapping.iter().map(|&j| p[j]).collect()).collect();
+	(reduced, mapping)
+}
+
+
+/// Removes the jobs that have processing time 0 on every machine.
+/// They do not change the makespan wherever they are inserted into an order,
+/// so they can be appended to an order computed for the reduced instance.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// The processing times of the reduced instance and the mapping from its job ids to the original ones,
+/// see `apply_mapping`.
+pub fn remove_zero_jobs(ptimes: &[Vec<Time>]) -> (Vec<Vec<Time>>, Vec<Job>) {
+	filter_jobs(ptimes, |j| ptimes.iter().any(|p| p[j] != 0))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::MultiMachineSchedule;
+	use crate::reference::{brute_force_permutation_flowshop, brute_force_permutation_flowshop_with_transport, for_each_permutation};
+	use proptest::prelude::*;
+	use proptest::collection;
+	use crate::testing::{assert_permutation, flow_shop_ptimes};
 
 	fn example_1() -> Vec<Vec<Time>> {
 		vec![vec![3, 2, 1], vec![4, 1, 5]]
@@ -93,6 +884,15 @@ mod tests {
 		assert_eq!(result, expected);
 	}
 
+	#[test]
+	fn test_johnson_zero_duration() {
+		let ptimes = vec![vec![0, 3, 2], vec![4, 0, 1]];
+		let solution = solve_johnson(&ptimes);
+		assert_eq!(solution.order, vec![0, 2, 1]);
+		let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+		assert_eq!(solution.makespan, optimum);
+	}
+
 	#[test]
 	fn test_partition_in_place() {
 		let mut container = vec![3, 4, 7, 1, 0, 2, 0, 4];
@@ -103,6 +903,37 @@ mod tests {
 		assert_eq!(container, vec![0, 0, 2, 4, 4, 1, 3, 7]);
 	}
 
+	proptest! {
+		#[test]
+		fn test_partition_in_place_counts(mut container in collection::vec(0..10i32, 0..10)) {
+			let num_even = container.iter().filter(|&x| x % 2 == 0).count();
+			let k = partition_in_place(&mut container, |x| x % 2 == 0);
+			prop_assert_eq!(k, num_even);
+			prop_assert!(container[..k].iter().all(|x| x % 2 == 0));
+			prop_assert!(container[k..].iter().all(|x| x % 2 != 0));
+		}
+
+		#[test]
+		fn test_transport_shifts_makespan(
+			(ptimes, transport) in (1..=4usize).prop_flat_map(|m| (
+				collection::vec(collection::vec(1..20 as Time, 5), m),
+				collection::vec(0..20 as Time, m - 1)
+			))
+		) {
+			// every critical path passes each transport stage exactly once,
+			// so transport times do not change which order is best
+			let total_transport: Time = transport.iter().sum();
+			let order = dannenbring(&ptimes);
+			let without = MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan();
+			let with = MultiMachineSchedule::from_order_ptimes_with_transport(&order, &ptimes, &transport).makespan();
+			prop_assert_eq!(with, without + total_transport);
+			let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+			let (best_order, optimum_with) = brute_force_permutation_flowshop_with_transport(&ptimes, &transport).unwrap();
+			prop_assert_eq!(optimum_with, optimum + total_transport);
+			prop_assert_eq!(MultiMachineSchedule::from_order_ptimes(&best_order, &ptimes).makespan(), optimum);
+		}
+	}
+
 	fn example_2() -> Vec<Vec<Time>> {
 		vec![
 			vec![3, 4, 10],
@@ -110,15 +941,440 @@ mod tests {
 			vec![7, 9, 13],
 			vec![10, 12, 2],
 		]
-		// the optimal flow shop schedule is given by the permutation 0, 3, 2, 1
+		// the optimal makespan is 40 (see test_dannenbring_example_2_brute_force)
+	}
+
+	#[test]
+	fn test_filter_jobs_mapping() {
+		let ptimes = vec![
+			vec![3, 0, 4, 0],
+			vec![2, 0, 1, 5],
+		];
+		let (reduced, mapping) = filter_jobs(&ptimes, |j| j != 0);
+		assert_eq!(reduced, vec![vec![0, 4, 0], vec![0, 1, 5]]);
+		assert_eq!(mapping, vec![1, 2, 3]);
+		assert_eq!(apply_mapping(&[2, 0, 1], &mapping), vec![3, 1, 2]);
+	}
+
+	#[test]
+	fn test_remove_zero_jobs() {
+		let ptimes = vec![
+			vec![3, 0, 4, 0, 6],
+			vec![2, 0, 1, 5, 3],
+		];
+		let (reduced, mapping) = remove_zero_jobs(&ptimes);
+		assert_eq!(mapping, vec![0, 2, 3, 4]);
+		let mut order = apply_mapping(&neh(&reduced), &mapping);
+		let makespan = MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan();
+		assert_eq!(makespan, MultiMachineSchedule::from_order_ptimes(&neh(&reduced), &reduced).makespan());
+		order.push(1);
+		assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), makespan);
+	}
+
+	#[test]
+	fn test_reverse_instance_example_2() {
+		let ptimes = example_2();
+		let reversed = reverse_instance(&ptimes);
+		assert_eq!(reversed[0], ptimes[3]);
+		for_each_permutation(3, |order| {
+			let reversed_order: Vec<Job> = order.iter().rev().copied().collect();
+			assert_eq!(
+				MultiMachineSchedule::from_order_ptimes(order, &ptimes).makespan(),
+				MultiMachineSchedule::from_order_ptimes(&reversed_order, &reversed).makespan()
+			);
+		});
+		assert_eq!(reverse_instance(&reversed), ptimes);
 	}
 
 	#[test]
 	fn test_dannenbring_example_2() {
 		let ptimes = example_2();
-		let result = dannenbring(&ptimes);
-		let schedule = MultiMachineSchedule::from_order_ptimes(&result, &ptimes);
-		assert!(schedule.makespan() <= 40);
-		assert!(schedule.makespan() >= 39); // this is the optimal solution
+		let solution = solve_dannenbring(&ptimes);
+		assert_eq!(solution.schedule, MultiMachineSchedule::from_order_ptimes(&solution.order, &ptimes));
+		assert_eq!(solution.makespan, solution.schedule.makespan());
+		assert!(solution.makespan <= 40);
+		// 40 is the optimal makespan
+		assert_eq!(solution.gap_to(40), 0);
+		assert_eq!(solution.to_string(), solution.schedule.to_string());
+	}
+
+	#[test]
+	fn test_dannenbring_degenerate() {
+		// on a single machine, every order has the same makespan
+		let ptimes = vec![vec![4, 1, 3]];
+		assert_eq!(dannenbring(&ptimes), vec![0, 1, 2]);
+		assert_eq!(solve_dannenbring(&ptimes).makespan, 8);
+		assert_eq!(dannenbring(&[vec![2], vec![5], vec![1]]), vec![0]);
+		assert!(dannenbring(&[vec![], vec![]]).is_empty());
+		assert!(dannenbring(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_dannenbring_example_2_brute_force() {
+		let ptimes = example_2();
+		let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+		assert_eq!(optimum, 40);
+		assert!(solve_dannenbring(&ptimes).makespan >= optimum);
+	}
+
+	#[test]
+	fn test_johnson_example_1_brute_force() {
+		let ptimes = example_1();
+		let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+		assert_eq!(solve_johnson(&ptimes).makespan, optimum);
+	}
+
+	#[test]
+	fn test_johnson_optimal_three_machines() {
+		// machine 2 is dominated by machine 1
+		let ptimes = vec![
+			vec![5, 8, 6, 9],
+			vec![2, 5, 1, 3],
+			vec![7, 3, 9, 2],
+		];
+		assert!(is_johnson_optimal(&ptimes));
+		let order = solve_flow_shop_exact_if_possible(&ptimes).unwrap();
+		let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+		assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), optimum);
+		assert!(johnson_lower_bound(&ptimes) <= optimum);
+		// not dominated any more
+		let mut ptimes = ptimes;
+		ptimes[1][0] = 6;
+		assert!(!is_johnson_optimal(&ptimes));
+		assert_eq!(solve_flow_shop_exact_if_possible(&ptimes), None);
+		assert_eq!(solve_flow_shop_exact_if_possible(&example_2()), None);
+		assert_eq!(solve_flow_shop_exact_if_possible(&[vec![3, 1]]), Some(vec![0, 1]));
+		assert_eq!(johnson_lower_bound(&example_1()), solve_johnson(&example_1()).makespan);
+	}
+
+	#[test]
+	fn test_johnson_with_lags() {
+		let ptimes = example_1();
+		assert_eq!(johnson_with_lags(&ptimes, &[0, 0, 0]), johnson(&ptimes));
+		// job 2 waits long before machine 2, which can process job 0 in the meantime
+		let lags = vec![0, 0, 20];
+		let order = johnson_with_lags(&ptimes, &lags);
+		assert_eq!(order, vec![0, 2, 1]);
+		let schedule = MultiMachineSchedule::from_order_ptimes_with_lags(&order, &ptimes, &lags);
+		// job 2 completes machine 1 at 4 and starts machine 2 at 24
+		assert_eq!(schedule.makespan(), 24 + 5 + 1);
+		let unaware = MultiMachineSchedule::from_order_ptimes_with_lags(&johnson(&ptimes), &ptimes, &lags);
+		assert_eq!(unaware.makespan(), 31);
 	}
-}
\ No newline at end of file
+
+	proptest! {
+		#[test]
+		fn test_johnson_with_lags_optimal(
+			(ptimes, lags) in (0..=6usize).prop_flat_map(|n| (
+				collection::vec(collection::vec(0..20 as Time, n), 2),
+				collection::vec(0..20 as Time, n),
+			))
+		) {
+			let makespan = |order: &[Job]| {
+				MultiMachineSchedule::from_order_ptimes_with_lags(order, &ptimes, &lags).makespan()
+			};
+			let mut optimum = Time::MAX;
+			for_each_permutation(lags.len(), |order| optimum = optimum.min(makespan(order)));
+			prop_assert_eq!(makespan(&johnson_with_lags(&ptimes, &lags)), optimum);
+		}
+	}
+
+	#[test]
+	fn test_late_release_changes_order() {
+		let ptimes = example_1();
+		// job 2 is the longest job on machine 2, but released late
+		let release_times = vec![0, 0, 10];
+		let makespan = |order: &[Job]| {
+			MultiMachineSchedule::from_order_ptimes_releasetimes(order, &ptimes, &release_times).makespan()
+		};
+		let mut best = (Vec::new(), Time::MAX);
+		for_each_permutation(3, |order| if makespan(order) < best.1 {
+			best = (order.to_vec(), makespan(order));
+		});
+		assert_eq!(best.1, 16);
+		assert_eq!(makespan(&johnson(&ptimes)), 21);
+		assert_eq!(makespan(&neh_releasetimes(&ptimes, &release_times)), 16);
+		assert_eq!(makespan(&dannenbring_releasetimes(&ptimes, &release_times)), 16);
+	}
+
+	#[test]
+	fn test_f2_release_example() {
+		let ptimes = example_1();
+		let release_times = vec![0, 0, 10];
+		let makespan = |order: &[Job]| {
+			MultiMachineSchedule::from_order_ptimes_releasetimes(order, &ptimes, &release_times).makespan()
+		};
+		// see test_late_release_changes_order
+		assert_eq!(makespan(&f2_release_heuristic(&ptimes, &release_times)), 16);
+		let (order, value, optimal) = f2_release_bnb(&ptimes, &release_times, &SearchLimits::default());
+		assert_eq!((makespan(&order), value, optimal), (16, 16, true));
+
+		// 8 jobs with releases spread over the day
+		let ptimes = vec![vec![4, 7, 2, 6, 3, 5, 8, 1], vec![6, 2, 5, 3, 7, 4, 1, 6]];
+		let release_times = vec![0, 3, 20, 5, 9, 14, 2, 25];
+		let makespan = |order: &[Job]| {
+			MultiMachineSchedule::from_order_ptimes_releasetimes(order, &ptimes, &release_times).makespan()
+		};
+		let mut optimum = Time::MAX;
+		for_each_permutation(8, |order| optimum = optimum.min(makespan(order)));
+		let (order, value, optimal) = f2_release_bnb(&ptimes, &release_times, &SearchLimits::default());
+		assert_permutation(&order, 8);
+		assert_eq!((makespan(&order), value, optimal), (optimum, optimum, true));
+		// without any expansions, the heuristic order is returned
+		let heuristic = f2_release_heuristic(&ptimes, &release_times);
+		let (order, value, _) = f2_release_bnb(&ptimes, &release_times, &SearchLimits{ max_nodes: 0 });
+		assert_eq!((value, &order), (makespan(&heuristic), &heuristic));
+	}
+
+	proptest! {
+		#[test]
+		fn test_johnson_lower_bound(ptimes in flow_shop_ptimes(1..=4, 1..=6, 10)) {
+			let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+			let bound = johnson_lower_bound(&ptimes);
+			prop_assert!(bound <= optimum);
+			if ptimes.len() <= 2 {
+				prop_assert_eq!(bound, optimum);
+			}
+			if let Some(order) = solve_flow_shop_exact_if_possible(&ptimes) {
+				assert_permutation(&order, ptimes[0].len());
+				prop_assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), optimum);
+			}
+		}
+
+		#[test]
+		fn test_johnson_dominated_middle(
+			ptimes in flow_shop_ptimes(3..=3, 1..=6, 10),
+			first in proptest::bool::ANY
+		) {
+			// scale machine 1 or 3 so that it dominates machine 2
+			let mut ptimes = ptimes;
+			let k = if first { 0 } else { 2 };
+			ptimes[k] = ptimes[k].iter().map(|p| p + 10).collect();
+			prop_assert!(is_johnson_optimal(&ptimes));
+			let order = solve_flow_shop_exact_if_possible(&ptimes).unwrap();
+			let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+			prop_assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), optimum);
+		}
+
+		#[test]
+		fn test_max_lateness_of_order(
+			(ptimes, due_times) in flow_shop_ptimes(1..=4, 1..=6, 10).prop_flat_map(|ptimes| {
+				let n = ptimes[0].len();
+				(Just(ptimes), collection::vec(0..40 as Time, n))
+			})
+		) {
+			let n = due_times.len();
+			let order = edd_neh(&ptimes, &due_times);
+			assert_permutation(&order, n);
+			let mut optimum = Time::MAX;
+			for_each_permutation(n, |order| {
+				let lateness = max_lateness_of_order(&ptimes, order, &due_times);
+				assert_eq!(lateness, MultiMachineSchedule::from_order_ptimes(order, &ptimes).max_lateness(&due_times));
+				optimum = optimum.min(lateness);
+			});
+			let lateness = max_lateness_of_order(&ptimes, &order, &due_times);
+			prop_assert!(lateness >= optimum);
+			if ptimes.len() == 1 {
+				prop_assert_eq!(lateness, optimum);
+			}
+		}
+
+		#[test]
+		fn test_f2_release_brute_force(
+			(ptimes, release_times) in (0..=6usize).prop_flat_map(|n| (
+				collection::vec(collection::vec(0..20 as Time, n), 2),
+				collection::vec(-5..40 as Time, n),
+			))
+		) {
+			let makespan = |order: &[Job]| {
+				MultiMachineSchedule::from_order_ptimes_releasetimes(order, &ptimes, &release_times).makespan()
+			};
+			let mut optimum = Time::MAX;
+			for_each_permutation(release_times.len(), |order| optimum = optimum.min(makespan(order)));
+			let heuristic = f2_release_heuristic(&ptimes, &release_times);
+			assert_permutation(&heuristic, release_times.len());
+			prop_assert!(makespan(&heuristic) >= optimum);
+			let (order, value, optimal) = f2_release_bnb(&ptimes, &release_times, &SearchLimits::default());
+			prop_assert!(optimal);
+			prop_assert_eq!(makespan(&order), optimum);
+			prop_assert_eq!(value, optimum);
+			// without releases, the heuristic keeps Johnson's optimal makespan
+			let zeros = vec![0; release_times.len()];
+			let johnson_makespan = MultiMachineSchedule::from_order_ptimes(&johnson(&ptimes), &ptimes).makespan();
+			let without = f2_release_heuristic(&ptimes, &zeros);
+			prop_assert_eq!(MultiMachineSchedule::from_order_ptimes(&without, &ptimes).makespan(), johnson_makespan);
+		}
+	}
+
+	proptest! {
+		#[test]
+		fn test_zero_releases_unchanged(ptimes in flow_shop_ptimes(1..=4, 0..=6, 20)) {
+			let zeros = vec![0; ptimes[0].len()];
+			prop_assert_eq!(neh_releasetimes(&ptimes, &zeros), neh(&ptimes));
+			prop_assert_eq!(dannenbring_releasetimes(&ptimes, &zeros), dannenbring(&ptimes));
+			let order = neh(&ptimes);
+			prop_assert_eq!(
+				MultiMachineSchedule::from_order_ptimes_releasetimes(&order, &ptimes, &zeros),
+				MultiMachineSchedule::from_order_ptimes(&order, &ptimes)
+			);
+		}
+	}
+
+	/// processing times, families and setup times
+	type FamilyInstance = (Vec<Vec<Time>>, Vec<usize>, Vec<Vec<Vec<Time>>>);
+
+	fn family_example() -> FamilyInstance {
+		let ptimes = vec![
+			vec![3, 4, 10, 2, 6, 1],
+			vec![11, 1, 5, 7, 3, 4],
+			vec![7, 9, 13, 2, 5, 8],
+		];
+		let family = vec![0, 1, 0, 2, 1, 2];
+		let setup = vec![
+			vec![vec![0, 4, 2], vec![3, 0, 5], vec![1, 1, 0]],
+			vec![vec![0, 2, 6], vec![2, 0, 2], vec![3, 4, 0]],
+			vec![vec![0, 1, 1], vec![5, 0, 3], vec![2, 2, 0]],
+		];
+		(ptimes, family, setup)
+	}
+
+	#[test]
+	fn test_group_scheduling_setups_at_boundaries() {
+		let (ptimes, family, setup) = family_example();
+		let order = group_scheduling(&ptimes, &family, &setup);
+		let mut sorted = order.clone();
+		sorted.sort_unstable();
+		assert_eq!(sorted, (0..6).collect::<Vec<Job>>());
+		// every family is processed consecutively
+		let boundaries: Vec<usize> = (1..order.len()).filter(|&k| family[order[k-1]] != family[order[k]]).collect();
+		assert_eq!(boundaries.len(), 2);
+		let (schedule, setups) = group_schedule(&order, &ptimes, &family, &setup);
+		for (i, (machine, machine_setups)) in schedule.machine_schedules.iter().zip(setups.iter()).enumerate() {
+			assert_eq!(machine_setups.len(), boundaries.len());
+			for (s, &k) in machine_setups.iter().zip(boundaries.iter()) {
+				let before = machine.schedule[k-1];
+				let after = machine.schedule[k];
+				assert_eq!((s.from_family, s.to_family), (family[before.job], family[after.job]));
+				assert_eq!(s.duration, setup[i][s.from_family][s.to_family]);
+				assert_eq!(s.time, before.time + before.duration);
+				assert!(after.time >= s.time + s.duration);
+			}
+		}
+	}
+
+	#[test]
+	fn test_group_scheduling_zero_setups() {
+		let (ptimes, family, _) = family_example();
+		let zero_setup = vec![vec![vec![0; 3]; 3]; 3];
+		let order = group_scheduling(&ptimes, &family, &zero_setup);
+		let (schedule, _) = group_schedule(&order, &ptimes, &family, &zero_setup);
+		assert_eq!(schedule, MultiMachineSchedule::from_order_ptimes(&order, &ptimes));
+		// with a single family, the jobs are ordered by the plain heuristics
+		let single = vec![0; 6];
+		let single_setup = vec![vec![vec![0]]; 3];
+		assert_eq!(group_scheduling(&ptimes, &single, &single_setup), neh(&ptimes));
+		let single_setup = vec![vec![vec![0]]; 2];
+		assert_eq!(group_scheduling(&ptimes[..2], &single, &single_setup), johnson(&ptimes[..2]));
+	}
+
+	#[test]
+	fn test_group_scheduling_zero_setups_families() {
+		let ptimes = vec![
+			vec![3, 4, 10, 2, 6, 1],
+			vec![11, 1, 5, 7, 3, 4],
+		];
+		// with one job per family and no setups, the families are ordered like the jobs by Johnson's algorithm
+		let setup = vec![vec![vec![0; 6]; 6]; 2];
+		let order = group_scheduling(&ptimes, &[0, 1, 2, 3, 4, 5], &setup);
+		assert_eq!(order, johnson(&ptimes));
+		// with several jobs per family, the setups between the families take no time
+		let family = vec![0, 1, 0, 2, 1, 2];
+		let setup = vec![vec![vec![0; 3]; 3]; 2];
+		let order = group_scheduling(&ptimes, &family, &setup);
+		let (schedule, setups) = group_schedule(&order, &ptimes, &family, &setup);
+		assert!(setups.iter().flatten().all(|s| s.duration == 0));
+		assert_eq!(schedule, MultiMachineSchedule::from_order_ptimes(&order, &ptimes));
+		assert!(group_scheduling(&[], &family, &[]).is_empty());
+	}
+
+	#[test]
+	#[should_panic(expected = "setup times for every machine")]
+	fn test_group_schedule_missing_setups() {
+		let (ptimes, family, setup) = family_example();
+		let order: Vec<Job> = (0..6).collect();
+		group_schedule(&order, &ptimes, &family, &setup[..2]);
+	}
+
+	#[test]
+	fn test_exact_bitmask_example_2() {
+		let ptimes = example_2();
+		let (order, makespan) = exact_bitmask(&ptimes).unwrap();
+		assert_eq!(makespan, 40);
+		assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), 40);
+	}
+
+	#[test]
+	fn test_exact_bitmask_too_many_jobs() {
+		let ptimes = vec![vec![1; MAX_BITMASK_JOBS + 1]; 2];
+		assert_eq!(
+			exact_bitmask(&ptimes),
+			Err(ScheduleError::TooManyJobs{ num_jobs: MAX_BITMASK_JOBS + 1, max_jobs: MAX_BITMASK_JOBS })
+		);
+	}
+
+	proptest! {
+		// brute force for 8 jobs is slow, so use fewer cases
+		#![proptest_config(ProptestConfig::with_cases(64))]
+		#[test]
+		fn test_exact_bitmask_optimal(ptimes in flow_shop_ptimes(1..=4, 0..=8, 20)) {
+			let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+			let (order, makespan) = exact_bitmask(&ptimes).unwrap();
+			prop_assert_eq!(makespan, optimum);
+			prop_assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), optimum);
+		}
+	}
+
+	#[test]
+	fn test_neh_example_2() {
+		let ptimes = example_2();
+		let order = neh(&ptimes);
+		assert_eq!(order, vec![1, 0, 2]);
+		assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), 40);
+	}
+
+	#[test]
+	fn test_edd_neh() {
+		let ptimes = vec![
+			vec![2, 3, 1, 5],
+			vec![1, 5, 8, 7],
+		];
+		let due_times = vec![27, 30, 18, 17];
+		let order = edd_neh(&ptimes, &due_times);
+		assert_eq!(order, vec![2, 3, 1, 0]);
+		let lateness = max_lateness_of_order(&ptimes, &order, &due_times);
+		assert_eq!(lateness, MultiMachineSchedule::from_order_ptimes(&order, &ptimes).max_lateness(&due_times));
+		assert_eq!(lateness, -1);
+		// EDD itself is worse
+		assert_eq!(max_lateness_of_order(&ptimes, &[3, 2, 0, 1], &due_times), 2);
+	}
+
+	#[test]
+	fn test_total_completion_heuristic_trade_off() {
+		let ptimes = vec![
+			vec![6, 2, 2, 9],
+			vec![2, 1, 6, 5],
+			vec![2, 1, 7, 5],
+		];
+		let makespan_order = neh(&ptimes);
+		let completion_order = total_completion_heuristic(&ptimes);
+		assert_eq!(makespan_order, vec![2, 3, 1, 0]);
+		assert_eq!(completion_order, vec![1, 0, 2, 3]);
+		let makespan_schedule = MultiMachineSchedule::from_order_ptimes(&makespan_order, &ptimes);
+		let completion_schedule = MultiMachineSchedule::from_order_ptimes(&completion_order, &ptimes);
+		// the order for sum C_j has smaller total completion time, but larger makespan
+		assert_eq!(makespan_schedule.total_completion_time(), 82);
+		assert_eq!(completion_schedule.total_completion_time(), 68);
+		assert_eq!(makespan_schedule.makespan(), 24);
+		assert_eq!(completion_schedule.makespan(), 29);
+	}
+}
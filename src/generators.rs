@@ -0,0 +1,241 @@
+use crate::{Time, ScheduleError, SingleMachineInstance};
+use crate::rng::Rng;
+
+
+/// Instances whose times can be transformed by the functions of this module:
+/// `SingleMachineInstance` and flow shop processing time matrices, where `ptimes[i][j]` is the time needed by machine i for job j.
+pub trait InstanceTimes: Clone {
+	/// Replaces every processing time `p` by `f(p)`.
+	fn map_ptimes<F: FnMut(Time) -> Time>(&mut self, f: F);
+
+	/// Replaces every time of the instance (processing times and, if present, release and due times) `t` by `f(t)`.
+	/// Stops at the first error.
+	fn try_map_times<F>(&mut self, f: F) -> Result<(), ScheduleError>
+	where
+		F: FnMut(Time) -> Result<Time, ScheduleError>;
+}
+
+impl InstanceTimes for SingleMachineInstance {
+	fn map_ptimes<F: FnMut(Time) -> Time>(&mut self, f: F) {
+		self.ptimes.iter_mut().for_each(map_in_place(f));
+	}
+
+	fn try_map_times<F>(&mut self, mut f: F) -> Result<(), ScheduleError>
+	where
+		F: FnMut(Time) -> Result<Time, ScheduleError>
+	{
+		for t in self.ptimes.iter_mut().chain(self.release_times.iter_mut()).chain(self.due_times.iter_mut()) {
+			*t = f(*t)?;
+		}
+		Ok(())
+	}
+}
+
+impl InstanceTimes for Vec<Vec<Time>> {
+	fn map_ptimes<F: FnMut(Time) -> Time>(&mut self, f: F) {
+		self.iter_mut().flatten().for_each(map_in_place(f));
+	}
+
+	fn try_map_times<F>(&mut self, mut f: F) -> Result<(), ScheduleError>
+	where
+		F: FnMut(Time) -> Result<Time, ScheduleError>
+	{
+		for t in self.iter_mut().flatten() {
+			*t = f(*t)?;
+		}
+		Ok(())
+	}
+}
+
+fn map_in_place<F: FnMut(Time) -> Time>(mut f: F) -> impl FnMut(&mut Time) {
+	move |t| *t = f(*t)
+}
+
+
+/// Generates a random single machine instance.
+/// Processing times are drawn from `1..=max_ptime`, release times from `0..=n * max_ptime / 2`,
+/// and job j is due `p_j` plus a slack from `0..=n * max_ptime / 2` after its release.
+///
+/// # Arguments
+/// * `num_jobs`: The number of jobs n
+/// * `max_ptime`: The maximum processing time
+/// * `seed`: Seed for the random times, the result is deterministic for a given seed
+///
+/// # Panics
+/// If `max_ptime` is not positive.
+pub fn random_single_machine(num_jobs: usize, max_ptime: Time, seed: u64) -> SingleMachineInstance {
+	assert!(max_ptime > 0, "Maximum processing time must be positive");
+	let mut rng = Rng::new(seed);
+	let horizon = num_jobs * max_ptime as usize / 2;
+	let mut draw = |n: usize| rng.below(n + 1) as Time;
+	let mut instance = SingleMachineInstance{ ptimes: Vec::new(), release_times: Vec::new(), due_times: Vec::new() };
+	for _ in 0..num_jobs {
+		let p = 1 + draw(max_ptime as usize - 1);
+		let r = draw(horizon);
+		instance.ptimes.push(p);
+		instance.release_times.push(r);
+		instance.due_times.push(r + p + draw(horizon));
+	}
+	instance
+}
+
+/// Multiplies every processing time by an independent random factor between `1 - noise_pct/100` and `1 + noise_pct/100`.
+/// The results are rounded to the nearest integer (halves away from zero) and negative results become 0,
+/// so the instance stays valid.
+/// Release and due times are not changed.
+///
+/// # Arguments
+/// * `instance`: The instance to perturb
+/// * `noise_pct`: The maximum relative change of a processing time in percent
+/// * `seed`: Seed for the random factors, the result is deterministic for a given seed
+///
+/// # Returns
+/// The perturbed instance.
+///
+/// # Panics
+/// If `noise_pct` is negative or not finite.
+pub fn perturb<I: InstanceTimes>(instance: &I, noise_pct: f64, seed: u64) -> I {
+	assert!(noise_pct.is_finite() && noise_pct >= 0.0, "Noise must be a non-negative percentage");
+	let mut rng = Rng::new(seed);
+	let mut result = instance.clone();
+	result.map_ptimes(|p| {
+		// uniform in [-1, 1]
+		let u = (rng.next_u64() >> 11) as f64 / (1u64 << 52) as f64 - 1.0;
+		let factor = 1.0 + u * noise_pct / 100.0;
+		(p as f64 * factor).round().max(0.0) as Time
+	});
+	result
+}
+
+
+/// Scales all times of the instance (processing, release and due times) by `factor_num / factor_den`,
+/// computed in integer arithmetic and rounded down.
+/// Scaling is monotone, so jobs are not due before they are released if this was the case before.
+///
+/// # Arguments
+/// * `instance`: The instance to scale
+/// * `factor_num`: Numerator of the scaling factor
+/// * `factor_den`: Denominator of the scaling factor
+///
+/// # Returns
+/// The scaled instance, or `ScheduleError::TimeOverflow` if a time multiplied by `factor_num` overflows.
+///
+/// # Panics
+/// If `factor_num` is negative or `factor_den` is not positive.
+pub fn scale_times<I: InstanceTimes>(instance: &I, factor_num: Time, factor_den: Time) -> Result<I, ScheduleError> {
+	assert!(factor_num >= 0, "Scaling factor must not be negative");
+	assert!(factor_den > 0, "Scaling factor must have a positive denominator");
+	let mut result = instance.clone();
+	result.try_map_times(|t| {
+		t.checked_mul(factor_num)
+			.map(|product| product.div_euclid(factor_den))
+			.ok_or(ScheduleError::TimeOverflow{ time: t })
+	})?;
+	Ok(result)
+}
+
+
+/// Moves all due times `delta` time units earlier (or later, if `delta` is negative).
+///
+/// # Arguments
+/// * `instance`: The instance whose due dates are tightened
+/// * `delta`: The amount by which every due time is decreased
+/// * `allow_infeasible`: Whether jobs may become due before they are released
+///
+/// # Returns
+/// The instance with the new due times,
+/// `ScheduleError::DueBeforeRelease` for the first job that is due before it is released unless `allow_infeasible` is set,
+/// or `ScheduleError::TimeOverflow` if a due time overflows.
+pub fn tighten_due_dates(
+	instance: &SingleMachineInstance,
+	delta: Time,
+	allow_infeasible: bool
+) -> Result<SingleMachineInstance, ScheduleError>
+{
+	let mut result = instance.clone();
+	for (job, due_time) in result.due_times.iter_mut().enumerate() {
+		*due_time = due_time.checked_sub(delta).ok_or(ScheduleError::TimeOverflow{ time: *due_time })?;
+		let release_time = result.release_times[job];
+		if !allow_infeasible && *due_time < release_time {
+			return Err(ScheduleError::DueBeforeRelease{ job, release_time, due_time: *due_time });
+		}
+	}
+	Ok(result)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::flow_shop_ptimes;
+	use proptest::prelude::*;
+
+	fn example_instance() -> SingleMachineInstance {
+		SingleMachineInstance::new(&[4, 7, 1], &[0, 3, 5], &[10, 12, 6]).unwrap()
+	}
+
+	#[test]
+	fn test_scale_times() {
+		let instance = example_instance();
+		assert_eq!(scale_times(&instance, 1, 1), Ok(instance.clone()));
+		let scaled = scale_times(&instance, 3, 2).unwrap();
+		assert_eq!(scaled, SingleMachineInstance::new(&[6, 10, 1], &[0, 4, 7], &[15, 18, 9]).unwrap());
+		assert_eq!(
+			scale_times(&vec![vec![1, Time::MAX / 2]], 3, 1),
+			Err(ScheduleError::TimeOverflow{ time: Time::MAX / 2 })
+		);
+	}
+
+	#[test]
+	fn test_tighten_due_dates() {
+		let instance = example_instance();
+		assert_eq!(tighten_due_dates(&instance, 1, false).unwrap().due_times, vec![9, 11, 5]);
+		// job 2 is released at 5
+		assert_eq!(
+			tighten_due_dates(&instance, 4, false),
+			Err(ScheduleError::DueBeforeRelease{ job: 2, release_time: 5, due_time: 2 })
+		);
+		assert_eq!(tighten_due_dates(&instance, 4, true).unwrap().due_times, vec![6, 8, 2]);
+		assert_eq!(tighten_due_dates(&instance, -2, false).unwrap().due_times, vec![12, 14, 8]);
+	}
+
+	#[test]
+	fn test_random_single_machine() {
+		let instance = random_single_machine(10, 5, 3);
+		assert_eq!(instance, random_single_machine(10, 5, 3));
+		assert_ne!(instance, random_single_machine(10, 5, 4));
+		assert_eq!(instance.num_jobs(), 10);
+		assert!(instance.ptimes.iter().all(|&p| (1..=5).contains(&p)));
+		for j in 0..10 {
+			assert!(instance.due_times[j] >= instance.release_times[j] + instance.ptimes[j]);
+		}
+	}
+
+	#[test]
+	fn test_perturb() {
+		let instance = example_instance();
+		assert_eq!(perturb(&instance, 0.0, 7), instance);
+		let perturbed = perturb(&instance, 50.0, 7);
+		assert_eq!(perturbed, perturb(&instance, 50.0, 7));
+		assert_eq!(perturbed.release_times, instance.release_times);
+		for (&p, &q) in instance.ptimes.iter().zip(perturbed.ptimes.iter()) {
+			assert!(2 * q >= p && 2 * q <= 3 * p + 1, "{} is not within 50% of {}", q, p);
+		}
+	}
+
+	proptest! {
+		#[test]
+		fn test_scale_round_trip(ptimes in flow_shop_ptimes(1..=4, 0..=10, 1000), factor in 1..100 as Time) {
+			prop_assert_eq!(scale_times(&ptimes, 1, 1), Ok(ptimes.clone()));
+			let scaled = scale_times(&ptimes, factor, 1).unwrap();
+			prop_assert_eq!(scale_times(&scaled, 1, factor), Ok(ptimes.clone()));
+		}
+
+		#[test]
+		fn test_perturb_valid(ptimes in flow_shop_ptimes(1..=4, 0..=10, 1000), noise in 0.0..200.0f64, seed in any::<u64>()) {
+			let perturbed = perturb(&ptimes, noise, seed);
+			prop_assert!(perturbed.iter().flatten().all(|&p| p >= 0));
+			prop_assert_eq!(perturb(&ptimes, 0.0, seed), ptimes);
+		}
+	}
+}
@@ -0,0 +1,162 @@
+use std::cmp::{max, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+use crate::flow_shop::dannenbring;
+
+
+/// Rule determining the order in which jobs enter the first stage of a hybrid flow shop.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OrderRule {
+	/// Dannenbring's heuristic (Johnson's algorithm for 2 stages) applied to the processing times
+	/// divided by the number of machines of each stage, i.e. to the average load of a stage per job.
+	Dannenbring,
+	/// The given permutation of the jobs.
+	Fixed(Vec<Job>),
+}
+
+
+/// List scheduling heuristic for the hybrid flow shop, where each stage consists of identical parallel machines.
+/// Every job is processed on one machine of each stage, stage after stage.
+/// At the first stage, the jobs are dispatched in the order given by `order_rule`,
+/// at each later stage in order of their completion at the previous stage (ties in the order of the first stage).
+/// Each job is scheduled on the machine of the stage that becomes available first (the lowest machine index in case of ties).
+/// With a single machine per stage, this yields the same schedule as `MultiMachineSchedule::from_order_ptimes`.
+///
+/// # Arguments
+/// * `ptimes_per_stage`: `ptimes_per_stage[i][j]` is the time needed by any machine of stage i for job j.
+/// * `machines_per_stage`: The number of machines of each stage.
+/// * `order_rule`: The order of the jobs at the first stage.
+///
+/// # Returns
+/// One schedule for each stage, with one machine schedule for each machine of that stage.
+///
+/// # Panics
+/// If a processing time is negative, a stage has no machines,
+/// or `ptimes_per_stage` and `machines_per_stage` have different lengths.
+pub fn schedule(
+	ptimes_per_stage: &[Vec<Time>],
+	machines_per_stage: &[usize],
+	order_rule: OrderRule
+) -> Vec<MultiMachineSchedule>
+{
+	assert_eq!(ptimes_per_stage.len(), machines_per_stage.len(), "Every stage needs a number of machines");
+	assert!(machines_per_stage.iter().all(|&m| m > 0), "Every stage needs at least one machine");
+	ptimes_per_stage.iter().for_each(|p| assert_valid_ptimes(p));
+	let n = ptimes_per_stage.first().map_or(0, |p| p.len());
+	let mut order = match order_rule {
+		OrderRule::Dannenbring => dannenbring(&stage_loads(ptimes_per_stage, machines_per_stage)),
+		OrderRule::Fixed(order) => order,
+	};
+	let mut ready_times = vec![0; n];
+	let mut result = Vec::with_capacity(ptimes_per_stage.len());
+	for (ptimes, &num_machines) in ptimes_per_stage.iter().zip(machines_per_stage.iter()) {
+		let mut schedules = vec![MachineSchedule::new(); num_machines];
+		// machines ordered by the time at which they become available
+		let mut available: BinaryHeap<_> = (0..num_machines).map(|i| Reverse((Time::MIN, i))).collect();
+		for &job in order.iter() {
+			let Reverse((free, machine)) = available.pop().unwrap();
+			let time = max(free, ready_times[job]);
+			schedules[machine].schedule.push(JobRun{
+				time,
+				job,
+				duration: ptimes[job],
+			});
+			ready_times[job] = time + ptimes[job];
+			available.push(Reverse((ready_times[job], machine)));
+		}
+		// stable sort keeps the previous order for ties
+		order.sort_by_key(|&job| ready_times[job]);
+		result.push(MultiMachineSchedule{ machine_schedules: schedules });
+	}
+	result
+}
+
+
+/// Scales the processing times of each stage by the product of the machine counts of all other stages,
+/// which is proportional to the processing time divided by the number of machines of the stage.
+fn stage_loads(ptimes_per_stage: &[Vec<Time>], machines_per_stage: &[usize]) -> Vec<Vec<Time>> {
+	ptimes_per_stage.iter().enumerate().map(|(i, ptimes)| {
+		let factor: Time = machines_per_stage.iter().enumerate()
+			.filter(|&(k, _)| k != i)
+			.map(|(_, &m)| m as Time)
+			.product();
+		ptimes.iter().map(|&p| p * factor).collect()
+	}).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+	use proptest::collection;
+
+	/// Panics if the schedules do not process every job once on each stage,
+	/// after it is completed on the previous stage, without overlaps on any machine.
+	fn assert_valid_hybrid(stages: &[MultiMachineSchedule], ptimes_per_stage: &[Vec<Time>]) {
+		let n = ptimes_per_stage[0].len();
+		let mut ready_times = vec![0; n];
+		for (stage, ptimes) in stages.iter().zip(ptimes_per_stage.iter()) {
+			let mut completion: Vec<Option<Time>> = vec![None; n];
+			for machine in stage.machine_schedules.iter() {
+				let mut previous_end = Time::MIN;
+				for run in machine.schedule.iter() {
+					assert!(completion[run.job].is_none(), "Job #{} is processed twice on a stage", run.job);
+					assert_eq!(run.duration, ptimes[run.job]);
+					assert!(run.time >= previous_end, "Job #{} overlaps with the previous run", run.job);
+					assert!(run.time >= ready_times[run.job], "Job #{} starts before the previous stage completes", run.job);
+					completion[run.job] = Some(run.time + run.duration);
+					previous_end = run.time + run.duration;
+				}
+			}
+			for (job, c) in completion.into_iter().enumerate() {
+				ready_times[job] = c.unwrap_or_else(|| panic!("Job #{} is missing on a stage", job));
+			}
+		}
+	}
+
+	#[test]
+	fn test_hybrid_flow_shop_optimal() {
+		let ptimes = vec![
+			vec![2, 2, 4, 4],
+			vec![3, 3, 1, 1],
+		];
+		let stages = schedule(&ptimes, &[2, 1], OrderRule::Dannenbring);
+		assert_valid_hybrid(&stages, &ptimes);
+		// the single machine of stage 2 has 8 time units of work and cannot start before 2
+		assert_eq!(stages[1].makespan(), 10);
+		assert_eq!(stages[0].machine_schedules[0].schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 2, duration: 4 },
+		]);
+	}
+
+	proptest! {
+		#[test]
+		fn test_single_machines_reduce_to_flow_shop(
+			ptimes in (1..=4usize, 0..=6usize).prop_flat_map(|(m, n)|
+				collection::vec(collection::vec(0..20 as Time, n), m)
+			)
+		) {
+			let stages = schedule(&ptimes, &vec![1; ptimes.len()], OrderRule::Dannenbring);
+			let flow_shop = MultiMachineSchedule::from_order_ptimes(&dannenbring(&ptimes), &ptimes);
+			let machine_schedules: Vec<MachineSchedule> = stages.into_iter()
+				.map(|mut stage| stage.machine_schedules.pop().unwrap())
+				.collect();
+			prop_assert_eq!(machine_schedules, flow_shop.machine_schedules);
+		}
+
+		#[test]
+		fn test_hybrid_flow_shop_valid(
+			(ptimes, machines) in (1..=3usize, 1..=8usize).prop_flat_map(|(m, n)| (
+				collection::vec(collection::vec(0..20 as Time, n), m),
+				collection::vec(1..=3usize, m),
+			))
+		) {
+			let stages = schedule(&ptimes, &machines, OrderRule::Dannenbring);
+			assert_valid_hybrid(&stages, &ptimes);
+		}
+	}
+}
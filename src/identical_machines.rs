@@ -0,0 +1,222 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Time, Job, MultiMachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+
+
+/// EDD list scheduling heuristic for scheduling on identical parallel machines
+/// in order to minimize the maximum lateness, i.e. for P||L_max.
+/// Jobs are considered in order of earliest due date, and each job is scheduled
+/// on the machine that becomes available first (the lowest machine index in case of ties).
+/// P||L_max is NP-hard, so the result is not optimal in general.
+/// Runs in O(n log n + n log m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * `ptimes`: ptimes[i] is the processing time of job i (on any machine).
+/// * `due_times`: due_times[i] is the due time of job i.
+/// * `num_machines`: The number of machines.
+///
+/// # Returns
+/// The resulting schedule.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn edd_parallel(
+	ptimes: &[Time],
+	due_times: &[Time],
+	num_machines: usize
+) -> MultiMachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
+	jobs.sort_by_key(|&job| due_times[job]);
+	let mut schedule = MultiMachineSchedule::with_machines(num_machines);
+	// machines ordered by the time at which they become available
+	let mut available: BinaryHeap<_> = (0..num_machines).map(|i| Reverse((0, i))).collect();
+	for job in jobs {
+		let Reverse((time, machine)) = available.pop().expect("Instance must have at least one machine");
+		schedule.append_run(machine, JobRun{
+			time,
+			job,
+			duration: ptimes[job],
+		});
+		available.push(Reverse((time + ptimes[job], machine)));
+	}
+	schedule
+}
+
+
+/// First Fit Decreasing heuristic for P||C_max with a given target makespan.
+/// Every machine is treated as a bin of capacity `target`:
+/// Jobs are considered in order of decreasing processing time (by job id in case of ties),
+/// and each job is appended to the first machine (i.e. the one with the lowest index)
+/// on which it completes no later than `target`.
+/// Searching for the smallest `target` for which a schedule is found, e.g. by binary search,
+/// yields a heuristic for P||C_max.
+/// Runs in O(n log n + n m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * `ptimes`: ptimes[i] is the processing time of job i (on any machine).
+/// * `num_machines`: The number of machines.
+/// * `target`: The makespan that no machine may exceed.
+///
+/// # Returns
+/// A schedule with makespan at most `target`, or None if some job does not fit on any machine.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn first_fit_decreasing(
+	ptimes: &[Time],
+	num_machines: usize,
+	target: Time
+) -> Option<MultiMachineSchedule>
+{
+	assert_valid_ptimes(ptimes);
+	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
+	jobs.sort_by_key(|&job| Reverse(ptimes[job]));
+	let mut schedule = MultiMachineSchedule::with_machines(num_machines);
+	let mut loads = vec![0; num_machines];
+	for job in jobs {
+		let machine = (0..num_machines).find(|&i| loads[i] + ptimes[job] <= target)?;
+		schedule.append_run(machine, JobRun{
+			time: loads[machine],
+			job,
+			duration: ptimes[job],
+		});
+		loads[machine] += ptimes[job];
+	}
+	Some(schedule)
+}
+
+
+
+/// LPT list scheduling for identical parallel machines with operating costs,
+/// minimizing the weighted machine cost sum cost_i * load_i (see `MultiMachineSchedule::weighted_machine_cost`).
+/// Jobs are considered in order of decreasing processing time (by job id in case of ties),
+/// and each job is appended to the machine where it increases the cost the least.
+/// As the cost is linear in the loads, this is a machine with the lowest cost,
+/// and among those the one with the smallest load (the lowest machine index in case of ties).
+/// Hence all jobs are processed by the cheapest machines, which are balanced like in LPT for P||C_max,
+/// and the weighted machine cost is optimal.
+/// Runs in O(n log n + n m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * `ptimes`: ptimes[i] is the processing time of job i (on any machine).
+/// * `num_machines`: The number of machines.
+/// * `machine_costs`: machine_costs[i] is the cost of machine i per time unit it processes a job.
+///
+/// # Returns
+/// The resulting schedule.
+///
+/// # Panics
+/// If a processing time is negative, there are no machines but some jobs,
+/// or `machine_costs` does not have `num_machines` entries.
+pub fn lpt_weighted_cost(
+	ptimes: &[Time],
+	num_machines: usize,
+	machine_costs: &[Time]
+) -> MultiMachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	assert_eq!(machine_costs.len(), num_machines, "Every machine needs a cost");
+	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
+	jobs.sort_by_key(|&job| Reverse(ptimes[job]));
+	let mut schedule = MultiMachineSchedule::with_machines(num_machines);
+	let mut loads = vec![0; num_machines];
+	for job in jobs {
+		let machine = (0..num_machines)
+			.min_by_key(|&i| (machine_costs[i] * ptimes[job], loads[i], i))
+			.expect("Instance must have at least one machine");
+		schedule.append_run(machine, JobRun{
+			time: loads[machine],
+			job,
+			duration: ptimes[job],
+		});
+		loads[machine] += ptimes[job];
+	}
+	schedule
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::MachineSchedule;
+	use crate::objective::MaxLateness;
+	use crate::reference::brute_force_parallel;
+
+	fn example_1() -> (Vec<Time>, Vec<Time>) {
+		(
+			//   0  1  2  3   4
+			vec![3, 2, 4, 1,  5], // processing
+			vec![4, 3, 9, 5, 10], // due
+		)
+	}
+
+	#[test]
+	fn test_lpt_weighted_cost() {
+		let (p, _) = example_1();
+		let schedule = lpt_weighted_cost(&p, 3, &[2, 1, 1]);
+		let jobs = |i: usize| schedule.machine_schedules[i].job_order();
+		// machine 0 is too expensive
+		assert!(jobs(0).is_empty());
+		assert_eq!(jobs(1), vec![4, 1, 3]);
+		assert_eq!(jobs(2), vec![2, 0]);
+		assert_eq!(schedule.weighted_machine_cost(&[2, 1, 1]), 15);
+		// with equal costs, this is LPT
+		assert_eq!(lpt_weighted_cost(&p, 2, &[1, 1]).makespan(), 8);
+	}
+
+	#[test]
+	fn test_edd_parallel_example_1() {
+		let (p, d) = example_1();
+		let schedule = edd_parallel(&p, &d, 2);
+		let jobs = |i: usize| schedule.machine_schedules[i].job_order();
+		assert_eq!(jobs(0), vec![1, 3, 2]);
+		assert_eq!(jobs(1), vec![0, 4]);
+		assert_eq!(schedule.max_lateness(&d), -1);
+		let (_, optimum) = brute_force_parallel(&p, 2, &d, &MaxLateness).unwrap();
+		assert!(schedule.max_lateness(&d) >= optimum);
+	}
+
+	#[test]
+	fn test_first_fit_decreasing() {
+		let p = vec![5, 4, 3, 3, 2, 2, 1];
+		let schedule = first_fit_decreasing(&p, 2, 10).unwrap();
+		let jobs = |i: usize| schedule.machine_schedules[i].job_order();
+		assert_eq!(jobs(0), vec![0, 1, 6]);
+		assert_eq!(jobs(1), vec![2, 3, 4, 5]);
+		assert_eq!(schedule.makespan(), 10);
+		// the total processing time of 20 does not fit on 2 machines with makespan 9
+		assert_eq!(first_fit_decreasing(&p, 2, 9), None);
+		// a single job that is too long
+		assert_eq!(first_fit_decreasing(&p, 7, 4), None);
+	}
+
+	#[test]
+	fn test_first_fit_decreasing_binary_search() {
+		let p = vec![7, 6, 5, 4, 4, 3, 2, 2];
+		let num_machines = 3;
+		let (mut low, mut high) = (0, p.iter().sum::<Time>());
+		while low < high {
+			let target = (low + high) / 2;
+			if first_fit_decreasing(&p, num_machines, target).is_some() {
+				high = target;
+			} else {
+				low = target + 1;
+			}
+		}
+		// the total processing time is 33, so 11 is optimal
+		assert_eq!(low, 11);
+	}
+
+	#[test]
+	fn test_edd_parallel_single_machine() {
+		let (p, d) = example_1();
+		let schedule = edd_parallel(&p, &d, 1);
+		assert_eq!(
+			schedule.machine_schedules[0],
+			MachineSchedule::from_order_ptimes(vec![1, 0, 3, 2, 4].into_iter(), &p)
+		);
+	}
+}
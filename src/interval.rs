@@ -0,0 +1,225 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Time, Job, Machine, MultiMachineSchedule, JobRun};
+use crate::flow::MinCostFlow;
+
+
+/// Weighted interval scheduling on identical machines:
+/// every job j must be processed exactly in its fixed interval [s_j, e_j) or rejected,
+/// and the total weight of the processed jobs is maximized.
+///
+/// Intervals are half-open, so a job ending at t and a job starting at t fit on the same machine,
+/// and identical intervals are distinct jobs that need distinct machines.
+/// A job with an empty interval [t, t) needs a machine that is not inside another job at time t,
+/// but any number of them can share that machine.
+/// Jobs without positive weight are never accepted.
+///
+/// The problem is solved optimally as a minimum cost flow of `num_machines` units
+/// along the sorted distinct interval endpoints, where each unit is a machine
+/// that can skip over the interval of a job at cost -w_j instead of moving along the timeline.
+/// Every endpoint has an additional edge of capacity 1 collecting the weight of the empty intervals at that time.
+/// Runs in O(k n^2) time for n jobs and k machines.
+///
+/// See Arkin, Silverberg: "Scheduling jobs with fixed start and end times" (1987)
+///
+/// # Arguments
+/// * `intervals`: `intervals[j]` is the start and end time of job j
+/// * `weights`: `weights[j]` is the weight of job j
+/// * `num_machines`: The number of machines
+///
+/// # Returns
+/// Whether each job is accepted, a schedule of the accepted jobs, and their total weight.
+/// Every machine processes its jobs in order of time.
+///
+/// # Panics
+/// If an interval ends before it starts, or the number of weights differs from the number of intervals.
+pub fn max_weight(
+	intervals: &[(Time, Time)],
+	weights: &[Time],
+	num_machines: usize
+) -> (Vec<bool>, MultiMachineSchedule, Time)
+{
+	assert_eq!(intervals.len(), weights.len(), "There must be one weight per interval");
+	for (job, &(start, end)) in intervals.iter().enumerate() {
+		assert!(start <= end, "Interval of job #{} ends at {} before it starts at {}", job, end, start);
+	}
+	let n = intervals.len();
+	let mut accepted = vec![false; n];
+	let candidates: Vec<Job> = (0..n).filter(|&j| weights[j] > 0).collect();
+	if num_machines == 0 || candidates.is_empty() {
+		return (accepted, MultiMachineSchedule::with_machines(num_machines), 0);
+	}
+	let mut points: Vec<Time> = candidates.iter().flat_map(|&j| [intervals[j].0, intervals[j].1]).collect();
+	points.sort_unstable();
+	points.dedup();
+	let index = |t: Time| points.binary_search(&t).unwrap();
+	// every point i has an entry node 2i and an exit node 2i + 1
+	let k = num_machines as Time;
+	let mut network = MinCostFlow::new(2 * points.len());
+	let mut empty_weight = vec![0; points.len()];
+	let mut job_edges = Vec::new();
+	for &job in candidates.iter() {
+		let (start, end) = intervals[job];
+		if start == end {
+			empty_weight[index(start)] += weights[job];
+		} else {
+			job_edges.push((job, network.add_edge(2 * index(start) + 1, 2 * index(end), 1, -weights[job])));
+		}
+	}
+	let mut empty_edges = vec![None; points.len()];
+	for i in 0..points.len() {
+		network.add_edge(2 * i, 2 * i + 1, k, 0);
+		if empty_weight[i] > 0 {
+			empty_edges[i] = Some(network.add_edge(2 * i, 2 * i + 1, 1, -empty_weight[i]));
+		}
+		if i + 1 < points.len() {
+			network.add_edge(2 * i + 1, 2 * i + 2, k, 0);
+		}
+	}
+	let (_, cost) = network.run(0, 2 * points.len() - 1, k);
+	for (job, edge) in job_edges {
+		accepted[job] = network.flow(edge) > 0;
+	}
+	for &job in candidates.iter() {
+		let (start, end) = intervals[job];
+		if start == end {
+			accepted[job] = empty_edges[index(start)].is_some_and(|e| network.flow(e) > 0);
+		}
+	}
+	let schedule = assign_machines(intervals, &accepted, num_machines);
+	(accepted, schedule, -cost)
+}
+
+
+/// Assigns the accepted intervals to machines greedily in order of start time,
+/// with empty intervals before the other intervals starting at the same time.
+/// This needs no more machines than the maximum number of accepted intervals processed at the same time.
+///
+/// # Panics
+/// If the accepted intervals need more than `num_machines` machines.
+fn assign_machines(intervals: &[(Time, Time)], accepted: &[bool], num_machines: usize) -> MultiMachineSchedule {
+	let mut jobs: Vec<Job> = (0..intervals.len()).filter(|&j| accepted[j]).collect();
+	jobs.sort_by_key(|&j| (intervals[j].0, intervals[j].1 > intervals[j].0, intervals[j].1));
+	let mut schedule = MultiMachineSchedule::with_machines(num_machines);
+	let mut idle: BinaryHeap<Reverse<Machine>> = (0..num_machines).map(Reverse).collect();
+	let mut busy: BinaryHeap<Reverse<(Time, Machine)>> = BinaryHeap::new();
+	for job in jobs {
+		let (start, end) = intervals[job];
+		while let Some(&Reverse((busy_until, machine))) = busy.peek() {
+			if busy_until > start {
+				break;
+			}
+			busy.pop();
+			idle.push(Reverse(machine));
+		}
+		let Reverse(machine) = if start == end {
+			// the machine stays idle after an empty interval
+			*idle.peek().expect("Accepted intervals need more machines than available")
+		} else {
+			idle.pop().expect("Accepted intervals need more machines than available")
+		};
+		schedule.append_run(machine, JobRun{ time: start, job, duration: end - start });
+		if start < end {
+			busy.push(Reverse((end, machine)));
+		}
+	}
+	schedule
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	/// Returns whether the jobs of `set` can be processed on `k` machines, see `max_weight`.
+	fn feasible(intervals: &[(Time, Time)], set: &[Job], k: usize) -> bool {
+		set.iter().all(|&j| {
+			let (s, e) = intervals[j];
+			// the jobs processed right after s, or at s for an empty interval
+			let covering = set.iter().filter(|&&i| {
+				let (a, b) = intervals[i];
+				a < b && if s < e { a <= s && s < b } else { a < s && s < b }
+			}).count();
+			if s < e { covering <= k } else { covering < k }
+		})
+	}
+
+	fn brute_force(intervals: &[(Time, Time)], weights: &[Time], k: usize) -> Time {
+		let n = intervals.len();
+		(0..1usize << n).map(|mask| {
+			let set: Vec<Job> = (0..n).filter(|&j| mask & (1 << j) != 0).collect();
+			if feasible(intervals, &set, k) { set.iter().map(|&j| weights[j]).sum() } else { Time::MIN }
+		}).max().unwrap()
+	}
+
+	/// The classic dynamic program for a single machine over the jobs sorted by end time.
+	fn single_machine_dp(intervals: &[(Time, Time)], weights: &[Time]) -> Time {
+		let mut jobs: Vec<Job> = (0..intervals.len()).collect();
+		jobs.sort_by_key(|&j| (intervals[j].1, intervals[j].0));
+		// best[i] is the maximum weight of the first i jobs
+		let mut best = vec![0; jobs.len() + 1];
+		for (i, &j) in jobs.iter().enumerate() {
+			let previous = jobs[..i].partition_point(|&p| intervals[p].1 <= intervals[j].0);
+			best[i + 1] = best[i].max(best[previous] + weights[j]);
+		}
+		best[jobs.len()]
+	}
+
+	fn assert_valid(intervals: &[(Time, Time)], weights: &[Time], k: usize) -> Time {
+		let (accepted, schedule, total) = max_weight(intervals, weights, k);
+		assert_eq!(schedule.num_machines(), k);
+		let mut jobs: Vec<Job> = schedule.machine_schedules.iter().flat_map(|s| s.job_order()).collect();
+		jobs.sort_unstable();
+		assert_eq!(jobs, (0..intervals.len()).filter(|&j| accepted[j]).collect::<Vec<Job>>());
+		for s in schedule.machine_schedules.iter() {
+			for run in s.schedule.iter() {
+				assert_eq!((run.time, run.time + run.duration), intervals[run.job]);
+			}
+		}
+		assert_eq!(total, jobs.iter().map(|&j| weights[j]).sum::<Time>());
+		total
+	}
+
+	#[test]
+	fn test_touching_and_duplicate_intervals() {
+		// touching intervals fit on one machine, duplicates need two
+		let intervals = vec![(0, 5), (5, 9), (0, 5), (3, 7), (5, 5), (4, 4)];
+		let weights = vec![4, 4, 4, 5, 1, 2];
+		let (accepted, schedule, total) = max_weight(&intervals, &weights, 1);
+		// (0, 5), (5, 5) and (5, 9)
+		assert_eq!(total, 9);
+		assert_eq!(accepted.iter().filter(|&&a| a).count(), 3);
+		assert!(accepted[4]);
+		assert_eq!(schedule.machine_schedules[0].schedule.len(), 3);
+		// (3, 7) next to (0, 5), (5, 5) and (5, 9), while (4, 4) is inside both
+		assert_eq!(assert_valid(&intervals, &weights, 2), 5 + 4 + 1 + 4);
+		assert_eq!(assert_valid(&intervals, &weights, 3), 4 + 4 + 4 + 5 + 1);
+		assert_eq!(assert_valid(&intervals, &weights, 4), 4 + 4 + 4 + 5 + 1 + 2);
+		assert_eq!(max_weight(&intervals, &weights, 0).2, 0);
+		assert_eq!(max_weight(&[(1, 2)], &[0], 1).0, vec![false]);
+	}
+
+	proptest! {
+		#[test]
+		fn test_single_machine_dp(
+			jobs in proptest::collection::vec((0..20 as Time, 0..6 as Time, 1..10 as Time), 0..=10)
+		) {
+			let intervals: Vec<(Time, Time)> = jobs.iter().map(|&(s, l, _)| (s, s + l)).collect();
+			let weights: Vec<Time> = jobs.iter().map(|j| j.2).collect();
+			prop_assert_eq!(assert_valid(&intervals, &weights, 1), single_machine_dp(&intervals, &weights));
+		}
+
+		#[test]
+		fn test_brute_force(
+			jobs in proptest::collection::vec((0..10 as Time, 0..5 as Time, -2..10 as Time), 0..=8),
+			k in 1..=3usize
+		) {
+			let intervals: Vec<(Time, Time)> = jobs.iter().map(|&(s, l, _)| (s, s + l)).collect();
+			let weights: Vec<Time> = jobs.iter().map(|j| j.2).collect();
+			let positive: Vec<Time> = weights.iter().map(|&w| w.max(0)).collect();
+			prop_assert_eq!(assert_valid(&intervals, &weights, k), brute_force(&intervals, &positive, k));
+		}
+	}
+}
@@ -0,0 +1,238 @@
+use crate::{Time, Job, MachineSchedule};
+
+
+/// The data of a single job in a `JobSet`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JobData {
+	pub processing: Time,
+	pub release: Time,
+	pub due: Time,
+	pub weight: Time,
+	/// A name for reports, which is not used by any algorithm
+	pub label: String,
+}
+
+
+/// A registry of jobs with stable ids, as an alternative to passing parallel slices of job data.
+/// Removed jobs leave a tombstone, so the ids of the remaining jobs do not change.
+///
+/// The slices returned by `processing_times`, `release_times` etc. contain the live jobs in order of their ids,
+/// so they can be passed to the slice-based algorithms.
+/// Position i of these slices belongs to the job `ids()[i]`, and `to_ids` translates schedules accordingly.
+/// The `*_on` functions of the algorithms (e.g. `single_machine::carlier_on`) do this translation themselves.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct JobSet {
+	jobs: Vec<Option<JobData>>,
+	ids: Vec<Job>,
+	processing_times: Vec<Time>,
+	release_times: Vec<Time>,
+	due_times: Vec<Time>,
+	weights: Vec<Time>,
+}
+
+impl JobSet {
+	/// Returns an empty registry.
+	pub fn new() -> JobSet {
+		JobSet::default()
+	}
+
+	/// Adds a job, which gets a larger id than all jobs added before.
+	///
+	/// # Panics
+	/// If the processing time is negative.
+	pub fn add(&mut self, data: JobData) -> Job {
+		assert!(data.processing >= 0, "Job \"{}\" has negative processing time {}", data.label, data.processing);
+		let job = self.jobs.len();
+		self.ids.push(job);
+		self.processing_times.push(data.processing);
+		self.release_times.push(data.release);
+		self.due_times.push(data.due);
+		self.weights.push(data.weight);
+		self.jobs.push(Some(data));
+		job
+	}
+
+	/// Removes a job. Its id is not reused.
+	///
+	/// # Returns
+	/// The data of the job, or None if there is no live job with this id.
+	pub fn remove(&mut self, job: Job) -> Option<JobData> {
+		let data = self.jobs.get_mut(job)?.take()?;
+		let i = self.index_of(job).unwrap();
+		self.ids.remove(i);
+		self.processing_times.remove(i);
+		self.release_times.remove(i);
+		self.due_times.remove(i);
+		self.weights.remove(i);
+		Some(data)
+	}
+
+	/// Returns the number of live jobs.
+	pub fn len(&self) -> usize {
+		self.ids.len()
+	}
+
+	/// Returns whether there are no live jobs.
+	pub fn is_empty(&self) -> bool {
+		self.ids.is_empty()
+	}
+
+	/// Returns whether `job` is a live job of this registry.
+	pub fn contains(&self, job: Job) -> bool {
+		self.get(job).is_some()
+	}
+
+	/// Returns the data of a job, or None if there is no live job with this id.
+	pub fn get(&self, job: Job) -> Option<&JobData> {
+		self.jobs.get(job).and_then(|data| data.as_ref())
+	}
+
+	/// Returns the position of a live job in the slices, or None if there is no live job with this id.
+	pub fn index_of(&self, job: Job) -> Option<usize> {
+		self.ids.binary_search(&job).ok()
+	}
+
+	/// Returns the ids of the live jobs in increasing order.
+	pub fn ids(&self) -> &[Job] {
+		&self.ids
+	}
+
+	/// Returns the processing times of the live jobs in order of their ids.
+	pub fn processing_times(&self) -> &[Time] {
+		&self.processing_times
+	}
+
+	/// Returns the release times of the live jobs in order of their ids.
+	pub fn release_times(&self) -> &[Time] {
+		&self.release_times
+	}
+
+	/// Returns the due times of the live jobs in order of their ids.
+	pub fn due_times(&self) -> &[Time] {
+		&self.due_times
+	}
+
+	/// Returns the weights of the live jobs in order of their ids.
+	pub fn weights(&self) -> &[Time] {
+		&self.weights
+	}
+
+	/// Returns the processing time of a job.
+	///
+	/// # Panics
+	/// If there is no live job with this id.
+	pub fn processing(&self, job: Job) -> Time {
+		self.live(job).processing
+	}
+
+	/// Returns the release time of a job, see `processing`.
+	pub fn release(&self, job: Job) -> Time {
+		self.live(job).release
+	}
+
+	/// Returns the due time of a job, see `processing`.
+	pub fn due(&self, job: Job) -> Time {
+		self.live(job).due
+	}
+
+	/// Returns the weight of a job, see `processing`.
+	pub fn weight(&self, job: Job) -> Time {
+		self.live(job).weight
+	}
+
+	/// Returns the label of a job, see `processing`.
+	pub fn label(&self, job: Job) -> &str {
+		&self.live(job).label
+	}
+
+	fn live(&self, job: Job) -> &JobData {
+		self.get(job).unwrap_or_else(|| panic!("Job #{} is not in the set", job))
+	}
+
+	/// Translates a schedule of the slices, where job i is the job at position i, to a schedule of job ids.
+	///
+	/// # Panics
+	/// If the schedule contains a job beyond the number of live jobs.
+	pub fn to_ids(&self, mut schedule: MachineSchedule) -> MachineSchedule {
+		for run in schedule.schedule.iter_mut() {
+			run.job = self.ids[run.job];
+		}
+		schedule
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::single_machine::{carlier, carlier_on, schedule_hodgson_on};
+
+	fn job(processing: Time, release: Time, due: Time) -> JobData {
+		JobData{ processing, release, due, weight: 1, label: format!("p{} r{} d{}", processing, release, due) }
+	}
+
+	/// Panics if the slices do not contain exactly the live jobs in order of their ids.
+	fn assert_consistent(set: &JobSet) {
+		let live: Vec<Job> = (0..set.jobs.len()).filter(|&j| set.contains(j)).collect();
+		assert_eq!(set.ids(), &live[..]);
+		for (i, &j) in live.iter().enumerate() {
+			assert_eq!(set.index_of(j), Some(i));
+			assert_eq!(set.processing_times()[i], set.processing(j));
+			assert_eq!(set.release_times()[i], set.release(j));
+			assert_eq!(set.due_times()[i], set.due(j));
+			assert_eq!(set.weights()[i], set.weight(j));
+		}
+		assert_eq!(set.len(), live.len());
+	}
+
+	#[test]
+	fn test_add_remove_solve() {
+		let mut set = JobSet::new();
+		let a = set.add(job(5, 10, 15));
+		let b = set.add(job(6, 13, 25));
+		let c = set.add(job(7, 11, 32));
+		let d = set.add(job(4, 20, 24));
+		assert_eq!((a, b, c, d), (0, 1, 2, 3));
+		assert_consistent(&set);
+		assert_eq!(set.label(c), "p7 r11 d32");
+
+		assert_eq!(set.remove(b), Some(job(6, 13, 25)));
+		assert_eq!(set.remove(b), None);
+		assert_eq!(set.remove(17), None);
+		assert_consistent(&set);
+		assert_eq!(set.ids(), &[0, 2, 3]);
+		let e = set.add(job(3, 0, 5));
+		assert_eq!(e, 4);
+		assert_consistent(&set);
+
+		let schedule = carlier_on(&set);
+		let expected = set.to_ids(carlier(set.processing_times(), set.release_times(), set.due_times()));
+		assert_eq!(schedule, expected);
+		let mut jobs = schedule.job_order();
+		jobs.sort_unstable();
+		assert_eq!(jobs, vec![a, c, d, e]);
+		for run in schedule.schedule.iter() {
+			assert!(run.time >= set.release(run.job));
+			assert_eq!(run.duration, set.processing(run.job));
+		}
+
+		set.remove(a);
+		set.remove(e);
+		assert_consistent(&set);
+		let schedule = schedule_hodgson_on(&set);
+		assert_eq!(schedule.schedule.iter().filter(|run| run.time + run.duration > set.due(run.job)).count(), 0);
+		set.remove(c);
+		set.remove(d);
+		assert!(set.is_empty());
+		assert!(carlier_on(&set).schedule.is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_removed_job_getter() {
+		let mut set = JobSet::new();
+		let a = set.add(job(1, 0, 1));
+		set.remove(a);
+		set.due(a);
+	}
+}
@@ -0,0 +1,866 @@
+use std::cmp::max;
+use std::collections::VecDeque;
+
+use crate::{Time, Job, Machine, ScheduleError, JobShopInstance, MachineSchedule, MultiMachineSchedule, JobRun};
+use crate::rng::Rng;
+use crate::tracking::TrackedRun;
+use crate::single_machine::carlier;
+
+
+/// The operations of a job shop job in processing order, as pairs of machine and processing time.
+pub type Route = Vec<(Machine, Time)>;
+
+
+/// Disjunctive graph of a job shop instance.
+/// The nodes are the operations, which are numbered job by job in route order
+/// (see `operation`). Conjunctive arcs connect consecutive operations of a job,
+/// and fixing the job sequence of a machine adds arcs between consecutive operations on that machine.
+/// Heads (earliest start times) and tails (longest path from the completion of an operation to the end)
+/// are kept up to date: after each change, only the operations after (for heads)
+/// or before (for tails) the changed arcs are recomputed.
+/// Every job may visit every machine at most once.
+#[derive(Debug, Clone)]
+pub struct DisjunctiveGraph {
+	/// job, machine and processing time of every operation
+	operations: Vec<(Job, Machine, Time)>,
+	/// index of the first operation of every job
+	first_operation: Vec<usize>,
+	job_next: Vec<Option<usize>>,
+	job_prev: Vec<Option<usize>>,
+	machine_next: Vec<Option<usize>>,
+	machine_prev: Vec<Option<usize>>,
+	heads: Vec<Time>,
+	tails: Vec<Time>,
+}
+
+impl DisjunctiveGraph {
+	/// Creates the graph of the given job shop without any fixed machine sequences.
+	///
+	/// # Arguments
+	/// * `routes`: `routes[j]` are the operations of job j.
+	///
+	/// # Panics
+	/// If a processing time is negative or a job visits a machine more than once.
+	pub fn new(routes: &[Route]) -> DisjunctiveGraph {
+		let mut operations = Vec::new();
+		let mut first_operation = Vec::with_capacity(routes.len());
+		for (job, route) in routes.iter().enumerate() {
+			first_operation.push(operations.len());
+			for (k, &(machine, ptime)) in route.iter().enumerate() {
+				assert!(ptime >= 0, "Job #{} has negative processing time {}", job, ptime);
+				assert!(route[..k].iter().all(|&(m, _)| m != machine),
+					"Job #{} visits machine {} more than once", job, machine
+				);
+				operations.push((job, machine, ptime));
+			}
+		}
+		let num_operations = operations.len();
+		let mut job_next = vec![None; num_operations];
+		let mut job_prev = vec![None; num_operations];
+		for v in 1..num_operations {
+			if operations[v - 1].0 == operations[v].0 {
+				job_next[v - 1] = Some(v);
+				job_prev[v] = Some(v - 1);
+			}
+		}
+		let mut graph = DisjunctiveGraph{
+			operations,
+			first_operation,
+			job_next,
+			job_prev,
+			machine_next: vec![None; num_operations],
+			machine_prev: vec![None; num_operations],
+			heads: vec![0; num_operations],
+			tails: vec![0; num_operations],
+		};
+		let (heads, tails) = graph.compute_from_scratch();
+		graph.heads = heads;
+		graph.tails = tails;
+		graph
+	}
+
+	/// Returns the index of the `k`-th operation of `job`.
+	pub fn operation(&self, job: Job, k: usize) -> usize {
+		self.first_operation[job] + k
+	}
+
+	/// Returns the number of operations.
+	pub fn num_operations(&self) -> usize {
+		self.operations.len()
+	}
+
+	/// Returns the earliest start time of every operation.
+	pub fn heads(&self) -> &[Time] {
+		&self.heads
+	}
+
+	/// Returns for every operation the length of the longest path from its completion to the end of the schedule.
+	pub fn tails(&self) -> &[Time] {
+		&self.tails
+	}
+
+	/// Returns the makespan of the (partial) schedule given by the heads.
+	pub fn makespan(&self) -> Time {
+		(0..self.num_operations()).map(|v| self.heads[v] + self.ptime(v)).max().unwrap_or(0)
+	}
+
+	/// Returns job, machine and processing time of the operation with the given index.
+	pub fn operation_details(&self, operation: usize) -> (Job, Machine, Time) {
+		self.operations[operation]
+	}
+
+	/// Returns a longest path through the graph, i.e. a sequence of operations,
+	/// each starting when the previous one completes, from time 0 until the makespan.
+	/// If there are several, the one starting with the lowest operation index is chosen,
+	/// preferring job successors over machine successors.
+	pub fn critical_path(&self) -> Vec<usize> {
+		let makespan = self.makespan();
+		let critical = |v: usize| self.heads[v] + self.ptime(v) + self.tails[v] == makespan;
+		let mut path = Vec::new();
+		let mut current = (0..self.num_operations()).find(|&v| self.heads[v] == 0 && critical(v));
+		while let Some(v) = current {
+			path.push(v);
+			let end = self.heads[v] + self.ptime(v);
+			current = self.successors(v).find(|&w| self.heads[w] == end && critical(w));
+		}
+		path
+	}
+
+	/// Returns the schedule in which every operation starts at its head.
+	/// It is feasible if every machine sequence is fixed.
+	///
+	/// # Arguments
+	/// * `num_machines`: The number of machine schedules in the result. Must be larger than every machine index.
+	pub fn to_schedule(&self, num_machines: usize) -> MultiMachineSchedule {
+		let mut schedules = vec![MachineSchedule::new(); num_machines];
+		for (v, &(job, machine, duration)) in self.operations.iter().enumerate() {
+			schedules[machine].schedule.push(JobRun{ time: self.heads[v], job, duration });
+		}
+		for s in schedules.iter_mut() {
+			s.schedule.sort_by_key(|run| (run.time, run.time + run.duration, run.job));
+		}
+		MultiMachineSchedule{ machine_schedules: schedules }
+	}
+
+	/// Fixes the order in which the machine processes its operations,
+	/// replacing any previously fixed order of the machine.
+	///
+	/// # Arguments
+	/// * `machine`: The machine
+	/// * `order`: The jobs visiting the machine, in processing order
+	///
+	/// # Returns
+	/// An error if the sequence would create a cycle, in which case the graph is left unchanged.
+	///
+	/// # Panics
+	/// If `order` is not a permutation of the jobs visiting the machine.
+	pub fn fix_machine_sequence(&mut self, machine: Machine, order: &[Job]) -> Result<(), ScheduleError> {
+		let sequence: Vec<usize> = order.iter().map(|&job| self.operation_on(job, machine)
+			.unwrap_or_else(|| panic!("Job #{} does not visit machine {}", job, machine))
+		).collect();
+		let mut sorted = sequence.clone();
+		sorted.sort_unstable();
+		let expected: Vec<usize> = (0..self.num_operations()).filter(|&v| self.operations[v].1 == machine).collect();
+		assert_eq!(sorted, expected, "Order must contain every job visiting machine {} once", machine);
+
+		let previous = self.clear_machine(machine);
+		for pair in sequence.windows(2) {
+			self.machine_next[pair[0]] = Some(pair[1]);
+			self.machine_prev[pair[1]] = Some(pair[0]);
+		}
+		let mut changed = previous.clone();
+		changed.extend(sequence.iter().copied());
+		if self.update(&changed).is_err() {
+			// restore the previous sequence
+			self.clear_machine(machine);
+			for pair in previous.windows(2) {
+				self.machine_next[pair[0]] = Some(pair[1]);
+				self.machine_prev[pair[1]] = Some(pair[0]);
+			}
+			return Err(ScheduleError::CyclicMachineSequence{ machine });
+		}
+		Ok(())
+	}
+
+	/// Returns the fixed order of the jobs on the machine,
+	/// or an empty vector if its order is not fixed and it has at least 2 operations.
+	pub fn machine_sequence(&self, machine: Machine) -> Vec<Job> {
+		let on_machine: Vec<usize> = (0..self.num_operations()).filter(|&v| self.operations[v].1 == machine).collect();
+		if on_machine.len() == 1 {
+			return vec![self.operations[on_machine[0]].0];
+		}
+		let mut sequence = Vec::new();
+		let mut current = on_machine.into_iter().find(|&v| self.machine_prev[v].is_none() && self.machine_next[v].is_some());
+		while let Some(v) = current {
+			sequence.push(self.operations[v].0);
+			current = self.machine_next[v];
+		}
+		sequence
+	}
+
+	/// Removes the fixed order of the machine, if any.
+	pub fn unfix_machine(&mut self, machine: Machine) {
+		let previous = self.clear_machine(machine);
+		self.update(&previous).expect("Removing arcs cannot create a cycle");
+	}
+
+	fn ptime(&self, v: usize) -> Time {
+		self.operations[v].2
+	}
+
+	fn operation_on(&self, job: Job, machine: Machine) -> Option<usize> {
+		let end = self.first_operation.get(job + 1).copied().unwrap_or(self.num_operations());
+		(self.first_operation[job]..end).find(|&v| self.operations[v].1 == machine)
+	}
+
+	fn successors(&self, v: usize) -> impl Iterator<Item = usize> {
+		self.job_next[v].into_iter().chain(self.machine_next[v])
+	}
+
+	fn predecessors(&self, v: usize) -> impl Iterator<Item = usize> {
+		self.job_prev[v].into_iter().chain(self.machine_prev[v])
+	}
+
+	/// Removes the machine arcs of the machine and returns its previous sequence.
+	fn clear_machine(&mut self, machine: Machine) -> Vec<usize> {
+		let mut previous = Vec::new();
+		let mut current = (0..self.num_operations())
+			.find(|&v| self.operations[v].1 == machine && self.machine_prev[v].is_none() && self.machine_next[v].is_some());
+		while let Some(v) = current {
+			previous.push(v);
+			current = self.machine_next[v].take();
+			self.machine_prev[v] = None;
+		}
+		previous
+	}
+
+	/// Recomputes the heads of all operations reachable from `changed`
+	/// and the tails of all operations from which `changed` can be reached.
+	/// Returns an error without modifying heads and tails if the graph contains a cycle.
+	fn update(&mut self, changed: &[usize]) -> Result<(), ()> {
+		let forward = self.topological_closure(changed, |g, v| g.successors(v).collect(), |g, v| g.predecessors(v).collect())?;
+		let backward = self.topological_closure(changed, |g, v| g.predecessors(v).collect(), |g, v| g.successors(v).collect())?;
+		for v in forward {
+			self.heads[v] = self.predecessors(v).map(|u| self.heads[u] + self.ptime(u)).max().unwrap_or(0);
+		}
+		for v in backward {
+			self.tails[v] = self.successors(v).map(|w| self.ptime(w) + self.tails[w]).max().unwrap_or(0);
+		}
+		Ok(())
+	}
+
+	/// Returns all nodes reachable from `start` via `next`,
+	/// in an order in which every node comes after all its reachable nodes of `prev`.
+	fn topological_closure<N, P>(&self, start: &[usize], next: N, prev: P) -> Result<Vec<usize>, ()>
+	where
+		N: Fn(&Self, usize) -> Vec<usize>,
+		P: Fn(&Self, usize) -> Vec<usize>,
+	{
+		let mut reached = vec![false; self.num_operations()];
+		let mut stack: Vec<usize> = start.to_vec();
+		let mut nodes = Vec::new();
+		while let Some(v) = stack.pop() {
+			if !std::mem::replace(&mut reached[v], true) {
+				nodes.push(v);
+				stack.extend(next(self, v));
+			}
+		}
+		// Kahn's algorithm restricted to the reached nodes
+		let mut in_degree = vec![0; self.num_operations()];
+		for &v in nodes.iter() {
+			in_degree[v] = prev(self, v).into_iter().filter(|&u| reached[u]).count();
+		}
+		let mut ready: Vec<usize> = nodes.iter().copied().filter(|&v| in_degree[v] == 0).collect();
+		let mut order = Vec::with_capacity(nodes.len());
+		while let Some(v) = ready.pop() {
+			order.push(v);
+			for w in next(self, v) {
+				in_degree[w] -= 1;
+				if in_degree[w] == 0 {
+					ready.push(w);
+				}
+			}
+		}
+		if order.len() == nodes.len() {
+			Ok(order)
+		} else {
+			Err(())
+		}
+	}
+
+	/// Returns the position of every operation in some topological order of the graph.
+	fn topological_positions(&self) -> Vec<usize> {
+		let all: Vec<usize> = (0..self.num_operations()).collect();
+		let order = self.topological_closure(&all, |g, v| g.successors(v).collect(), |g, v| g.predecessors(v).collect())
+			.expect("Disjunctive graph contains a cycle");
+		let mut positions = vec![0; self.num_operations()];
+		for (i, v) in order.into_iter().enumerate() {
+			positions[v] = i;
+		}
+		positions
+	}
+
+	/// Computes heads and tails of all operations without using the current values.
+	fn compute_from_scratch(&self) -> (Vec<Time>, Vec<Time>) {
+		let all: Vec<usize> = (0..self.num_operations()).collect();
+		let mut heads = vec![0; self.num_operations()];
+		let mut tails = vec![0; self.num_operations()];
+		let forward = self.topological_closure(&all, |g, v| g.successors(v).collect(), |g, v| g.predecessors(v).collect())
+			.expect("Disjunctive graph contains a cycle");
+		for &v in forward.iter() {
+			heads[v] = self.predecessors(v).map(|u| heads[u] + self.ptime(u)).fold(0, max);
+		}
+		for &v in forward.iter().rev() {
+			tails[v] = self.successors(v).map(|w| self.ptime(w) + tails[w]).fold(0, max);
+		}
+		(heads, tails)
+	}
+}
+
+
+/// Rule choosing which operation `giffler_thompson_with_rule` schedules among the conflicting operations on a machine.
+/// Ties are broken in favor of the lowest job id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DispatchRule {
+	/// The job with the most remaining work, including the operation itself
+	MostWorkRemaining,
+	/// The operation with the shortest processing time
+	ShortestProcessingTime,
+	/// The job with the earliest due date
+	EarliestDueDate,
+	/// The operation with the earliest operation due date,
+	/// i.e. the job's due date minus the processing time of its later operations
+	OperationDueDate,
+	/// The operation with the largest apparent tardiness cost index
+	/// `w_j / p * exp(-max(0, d_j - t - R_j) / (k * p_avg))`,
+	/// where `t` is the time at which the operation can start, `R_j` the remaining work of job j
+	/// and `p_avg` the average processing time of the conflicting operations.
+	/// Small values of the look-ahead parameter `k` favor urgent jobs.
+	ApparentTardinessCost{ k: f64 },
+}
+
+
+/// Active schedule for the job shop J||C_max from the Giffler–Thompson algorithm,
+/// scheduling the job with the most remaining work among the conflicting operations.
+/// See `giffler_thompson_with_rule`.
+///
+/// # Arguments
+/// * `instance`: The job shop instance
+///
+/// # Returns
+/// The schedule, with one machine schedule for each machine of the instance.
+pub fn giffler_thompson(instance: &JobShopInstance) -> MultiMachineSchedule {
+	giffler_thompson_with_rule(instance, DispatchRule::MostWorkRemaining)
+}
+
+
+/// Active schedule for the job shop from the Giffler–Thompson algorithm.
+/// In each step, the operation with the earliest possible completion time C is determined.
+/// Among the operations on its machine that could start before C,
+/// the one chosen by the dispatch rule is scheduled.
+/// Takes O(n^2 m) time for n jobs and m machines.
+///
+/// # Arguments
+/// * `instance`: The job shop instance
+/// * `rule`: The dispatch rule. Missing weights count as 1.
+///
+/// # Returns
+/// The schedule, with one machine schedule for each machine of the instance.
+///
+/// # Panics
+/// If the rule uses due dates but the instance has none.
+pub fn giffler_thompson_with_rule(instance: &JobShopInstance, rule: DispatchRule) -> MultiMachineSchedule {
+	let routes = &instance.routes;
+	let due_times = || instance.due_times.as_ref().expect("Dispatch rule needs due dates");
+	let weight = |job: Job| instance.weights.as_ref().map_or(1, |w| w[job]);
+	let mut schedules = vec![MachineSchedule::new(); instance.num_machines()];
+	let mut next_operation = vec![0; routes.len()];
+	let mut job_ready = vec![0; routes.len()];
+	let mut machine_ready = vec![0; schedules.len()];
+	let mut remaining_work: Vec<Time> = routes.iter().map(|route| route.iter().map(|&(_, p)| p).sum()).collect();
+	let earliest_start = |job: Job, next_operation: &[usize], job_ready: &[Time], machine_ready: &[Time]| {
+		let (machine, _) = routes[job][next_operation[job]];
+		max(job_ready[job], machine_ready[machine])
+	};
+	loop {
+		let pending = (0..routes.len()).filter(|&job| next_operation[job] < routes[job].len());
+		let first = pending.min_by_key(|&job| {
+			(earliest_start(job, &next_operation, &job_ready, &machine_ready) + routes[job][next_operation[job]].1, job)
+		});
+		let Some(first) = first else { break; };
+		let (machine, ptime) = routes[first][next_operation[first]];
+		let completion = earliest_start(first, &next_operation, &job_ready, &machine_ready) + ptime;
+		let conflicts: Vec<Job> = (0..routes.len())
+			.filter(|&job| next_operation[job] < routes[job].len() && routes[job][next_operation[job]].0 == machine)
+			.filter(|&job| earliest_start(job, &next_operation, &job_ready, &machine_ready) < completion || job == first)
+			.collect();
+		let ptime_of = |job: Job| routes[job][next_operation[job]].1;
+		let average_ptime = conflicts.iter().map(|&job| ptime_of(job)).sum::<Time>() as f64 / conflicts.len() as f64;
+		// larger is better
+		let priority = |job: Job| -> f64 {
+			match rule {
+				DispatchRule::MostWorkRemaining => remaining_work[job] as f64,
+				DispatchRule::ShortestProcessingTime => -(ptime_of(job) as f64),
+				DispatchRule::EarliestDueDate => -(due_times()[job] as f64),
+				DispatchRule::OperationDueDate => -((due_times()[job] - (remaining_work[job] - ptime_of(job))) as f64),
+				DispatchRule::ApparentTardinessCost{ k } => {
+					let t = earliest_start(job, &next_operation, &job_ready, &machine_ready);
+					let slack = max(0, due_times()[job] - t - remaining_work[job]) as f64;
+					// guard against division by 0 for zero processing times
+					let scale = (k * average_ptime).max(f64::MIN_POSITIVE);
+					weight(job) as f64 / (ptime_of(job) as f64).max(1.0) * (-slack / scale).exp()
+				},
+			}
+		};
+		let job = conflicts.iter().copied()
+			.map(|job| (priority(job), job))
+			.reduce(|best, candidate| if candidate.0 > best.0 { candidate } else { best })
+			.unwrap().1;
+		let time = earliest_start(job, &next_operation, &job_ready, &machine_ready);
+		let duration = ptime_of(job);
+		schedules[machine].schedule.push(JobRun{ time, job, duration });
+		job_ready[job] = time + duration;
+		machine_ready[machine] = time + duration;
+		remaining_work[job] -= duration;
+		next_operation[job] += 1;
+	}
+	MultiMachineSchedule{ machine_schedules: schedules }
+}
+
+
+/// Shifting bottleneck heuristic for the job shop J||C_max.
+/// For every machine whose sequence is not fixed yet, the subproblem 1|r_j|L_max is solved with `carlier`,
+/// where the release and due times of the operations are derived from their heads and tails in the disjunctive graph.
+/// The machine with the largest maximum lateness is the bottleneck and its sequence is fixed.
+/// Afterwards, every previously fixed machine is re-optimized once in the same way,
+/// keeping the new sequence if it does not increase the makespan.
+/// If a sequence from `carlier` would create a cycle, which can happen because the subproblems
+/// do not know about paths between operations through other machines,
+/// the operations are sequenced by their heads instead.
+/// See Adams, Balas, Zawack: "The shifting bottleneck procedure for job shop scheduling" (1988).
+///
+/// # Arguments
+/// * `routes`: `routes[j]` are the operations of job j, as pairs of machine and processing time.
+///
+/// # Returns
+/// The schedule, with one machine schedule for each machine visited by any job.
+///
+/// # Panics
+/// If a processing time is negative or a job visits a machine more than once.
+pub fn shifting_bottleneck(routes: &[Route]) -> MultiMachineSchedule {
+	let num_machines = routes.iter().flatten().map(|&(machine, _)| machine + 1).max().unwrap_or(0);
+	let mut graph = DisjunctiveGraph::new(routes);
+	let mut fixed: Vec<Machine> = Vec::new();
+	while fixed.len() < num_machines {
+		let (bottleneck, sequence) = (0..num_machines)
+			.filter(|machine| !fixed.contains(machine))
+			.map(|machine| {
+				let (lateness, sequence) = bottleneck_subproblem(&graph, machine);
+				(lateness, machine, sequence)
+			})
+			.max_by_key(|&(lateness, machine, _)| (lateness, std::cmp::Reverse(machine)))
+			.map(|(_, machine, sequence)| (machine, sequence))
+			.unwrap();
+		fix_or_sequence_by_heads(&mut graph, bottleneck, &sequence);
+		for &machine in fixed.iter() {
+			let makespan = graph.makespan();
+			let previous = graph.machine_sequence(machine);
+			graph.unfix_machine(machine);
+			let (_, sequence) = bottleneck_subproblem(&graph, machine);
+			if graph.fix_machine_sequence(machine, &sequence).is_err() || graph.makespan() > makespan {
+				graph.fix_machine_sequence(machine, &previous).expect("Restoring a sequence cannot create a cycle");
+			}
+		}
+		fixed.push(bottleneck);
+	}
+	graph.to_schedule(num_machines)
+}
+
+
+/// Solves the 1|r_j|L_max subproblem of an unfixed machine in the shifting bottleneck heuristic.
+/// Returns the maximum lateness and the sequence of jobs.
+fn bottleneck_subproblem(graph: &DisjunctiveGraph, machine: Machine) -> (Time, Vec<Job>) {
+	let makespan = graph.makespan();
+	let operations: Vec<usize> = (0..graph.num_operations())
+		.filter(|&v| graph.operation_details(v).1 == machine)
+		.collect();
+	if operations.is_empty() {
+		return (Time::MIN, Vec::new());
+	}
+	let ptimes: Vec<Time> = operations.iter().map(|&v| graph.operation_details(v).2).collect();
+	let release_times: Vec<Time> = operations.iter().map(|&v| graph.heads()[v]).collect();
+	let due_times: Vec<Time> = operations.iter().map(|&v| makespan - graph.tails()[v]).collect();
+	let schedule = carlier(&ptimes, &release_times, &due_times);
+	let sequence = schedule.job_order().into_iter().map(|i| graph.operation_details(operations[i]).0).collect();
+	(schedule.max_lateness(&due_times), sequence)
+}
+
+
+/// Fixes the sequence of the machine, or if that creates a cycle,
+/// sequences its operations by their heads, which is consistent with all paths of the graph.
+fn fix_or_sequence_by_heads(graph: &mut DisjunctiveGraph, machine: Machine, sequence: &[Job]) {
+	if graph.fix_machine_sequence(machine, sequence).is_ok() {
+		return;
+	}
+	let positions = graph.topological_positions();
+	let mut operations: Vec<usize> = (0..graph.num_operations())
+		.filter(|&v| graph.operation_details(v).1 == machine)
+		.collect();
+	operations.sort_by_key(|&v| (graph.heads()[v], positions[v]));
+	let sequence: Vec<Job> = operations.iter().map(|&v| graph.operation_details(v).0).collect();
+	graph.fix_machine_sequence(machine, &sequence).expect("Sequencing by heads cannot create a cycle");
+}
+
+/// Parameters of `tabu_search`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TabuParams {
+	/// Number of moves to perform
+	pub iterations: usize,
+	/// Number of recent moves whose reversal is forbidden
+	pub tabu_tenure: usize,
+}
+
+impl Default for TabuParams {
+	fn default() -> Self {
+		TabuParams{ iterations: 1000, tabu_tenure: 8 }
+	}
+}
+
+
+/// Tabu search for the job shop J||C_max in the style of Nowicki and Smutnicki.
+/// Starting from `giffler_thompson`, each iteration swaps the first two or the last two operations
+/// of a block of consecutive operations on the same machine of a critical path,
+/// except at the start of the first and the end of the last block, where swaps cannot improve the makespan.
+/// The best move is performed (ties are broken randomly),
+/// unless it would restore one of the last `tabu_tenure` reversed machine arcs without improving on the best schedule found.
+/// If all moves are tabu, a random one is performed.
+/// The search stops early if a critical path has no such moves, in which case the schedule is optimal.
+/// Makespans of moves are evaluated with incremental updates of the `DisjunctiveGraph`.
+/// See Nowicki, Smutnicki: "A fast taboo search algorithm for the job shop problem" (1996).
+///
+/// # Arguments
+/// * `instance`: The job shop instance
+/// * `params`: Iteration budget and tabu list length
+/// * `seed`: Seed for breaking ties, the result is deterministic for a given seed
+///
+/// # Returns
+/// The best schedule found, with one machine schedule for each machine of the instance.
+pub fn tabu_search(instance: &JobShopInstance, params: TabuParams, seed: u64) -> MultiMachineSchedule {
+	tabu_search_recorded(instance, params, seed, None)
+}
+
+
+/// Like `tabu_search`, but also records the makespan of the best schedule found after each iteration.
+/// If the search stops early, there are fewer entries than iterations.
+pub fn tabu_search_tracked(instance: &JobShopInstance, params: TabuParams, seed: u64) -> TrackedRun<MultiMachineSchedule> {
+	let mut best_per_iter = Vec::with_capacity(params.iterations);
+	let result = tabu_search_recorded(instance, params, seed, Some(&mut best_per_iter));
+	TrackedRun{ result, best_per_iter }
+}
+
+
+fn tabu_search_recorded(
+	instance: &JobShopInstance,
+	params: TabuParams,
+	seed: u64,
+	mut recorder: Option<&mut Vec<Time>>
+) -> MultiMachineSchedule
+{
+	let num_machines = instance.num_machines();
+	let mut rng = Rng::new(seed);
+	let mut graph = DisjunctiveGraph::new(&instance.routes);
+	let mut sequences: Vec<Vec<Job>> = giffler_thompson(instance).machine_schedules.iter()
+		.map(|s| s.job_order())
+		.collect();
+	for (machine, sequence) in sequences.iter().enumerate() {
+		graph.fix_machine_sequence(machine, sequence).expect("Feasible schedules have acyclic graphs");
+	}
+	let mut best_makespan = graph.makespan();
+	let mut best = graph.to_schedule(num_machines);
+	// reversed machine arcs (before, after) that must not be restored
+	let mut tabu: VecDeque<(usize, usize)> = VecDeque::with_capacity(params.tabu_tenure + 1);
+	for _ in 0..params.iterations {
+		let moves = block_moves(&graph);
+		if moves.is_empty() {
+			break;
+		}
+		let mut candidates = Vec::new();
+		let mut candidate_makespan = Time::MAX;
+		for &(u, v) in moves.iter() {
+			let (machine, position) = arc_position(&graph, &sequences, u);
+			sequences[machine].swap(position, position + 1);
+			let makespan = graph.fix_machine_sequence(machine, &sequences[machine])
+				.map(|_| graph.makespan())
+				.unwrap_or(Time::MAX);
+			sequences[machine].swap(position, position + 1);
+			graph.fix_machine_sequence(machine, &sequences[machine]).expect("Restoring a sequence cannot create a cycle");
+			let allowed = !tabu.contains(&(v, u)) || makespan < best_makespan;
+			if allowed && makespan <= candidate_makespan && makespan < Time::MAX {
+				if makespan < candidate_makespan {
+					candidates.clear();
+					candidate_makespan = makespan;
+				}
+				candidates.push((u, v));
+			}
+		}
+		let (u, v) = if candidates.is_empty() {
+			moves[rng.below(moves.len())]
+		} else {
+			candidates[rng.below(candidates.len())]
+		};
+		let (machine, position) = arc_position(&graph, &sequences, u);
+		sequences[machine].swap(position, position + 1);
+		if graph.fix_machine_sequence(machine, &sequences[machine]).is_err() {
+			// only possible for a random tabu move, keep the current sequence
+			sequences[machine].swap(position, position + 1);
+		} else {
+			tabu.push_back((u, v));
+			if tabu.len() > params.tabu_tenure {
+				tabu.pop_front();
+			}
+			if graph.makespan() < best_makespan {
+				best_makespan = graph.makespan();
+				best = graph.to_schedule(num_machines);
+			}
+		}
+		if let Some(recorder) = recorder.as_mut() {
+			recorder.push(best_makespan);
+		}
+	}
+	best
+}
+
+
+/// Returns the machine arcs `(u, v)` that `tabu_search` may reverse,
+/// from the blocks of the critical path of the graph.
+fn block_moves(graph: &DisjunctiveGraph) -> Vec<(usize, usize)> {
+	let path = graph.critical_path();
+	let machine = |v: usize| graph.operation_details(v).1;
+	let mut blocks: Vec<&[usize]> = Vec::new();
+	let mut start = 0;
+	for end in 1..=path.len() {
+		if end == path.len() || machine(path[end]) != machine(path[start]) {
+			blocks.push(&path[start..end]);
+			start = end;
+		}
+	}
+	let mut moves = Vec::new();
+	for (i, block) in blocks.iter().enumerate() {
+		if block.len() < 2 {
+			continue;
+		}
+		if i > 0 {
+			moves.push((block[0], block[1]));
+		}
+		if i + 1 < blocks.len() && (block.len() > 2 || i == 0) {
+			moves.push((block[block.len() - 2], block[block.len() - 1]));
+		}
+	}
+	moves
+}
+
+
+/// Returns the machine of the operation and its position in the machine's sequence.
+fn arc_position(graph: &DisjunctiveGraph, sequences: &[Vec<Job>], operation: usize) -> (Machine, usize) {
+	let (job, machine, _) = graph.operation_details(operation);
+	(machine, sequences[machine].iter().position(|&j| j == job).unwrap())
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instances::ft06;
+	use crate::testing::assert_valid_job_shop_schedule;
+	use proptest::prelude::*;
+
+	fn example_routes() -> Vec<Route> {
+		vec![
+			vec![(0, 3), (1, 2), (2, 2)],
+			vec![(0, 2), (2, 1), (1, 4)],
+			vec![(1, 4), (2, 3)],
+		]
+	}
+
+	#[test]
+	fn test_disjunctive_graph_example() {
+		let mut graph = DisjunctiveGraph::new(&example_routes());
+		// without machine sequences, the makespan is the longest job
+		assert_eq!(graph.makespan(), 7);
+		assert_eq!(graph.heads(), &[0, 3, 5, 0, 2, 3, 0, 4]);
+		assert_eq!(graph.tails(), &[4, 2, 0, 5, 4, 0, 3, 0]);
+		graph.fix_machine_sequence(0, &[0, 1]).unwrap();
+		graph.fix_machine_sequence(1, &[2, 0, 1]).unwrap();
+		graph.fix_machine_sequence(2, &[1, 2, 0]).unwrap();
+		// machine 1 processes job 2 in [0, 4], job 0 in [4, 6], job 1 in [6, 10]
+		assert_eq!(graph.makespan(), 11);
+		assert_eq!(graph.heads()[graph.operation(1, 2)], 6);
+		graph.unfix_machine(1);
+		assert_eq!((graph.heads().to_vec(), graph.tails().to_vec()), graph.compute_from_scratch());
+	}
+
+	#[test]
+	fn test_disjunctive_graph_cycle() {
+		let routes = vec![
+			vec![(0, 1), (1, 1)],
+			vec![(1, 1), (0, 1)],
+		];
+		let mut graph = DisjunctiveGraph::new(&routes);
+		graph.fix_machine_sequence(0, &[1, 0]).unwrap();
+		let heads = graph.heads().to_vec();
+		// job 0 would wait for job 1 on machine 0, and job 1 for job 0 on machine 1
+		assert_eq!(
+			graph.fix_machine_sequence(1, &[0, 1]),
+			Err(ScheduleError::CyclicMachineSequence{ machine: 1 })
+		);
+		assert_eq!(graph.heads(), &heads[..]);
+		graph.fix_machine_sequence(1, &[1, 0]).unwrap();
+		assert_eq!(graph.makespan(), 4);
+	}
+
+	#[test]
+	fn test_critical_path() {
+		let mut graph = DisjunctiveGraph::new(&example_routes());
+		graph.fix_machine_sequence(0, &[0, 1]).unwrap();
+		graph.fix_machine_sequence(1, &[2, 0, 1]).unwrap();
+		graph.fix_machine_sequence(2, &[1, 2, 0]).unwrap();
+		let path = graph.critical_path();
+		let length: Time = path.iter().map(|&v| graph.operation_details(v).2).sum();
+		assert_eq!(length, graph.makespan());
+		assert_eq!(graph.heads()[path[0]], 0);
+		let schedule = graph.to_schedule(3);
+		assert_valid_job_shop_schedule(&schedule, &JobShopInstance::new(example_routes()).unwrap());
+		assert_eq!(schedule.makespan(), 11);
+	}
+
+	#[test]
+	fn test_giffler_thompson_ft06() {
+		let instance = ft06();
+		let schedule = giffler_thompson(&instance);
+		assert_valid_job_shop_schedule(&schedule, &instance);
+		assert!(schedule.makespan() >= 55);
+	}
+
+	#[test]
+	fn test_tabu_search_ft06() {
+		let instance = ft06();
+		let params = TabuParams{ iterations: 500, tabu_tenure: 8 };
+		let schedule = tabu_search(&instance, params, 1);
+		assert_valid_job_shop_schedule(&schedule, &instance);
+		assert_eq!(schedule.makespan(), 55);
+		assert_eq!(tabu_search(&instance, params, 1), schedule);
+		let tracked = tabu_search_tracked(&instance, params, 1);
+		assert_eq!(tracked.result, schedule);
+		assert!(tracked.best_per_iter.len() <= 500);
+		assert!(tracked.best_per_iter.windows(2).all(|w| w[1] <= w[0]));
+		assert_eq!(tracked.final_value(), Some(55));
+		assert!(tracked.best_per_iter[0] > 55);
+	}
+
+	#[test]
+	fn test_shifting_bottleneck_ft06() {
+		let instance = ft06();
+		let schedule = shifting_bottleneck(&instance.routes);
+		assert_valid_job_shop_schedule(&schedule, &instance);
+		// the optimal makespan is 55
+		assert_eq!(schedule.makespan(), 58);
+	}
+
+	#[test]
+	fn test_dispatch_rules_tardiness() {
+		// job 0 is short on machine 0 but long on machine 1, job 1 is the other way around and urgent
+		let instance = JobShopInstance::new(vec![
+			vec![(0, 1), (1, 10)],
+			vec![(0, 10), (1, 1)],
+		]).unwrap().with_due_times(vec![100, 11]);
+		let total_tardiness = |schedule: &MultiMachineSchedule| -> Time {
+			schedule.job_shop_tardiness(&instance).iter().map(|&(_, t)| t).sum()
+		};
+		let spt = giffler_thompson_with_rule(&instance, DispatchRule::ShortestProcessingTime);
+		let edd = giffler_thompson_with_rule(&instance, DispatchRule::EarliestDueDate);
+		assert_valid_job_shop_schedule(&spt, &instance);
+		assert_valid_job_shop_schedule(&edd, &instance);
+		// SPT keeps machine 1 busy, EDD starts the urgent job first
+		assert_eq!((spt.makespan(), total_tardiness(&spt)), (12, 1));
+		assert_eq!((edd.makespan(), total_tardiness(&edd)), (21, 0));
+		assert_eq!(spt.job_shop_tardiness(&instance), vec![(0, 0), (1, 1)]);
+		for rule in [DispatchRule::OperationDueDate, DispatchRule::ApparentTardinessCost{ k: 2.0 }] {
+			assert_eq!(giffler_thompson_with_rule(&instance, rule), edd);
+		}
+	}
+
+	/// Random job shop with 3 machines, where every job visits every machine in a random order.
+	fn random_routes() -> impl Strategy<Value = Vec<Route>> {
+		proptest::collection::vec(
+			(Just(vec![0, 1, 2]).prop_shuffle(), proptest::collection::vec(0..10 as Time, 3)).prop_map(
+				|(machines, ptimes)| machines.into_iter().zip(ptimes).collect::<Route>()
+			),
+			1..=5
+		)
+	}
+
+	proptest! {
+		#[test]
+		fn test_incremental_matches_full_recomputation(
+			routes in random_routes(),
+			operations in proptest::collection::vec((0..3usize, any::<bool>(), Just((0..5).collect::<Vec<Job>>()).prop_shuffle()), 1..20)
+		) {
+			let mut graph = DisjunctiveGraph::new(&routes);
+			for (machine, fix, jobs) in operations {
+				if fix {
+					let order: Vec<Job> = jobs.into_iter().filter(|&j| j < routes.len()).collect();
+					let result = graph.fix_machine_sequence(machine, &order);
+					if result.is_err() {
+						prop_assert_eq!(result, Err(ScheduleError::CyclicMachineSequence{ machine }));
+					}
+				} else {
+					graph.unfix_machine(machine);
+				}
+				let (heads, tails) = graph.compute_from_scratch();
+				prop_assert_eq!(graph.heads(), &heads[..]);
+				prop_assert_eq!(graph.tails(), &tails[..]);
+			}
+		}
+
+		#[test]
+		fn test_tabu_search_valid(routes in random_routes(), seed in any::<u64>()) {
+			let instance = JobShopInstance::new(routes).unwrap();
+			let initial = giffler_thompson(&instance);
+			assert_valid_job_shop_schedule(&initial, &instance);
+			let schedule = tabu_search(&instance, TabuParams{ iterations: 50, tabu_tenure: 4 }, seed);
+			assert_valid_job_shop_schedule(&schedule, &instance);
+			prop_assert!(schedule.makespan() <= initial.makespan());
+		}
+
+		#[test]
+		fn test_dispatch_rules_valid(
+			routes in random_routes(),
+			due_times in proptest::collection::vec(0..60 as Time, 5),
+			k in 0.1..5.0f64
+		) {
+			let n = routes.len();
+			let instance = JobShopInstance::new(routes).unwrap().with_due_times(due_times[..n].to_vec());
+			for rule in [
+				DispatchRule::MostWorkRemaining,
+				DispatchRule::ShortestProcessingTime,
+				DispatchRule::EarliestDueDate,
+				DispatchRule::OperationDueDate,
+				DispatchRule::ApparentTardinessCost{ k },
+			] {
+				assert_valid_job_shop_schedule(&giffler_thompson_with_rule(&instance, rule), &instance);
+			}
+		}
+
+		#[test]
+		fn test_shifting_bottleneck_valid(routes in random_routes()) {
+			let instance = JobShopInstance::new(routes).unwrap();
+			let schedule = shifting_bottleneck(&instance.routes);
+			assert_valid_job_shop_schedule(&schedule, &instance);
+			let lower_bound = instance.ptimes().iter().map(|p| p.iter().sum::<Time>())
+				.chain(instance.routes.iter().map(|route| route.iter().map(|&(_, p)| p).sum()))
+				.max()
+				.unwrap_or(0);
+			prop_assert!(schedule.makespan() >= lower_bound);
+		}
+	}
+}
@@ -0,0 +1,306 @@
+use std::fmt::Write;
+
+use crate::{Time, ScheduleError};
+
+
+/// Minimal JSON reader and writer for the exchange formats of instances and schedules,
+/// so that they do not depend on the optional `serde` feature.
+/// Numbers must be integers, since all values of the formats are times or job indices.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+	Null,
+	Bool(bool),
+	Int(i128),
+	Str(String),
+	Array(Vec<Value>),
+	Object(Vec<(String, Value)>),
+}
+
+impl Value {
+	/// Parses a complete JSON document.
+	pub(crate) fn parse(text: &str) -> Result<Value, ScheduleError> {
+		let mut parser = Parser{ bytes: text.as_bytes(), pos: 0, depth: 0 };
+		let value = parser.value()?;
+		parser.skip_whitespace();
+		if parser.pos < parser.bytes.len() {
+			return Err(parser.error("trailing characters"));
+		}
+		Ok(value)
+	}
+
+	/// Returns the fields of an object, or an error mentioning `what`.
+	pub(crate) fn as_object(&self, what: &str) -> Result<&[(String, Value)], ScheduleError> {
+		match self {
+			Value::Object(fields) => Ok(fields),
+			_ => Err(invalid(format!("{} must be an object", what))),
+		}
+	}
+
+	/// Returns the value as a time, or an error mentioning `what`.
+	pub(crate) fn as_time(&self, what: &str) -> Result<Time, ScheduleError> {
+		match self {
+			Value::Int(x) => Time::try_from(*x).map_err(|_| invalid(format!("{} is out of range", what))),
+			_ => Err(invalid(format!("{} must be an integer", what))),
+		}
+	}
+
+	/// Returns the value as an array of times, or an error mentioning `what`.
+	pub(crate) fn as_times(&self, what: &str) -> Result<Vec<Time>, ScheduleError> {
+		match self {
+			Value::Array(values) => values.iter().map(|v| v.as_time(what)).collect(),
+			_ => Err(invalid(format!("{} must be an array", what))),
+		}
+	}
+}
+
+
+/// Returns the fields `keys` of an object, in this order, or an error if the object has any other field.
+/// Fields that are missing are None.
+pub(crate) fn fields<'a, const N: usize>(
+	object: &'a [(String, Value)],
+	keys: [&str; N],
+	what: &str
+) -> Result<[Option<&'a Value>; N], ScheduleError>
+{
+	let mut result = [None; N];
+	for (key, value) in object.iter() {
+		let Some(i) = keys.iter().position(|k| k == key) else {
+			return Err(invalid(format!("unknown field \"{}\" in {}", key, what)));
+		};
+		if result[i].is_some() {
+			return Err(invalid(format!("duplicate field \"{}\" in {}", key, what)));
+		}
+		result[i] = Some(value);
+	}
+	Ok(result)
+}
+
+/// Returns the error for a document that does not match the format.
+pub(crate) fn invalid(message: String) -> ScheduleError {
+	ScheduleError::InvalidJson{ message }
+}
+
+/// Appends `text` as a JSON string literal to `out`.
+#[cfg(any(test, feature = "wasm"))]
+pub(crate) fn write_string(out: &mut String, text: &str) {
+	out.push('"');
+	for c in text.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\t' => out.push_str("\\t"),
+			'\r' => out.push_str("\\r"),
+			c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+}
+
+/// Appends `[t0, t1, ...]` to `out`.
+pub(crate) fn write_times(out: &mut String, times: &[Time]) {
+	out.push('[');
+	for (i, t) in times.iter().enumerate() {
+		if i > 0 {
+			out.push_str(", ");
+		}
+		write!(out, "{}", t).unwrap();
+	}
+	out.push(']');
+}
+
+
+/// The maximum number of nested arrays and objects, far above the nesting of the exchange formats,
+/// so that malicious input cannot overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+	/// The number of arrays and objects containing the current position.
+	depth: usize,
+}
+
+impl Parser<'_> {
+	fn error(&self, message: &str) -> ScheduleError {
+		invalid(format!("{} at byte {}", message, self.pos))
+	}
+
+	fn skip_whitespace(&mut self) {
+		while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+			self.pos += 1;
+		}
+	}
+
+	fn peek(&mut self) -> Option<u8> {
+		self.skip_whitespace();
+		self.bytes.get(self.pos).copied()
+	}
+
+	fn expect(&mut self, c: u8) -> Result<(), ScheduleError> {
+		if self.peek() == Some(c) {
+			self.pos += 1;
+			Ok(())
+		} else {
+			Err(self.error(&format!("expected '{}'", c as char)))
+		}
+	}
+
+	fn keyword(&mut self, word: &str, value: Value) -> Result<Value, ScheduleError> {
+		if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+			self.pos += word.len();
+			Ok(value)
+		} else {
+			Err(self.error("invalid literal"))
+		}
+	}
+
+	fn value(&mut self) -> Result<Value, ScheduleError> {
+		match self.peek() {
+			Some(b'{' | b'[') if self.depth == MAX_DEPTH => Err(self.error("nesting too deep")),
+			Some(b'{') => self.nested(Self::object),
+			Some(b'[') => self.nested(Self::array),
+			Some(b'"') => self.string().map(Value::Str),
+			Some(b'-' | b'0'..=b'9') => self.number(),
+			Some(b'n') => self.keyword("null", Value::Null),
+			Some(b't') => self.keyword("true", Value::Bool(true)),
+			Some(b'f') => self.keyword("false", Value::Bool(false)),
+			Some(_) => Err(self.error("unexpected character")),
+			None => Err(self.error("unexpected end of input")),
+		}
+	}
+
+	fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, ScheduleError>) -> Result<Value, ScheduleError> {
+		self.depth += 1;
+		let result = parse(self);
+		self.depth -= 1;
+		result
+	}
+
+	fn object(&mut self) -> Result<Value, ScheduleError> {
+		self.expect(b'{')?;
+		let mut fields = Vec::new();
+		if self.peek() == Some(b'}') {
+			self.pos += 1;
+			return Ok(Value::Object(fields));
+		}
+		loop {
+			if self.peek() != Some(b'"') {
+				return Err(self.error("expected field name"));
+			}
+			let key = self.string()?;
+			self.expect(b':')?;
+			fields.push((key, self.value()?));
+			match self.peek() {
+				Some(b',') => self.pos += 1,
+				Some(b'}') => {
+					self.pos += 1;
+					return Ok(Value::Object(fields));
+				},
+				_ => return Err(self.error("expected ',' or '}'")),
+			}
+		}
+	}
+
+	fn array(&mut self) -> Result<Value, ScheduleError> {
+		self.expect(b'[')?;
+		let mut values = Vec::new();
+		if self.peek() == Some(b']') {
+			self.pos += 1;
+			return Ok(Value::Array(values));
+		}
+		loop {
+			values.push(self.value()?);
+			match self.peek() {
+				Some(b',') => self.pos += 1,
+				Some(b']') => {
+					self.pos += 1;
+					return Ok(Value::Array(values));
+				},
+				_ => return Err(self.error("expected ',' or ']'")),
+			}
+		}
+	}
+
+	fn string(&mut self) -> Result<String, ScheduleError> {
+		self.expect(b'"')?;
+		let mut result = String::new();
+		loop {
+			let start = self.pos;
+			while self.pos < self.bytes.len() && !matches!(self.bytes[self.pos], b'"' | b'\\') {
+				self.pos += 1;
+			}
+			// the input is a str and we only stop at ASCII characters, so this is valid UTF-8
+			result.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+			match self.bytes.get(self.pos) {
+				Some(b'"') => {
+					self.pos += 1;
+					return Ok(result);
+				},
+				Some(b'\\') => {
+					let escaped = match self.bytes.get(self.pos + 1) {
+						Some(b'"') => '"',
+						Some(b'\\') => '\\',
+						Some(b'/') => '/',
+						Some(b'n') => '\n',
+						Some(b't') => '\t',
+						Some(b'r') => '\r',
+						Some(b'b') => '\u{8}',
+						Some(b'f') => '\u{c}',
+						_ => return Err(self.error("unsupported escape sequence")),
+					};
+					result.push(escaped);
+					self.pos += 2;
+				},
+				_ => return Err(self.error("unterminated string")),
+			}
+		}
+	}
+
+	fn number(&mut self) -> Result<Value, ScheduleError> {
+		let start = self.pos;
+		if self.bytes[self.pos] == b'-' {
+			self.pos += 1;
+		}
+		while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_digit() {
+			self.pos += 1;
+		}
+		if matches!(self.bytes.get(self.pos), Some(b'.' | b'e' | b'E')) {
+			return Err(self.error("numbers must be integers"));
+		}
+		std::str::from_utf8(&self.bytes[start..self.pos]).unwrap()
+			.parse()
+			.map(Value::Int)
+			.map_err(|_| self.error("invalid number"))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		let value = Value::parse(" {\"a\": [1, -2, {}], \"b\\n\": null, \"c\": [true, false, \"x\"]} ").unwrap();
+		assert_eq!(value, Value::Object(vec![
+			("a".to_string(), Value::Array(vec![Value::Int(1), Value::Int(-2), Value::Object(vec![])])),
+			("b\n".to_string(), Value::Null),
+			("c".to_string(), Value::Array(vec![Value::Bool(true), Value::Bool(false), Value::Str("x".to_string())])),
+		]));
+		assert!(Value::parse("[1, 2").is_err());
+		assert!(Value::parse("[1.5]").is_err());
+		assert!(Value::parse("[1] 2").is_err());
+		assert!(Value::parse("{\"a\" 1}").is_err());
+	}
+
+	#[test]
+	fn test_write_string() {
+		let text = "a \"quoted\" \\ line\n";
+		let mut out = String::new();
+		write_string(&mut out, text);
+		assert_eq!(out, "\"a \\\"quoted\\\" \\\\ line\\n\"");
+		assert_eq!(Value::parse(&out).unwrap(), Value::Str(text.to_string()));
+	}
+}
@@ -2,8 +2,53 @@ pub type Time = isize; // allowing negative times can be useful occasionally
 pub type Job = usize; // jobs are ids
 pub type Machine = usize; // machines are ids
 
+pub mod error;
+pub use error::ScheduleError;
 pub mod schedule;
 pub use schedule::*;
+pub mod schedule_index;
+pub use schedule_index::ScheduleIndex;
+pub mod audit;
+pub mod evaluate;
+pub mod repair;
+pub mod explain;
+pub mod display;
+pub use display::{DisplayOptions, TimeFormat};
+pub mod objective;
+pub mod cost;
+pub mod instances;
+pub use instances::{SingleMachineInstance, JobShopInstance};
+pub mod job_set;
+pub use job_set::{JobSet, JobData};
+pub mod generators;
+pub mod experiments;
+pub mod tracking;
+pub mod event_clock;
+pub use event_clock::EventClock;
+pub mod calendar;
+pub use calendar::Calendar;
 pub mod single_machine;
+pub mod identical_machines;
+pub mod uniform_machines;
 pub mod unrelated_machines;
-pub mod flow_shop;
\ No newline at end of file
+pub mod interval;
+pub mod periodic;
+pub mod resources;
+pub mod flow_shop;
+pub mod hybrid_flow_shop;
+pub mod open_shop;
+pub mod job_shop;
+pub mod precedence;
+pub mod reference;
+#[cfg(feature = "benchdata")]
+pub mod benchdata;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod rng;
+mod json;
+mod fingerprint;
+mod flow;
+#[cfg(any(test, feature = "proptest-support"))]
+pub mod testing;
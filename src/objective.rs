@@ -0,0 +1,121 @@
+use crate::{Time, Job, MachineSchedule, MultiMachineSchedule};
+
+use std::cmp::max;
+
+
+/// An objective function that depends only on the completion times of the jobs.
+/// Smaller values are better.
+pub trait Objective {
+	/// Returns the objective value.
+	///
+	/// # Arguments
+	/// * `completion_times`: Every job together with its completion time.
+	/// * `due_times`: `due_times[i]` is the due time of job `i`.
+	fn evaluate(&self, completion_times: &[(Job, Time)], due_times: &[Time]) -> Time;
+
+	/// Returns the objective value of the given single machine schedule.
+	fn evaluate_schedule(&self, schedule: &MachineSchedule, due_times: &[Time]) -> Time {
+		self.evaluate(&schedule.completion_times(), due_times)
+	}
+
+	/// Returns the objective value of the given multi-machine schedule.
+	/// Jobs processed on several machines complete with their last run.
+	fn evaluate_multi_schedule(&self, schedule: &MultiMachineSchedule, due_times: &[Time]) -> Time {
+		self.evaluate(&schedule.completion_times(), due_times)
+	}
+}
+
+
+/// The maximum completion time C_max
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Makespan;
+
+impl Objective for Makespan {
+	fn evaluate(&self, completion_times: &[(Job, Time)], _due_times: &[Time]) -> Time {
+		completion_times.iter().map(|&(_, c)| c).max().unwrap_or(0)
+	}
+}
+
+
+/// The maximum lateness L_max.
+/// The maximum lateness of an empty schedule is `Time::MIN`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MaxLateness;
+
+impl Objective for MaxLateness {
+	fn evaluate(&self, completion_times: &[(Job, Time)], due_times: &[Time]) -> Time {
+		completion_times.iter().map(|&(j, c)| c - due_times[j]).max().unwrap_or(Time::MIN)
+	}
+}
+
+
+/// The total tardiness sum T_j
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TotalTardiness;
+
+impl Objective for TotalTardiness {
+	fn evaluate(&self, completion_times: &[(Job, Time)], due_times: &[Time]) -> Time {
+		completion_times.iter().map(|&(j, c)| max(0, c - due_times[j])).sum()
+	}
+}
+
+
+/// The number of tardy jobs sum U_j
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NumTardy;
+
+impl Objective for NumTardy {
+	fn evaluate(&self, completion_times: &[(Job, Time)], due_times: &[Time]) -> Time {
+		completion_times.iter().filter(|&&(j, c)| c > due_times[j]).count() as Time
+	}
+}
+
+
+/// The total completion time sum C_j
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TotalCompletionTime;
+
+impl Objective for TotalCompletionTime {
+	fn evaluate(&self, completion_times: &[(Job, Time)], _due_times: &[Time]) -> Time {
+		completion_times.iter().map(|&(_, c)| c).sum()
+	}
+}
+
+
+/// The total weighted completion time sum w_j C_j,
+/// where the contained vector holds the weight of each job.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WeightedCompletionTime(pub Vec<Time>);
+
+impl Objective for WeightedCompletionTime {
+	fn evaluate(&self, completion_times: &[(Job, Time)], _due_times: &[Time]) -> Time {
+		completion_times.iter().map(|&(j, c)| self.0[j] * c).sum()
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn example_schedule() -> MachineSchedule {
+		MachineSchedule::from_ptimes_releasetimes(
+			&[ 5,  6,  7,  3,  6,  2],
+			&[10, 13, 11, 30,  0, 30]
+		)
+	}
+
+	#[test]
+	fn test_objectives_match_schedule_metrics() {
+		let schedule = example_schedule();
+		let due_times = vec![19, 20, 24, 35, 17, 38];
+		assert_eq!(Makespan.evaluate_schedule(&schedule, &due_times), schedule.makespan());
+		assert_eq!(MaxLateness.evaluate_schedule(&schedule, &due_times), schedule.max_lateness(&due_times));
+		assert_eq!(NumTardy.evaluate_schedule(&schedule, &due_times), schedule.num_tardy(&due_times) as Time);
+		// completion times are 15, 21, 28, 33, 39, 41
+		assert_eq!(TotalCompletionTime.evaluate_schedule(&schedule, &due_times), 177);
+		assert_eq!(TotalTardiness.evaluate_schedule(&schedule, &due_times), 1 + 4 + 22 + 3);
+		let weights = WeightedCompletionTime(vec![1, 0, 0, 0, 0, 2]);
+		assert_eq!(weights.evaluate_schedule(&schedule, &due_times), 15 + 2*41);
+	}
+}
@@ -0,0 +1,228 @@
+use std::cmp::max;
+
+use crate::{Time, Job, Machine, MachineSchedule, MultiMachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+
+
+/// A schedule for an open shop together with its makespan and a lower bound on the optimal makespan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenShopSolution {
+	/// The schedule, with one machine schedule for each machine
+	pub schedule: MultiMachineSchedule,
+	/// The makespan of `schedule`
+	pub makespan: Time,
+	/// The lower bound from `lower_bound`
+	pub lower_bound: Time,
+	/// `makespan / lower_bound`, or 1 if the lower bound is 0.
+	/// The makespan is at most this factor away from the optimum.
+	pub ratio: f64,
+}
+
+impl OpenShopSolution {
+	fn new(schedule: MultiMachineSchedule, ptimes: &[Vec<Time>]) -> OpenShopSolution {
+		let makespan = schedule.makespan();
+		let lower_bound = lower_bound(ptimes);
+		let ratio = if lower_bound == 0 { 1.0 } else { makespan as f64 / lower_bound as f64 };
+		OpenShopSolution{ schedule, makespan, lower_bound, ratio }
+	}
+}
+
+
+/// Lower bound on the optimal makespan of the open shop O||C_max:
+/// No machine can complete before it has processed all jobs,
+/// and no job can be completed before it has been processed by all machines.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// The maximum of the largest machine load and the largest total processing time of a job.
+pub fn lower_bound(ptimes: &[Vec<Time>]) -> Time {
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let machine_load = ptimes.iter().map(|p| p.iter().sum()).max().unwrap_or(0);
+	let job_load = (0..n).map(|j| ptimes.iter().map(|p| p[j]).sum()).max().unwrap_or(0);
+	max(machine_load, job_load)
+}
+
+
+/// Dense schedule for the open shop O||C_max, where every job has to be processed once by every machine
+/// in any order, but not by two machines at the same time.
+/// Whenever a machine becomes available, it starts the job with the largest remaining total processing time
+/// among the jobs it still has to process and that are not being processed by another machine.
+/// No machine is idle while it could process a job, so the makespan is less than twice the optimum.
+/// Takes O(n^2 m^2) time for n jobs and m machines.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// The schedule together with its makespan and lower bound.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn dense_schedule(ptimes: &[Vec<Time>]) -> OpenShopSolution {
+	let schedule = greedy_schedule(ptimes, |job, _, remaining: &[Vec<bool>]| {
+		(0..ptimes.len()).filter(|&i| remaining[i][job]).map(|i| ptimes[i][job]).sum::<Time>()
+	});
+	OpenShopSolution::new(schedule, ptimes)
+}
+
+
+/// The Longest Alternate Processing Time first rule, which solves the two-machine open shop O2||C_max optimally.
+/// Whenever a machine becomes available, it starts the job with the longest processing time on the other machine
+/// among the jobs that have not been processed on the other machine yet.
+/// Jobs that have already been processed on the other machine have the lowest priority.
+/// The optimal makespan always equals `lower_bound`.
+/// Takes O(n^2) time for n jobs.
+/// See Pinedo: "Scheduling: Theory, Algorithms, and Systems", theorem 8.1.1.
+///
+/// # Arguments
+/// * ptimes: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// The optimal schedule together with its makespan and lower bound.
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or a processing time is negative.
+pub fn lapt(ptimes: &[Vec<Time>]) -> OpenShopSolution {
+	assert!(ptimes.len() == 2, "Instance must have exactly 2 machines");
+	let schedule = greedy_schedule(ptimes, |job, machine, remaining: &[Vec<bool>]| {
+		let other = 1 - machine;
+		(remaining[other][job], ptimes[other][job])
+	});
+	OpenShopSolution::new(schedule, ptimes)
+}
+
+
+/// Non-delay list scheduling for open shops: the machine that becomes available first
+/// starts the available job with the highest priority (the smallest job id in case of ties).
+/// `priority(job, machine, remaining)` gets `remaining[i][j]`, which tells whether machine i still has to process job j.
+fn greedy_schedule<F, K>(ptimes: &[Vec<Time>], priority: F) -> MultiMachineSchedule
+where
+	F: Fn(Job, Machine, &[Vec<bool>]) -> K,
+	K: Ord,
+{
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	let m = ptimes.len();
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let mut remaining = vec![vec![true; n]; m];
+	let mut schedules = vec![MachineSchedule::new(); m];
+	// time at which each machine is available again, or None if it is done
+	let mut available: Vec<Option<Time>> = vec![Some(0); m];
+	while let Some(machine) = (0..m).filter(|&i| available[i].is_some()).min_by_key(|&i| available[i]) {
+		let t = available[machine].unwrap();
+		// the job another machine is processing at time t
+		let busy = |job: Job| (0..m).any(|i| i != machine && schedules[i].schedule.last()
+			.is_some_and(|run| run.job == job && run.time + run.duration > t)
+		);
+		let job = (0..n)
+			.filter(|&j| remaining[machine][j] && !busy(j))
+			.max_by_key(|&j| (priority(j, machine, &remaining), std::cmp::Reverse(j)));
+		match job {
+			Some(job) => {
+				schedules[machine].schedule.push(JobRun{ time: t, job, duration: ptimes[machine][job] });
+				remaining[machine][job] = false;
+				available[machine] = Some(t + ptimes[machine][job]);
+			},
+			None => {
+				// wait until another machine completes a job this machine still has to process
+				available[machine] = (0..m)
+					.filter(|&i| i != machine)
+					.filter_map(|i| schedules[i].schedule.last())
+					.filter(|run| remaining[machine][run.job] && run.time + run.duration > t)
+					.map(|run| run.time + run.duration)
+					.min();
+			}
+		}
+	}
+	MultiMachineSchedule{ machine_schedules: schedules }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::flow_shop_ptimes;
+	use proptest::prelude::*;
+
+	/// Panics if some job is not processed exactly once by every machine,
+	/// or is processed by two machines at the same time.
+	fn assert_valid_open_shop(schedule: &MultiMachineSchedule, ptimes: &[Vec<Time>]) {
+		let n = ptimes.first().map_or(0, |p| p.len());
+		let mut runs_of_job = vec![Vec::new(); n];
+		for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+			let mut previous_end = Time::MIN;
+			let mut jobs: Vec<Job> = Vec::new();
+			for run in s.schedule.iter() {
+				assert_eq!(run.duration, ptimes[machine][run.job]);
+				assert!(run.time >= previous_end, "Runs overlap on machine {}", machine);
+				previous_end = run.time + run.duration;
+				jobs.push(run.job);
+				runs_of_job[run.job].push((run.time, run.time + run.duration));
+			}
+			jobs.sort_unstable();
+			assert_eq!(jobs, (0..n).collect::<Vec<Job>>());
+		}
+		for (job, mut runs) in runs_of_job.into_iter().enumerate() {
+			runs.sort_unstable();
+			for pair in runs.windows(2) {
+				assert!(pair[1].0 >= pair[0].1, "Job #{} is processed by two machines at the same time", job);
+			}
+		}
+	}
+
+	#[test]
+	fn test_lower_bound() {
+		let ptimes = vec![
+			vec![3, 2, 4],
+			vec![1, 8, 1],
+		];
+		// the load of machine 0 is 9, job 1 needs 10 time units
+		assert_eq!(lower_bound(&ptimes), 10);
+		assert_eq!(lower_bound(&[]), 0);
+	}
+
+	#[test]
+	fn test_lapt_example() {
+		let ptimes = vec![
+			vec![3, 2, 4],
+			vec![1, 8, 1],
+		];
+		let solution = lapt(&ptimes);
+		assert_valid_open_shop(&solution.schedule, &ptimes);
+		assert_eq!(solution.makespan, 10);
+		assert_eq!(solution.ratio, 1.0);
+	}
+
+	#[test]
+	fn test_dense_schedule_example() {
+		let ptimes = vec![
+			vec![3, 2, 4, 1],
+			vec![1, 8, 1, 2],
+			vec![5, 1, 2, 2],
+		];
+		let solution = dense_schedule(&ptimes);
+		assert_valid_open_shop(&solution.schedule, &ptimes);
+		// the load of machine 1 is 12
+		assert_eq!(solution.lower_bound, 12);
+		assert!(solution.ratio >= 1.0 && solution.ratio < 2.0);
+	}
+
+	proptest! {
+		#[test]
+		fn test_lapt_optimal(ptimes in flow_shop_ptimes(2..=2, 0..=10, 20)) {
+			let solution = lapt(&ptimes);
+			assert_valid_open_shop(&solution.schedule, &ptimes);
+			prop_assert_eq!(solution.makespan, solution.lower_bound);
+			prop_assert_eq!(solution.ratio, 1.0);
+		}
+
+		#[test]
+		fn test_dense_schedule_ratio(ptimes in flow_shop_ptimes(1..=4, 0..=8, 20)) {
+			let solution = dense_schedule(&ptimes);
+			assert_valid_open_shop(&solution.schedule, &ptimes);
+			prop_assert!(solution.makespan >= solution.lower_bound);
+			prop_assert!(solution.ratio < 2.0);
+		}
+	}
+}
@@ -0,0 +1,417 @@
+use std::cmp::{max, min, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::{Time, Job, JobRun, MachineSchedule, EventClock};
+use crate::schedule::assert_valid_ptimes;
+
+
+/// A task that releases a job with processing time `wcet` at every multiple of `period`,
+/// where each job is due `deadline` time units after its release.
+/// The first job is released at time 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeriodicTask {
+	/// The (worst-case) processing time of every job of the task
+	pub wcet: Time,
+	/// The time between the releases of two consecutive jobs of the task
+	pub period: Time,
+	/// The relative deadline of every job of the task, which may differ from the period
+	pub deadline: Time,
+}
+
+
+/// A point in time at which the jobs that must be completed by then need more processing time than available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DemandViolation {
+	/// The time, which is an absolute deadline of a job
+	pub time: Time,
+	/// The total processing time of the jobs released at or after 0 and due at or before `time`
+	pub demand: Time,
+}
+
+
+/// The result of `edf_analysis`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulabilityReport {
+	/// The total utilization of the tasks, i.e. the sum of `wcet / period`
+	pub utilization: f64,
+	/// The earliest time at which the demand bound function exceeds the available time, if any
+	pub first_violation: Option<DemandViolation>,
+}
+
+impl SchedulabilityReport {
+	/// Returns whether preemptive EDF meets every deadline of the tasks.
+	pub fn is_schedulable(&self) -> bool {
+		self.first_violation.is_none()
+	}
+}
+
+
+/// Returns the least common multiple of the periods of the tasks, or None if it does not fit into `Time`.
+/// Returns 1 if there are no tasks.
+///
+/// # Panics
+/// If a period is not positive.
+pub fn hyperperiod(tasks: &[PeriodicTask]) -> Option<Time> {
+	hyperperiod_i128(tasks).and_then(|h| Time::try_from(h).ok())
+}
+
+
+fn hyperperiod_i128(tasks: &[PeriodicTask]) -> Option<i128> {
+	tasks.iter().try_fold(1i128, |h, task| {
+		assert!(task.period > 0, "Period {} is not positive", task.period);
+		let period = task.period as i128;
+		(h / gcd(h, period)).checked_mul(period)
+	})
+}
+
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+
+fn ceil_div(a: i128, b: i128) -> i128 {
+	a.div_euclid(b) + if a.rem_euclid(b) != 0 { 1 } else { 0 }
+}
+
+
+/// Exact EDF schedulability test for periodic tasks with synchronous releases on a single machine with preemptions,
+/// based on the demand bound function dbf(t), the total processing time of the jobs due at or before t.
+/// The tasks are schedulable if and only if dbf(t) <= t for every t,
+/// which only needs to be checked at the absolute deadlines up to the bound of Baruah, Rosier, Howell (1990),
+/// i.e. up to the hyperperiod plus the largest deadline, or less if the utilization is below 1.
+/// If the utilization exceeds 1, dbf(t) > t for some deadline t, which is found in the same way.
+/// Runs in pseudo-polynomial time, as every deadline up to the bound is checked.
+///
+/// See Baruah, Rosier, Howell: "Algorithms and complexity concerning the preemptive scheduling
+/// of periodic, real-time tasks on one processor" (1990)
+///
+/// # Arguments
+/// * `tasks`: The periodic tasks
+///
+/// # Panics
+/// If a processing time is negative, or a period or deadline is not positive,
+/// or the hyperperiod of the tasks does not fit into 128 bits.
+pub fn edf_analysis(tasks: &[PeriodicTask]) -> SchedulabilityReport {
+	for task in tasks {
+		assert!(task.wcet >= 0, "Processing time {} is negative", task.wcet);
+		assert!(task.deadline > 0, "Deadline {} is not positive", task.deadline);
+	}
+	let utilization = tasks.iter().map(|task| task.wcet as f64 / task.period as f64).sum();
+	let bound = demand_check_bound(tasks);
+	let mut deadlines: BinaryHeap<Reverse<(Time, usize)>> = tasks.iter().enumerate()
+		.filter(|(_, task)| task.wcet > 0 && task.deadline <= bound)
+		.map(|(i, task)| Reverse((task.deadline, i)))
+		.collect();
+	let mut demand: Time = 0;
+	let mut first_violation = None;
+	while let Some(Reverse((t, i))) = deadlines.pop() {
+		demand += tasks[i].wcet;
+		if let Some(next) = t.checked_add(tasks[i].period).filter(|&next| next <= bound) {
+			deadlines.push(Reverse((next, i)));
+		}
+		let more_at_t = deadlines.peek().is_some_and(|&Reverse((next, _))| next == t);
+		if !more_at_t && demand > t {
+			first_violation = Some(DemandViolation{ time: t, demand });
+			break;
+		}
+	}
+	SchedulabilityReport{ utilization, first_violation }
+}
+
+
+/// Returns a time such that dbf(t) > t for some t if and only if this holds for some t up to the returned time.
+fn demand_check_bound(tasks: &[PeriodicTask]) -> Time {
+	let h = hyperperiod_i128(tasks).expect("The hyperperiod of the tasks does not fit into 128 bits");
+	let max_deadline = tasks.iter().map(|task| task.deadline as i128).max().unwrap_or(0);
+	// all utilizations are scaled by the hyperperiod to keep them integral
+	let scaled = |task: &PeriodicTask| task.wcet as i128 * (h / task.period as i128);
+	let work: i128 = tasks.iter().map(scaled).sum();
+	let bound = if work < h {
+		let slack: i128 = tasks.iter().map(|task| (task.period - task.deadline) as i128 * scaled(task)).sum();
+		min(h + max_deadline, max(max_deadline, ceil_div(slack, h - work)))
+	} else if work == h {
+		h + max_deadline
+	} else {
+		// dbf(t) > utilization * t - sum of utilization * deadline
+		let offset: i128 = tasks.iter().map(|task| task.deadline as i128 * scaled(task)).sum();
+		max(max_deadline, ceil_div(offset, work - h))
+	};
+	min(bound, Time::MAX as i128) as Time
+}
+
+
+/// Returns the task and the index of the instance within its task of every job of `unroll(tasks, horizon)`.
+/// The jobs are ordered by task and then by instance.
+///
+/// # Panics
+/// If a period is not positive.
+pub fn instances(tasks: &[PeriodicTask], horizon: Time) -> Vec<(usize, usize)> {
+	let mut result = Vec::new();
+	for (i, task) in tasks.iter().enumerate() {
+		assert!(task.period > 0, "Period {} is not positive", task.period);
+		let mut release: Time = 0;
+		let mut instance = 0;
+		while release < horizon {
+			result.push((i, instance));
+			instance += 1;
+			release = match release.checked_add(task.period) {
+				Some(next) => next,
+				None => break,
+			};
+		}
+	}
+	result
+}
+
+
+/// Expands the jobs of the tasks released before `horizon` into processing times, release times and due times,
+/// e.g. to schedule them with `edd_preemptive`.
+/// Job j is the instance `instances(tasks, horizon)[j]`, so the jobs are ordered by task and then by instance.
+/// To check every deadline of synchronous tasks, unroll them over the hyperperiod plus the largest deadline.
+///
+/// # Arguments
+/// * `tasks`: The periodic tasks
+/// * `horizon`: Only jobs released before this time are included
+///
+/// # Returns
+/// The processing times, release times, and due times of the jobs.
+///
+/// # Panics
+/// If a period is not positive.
+pub fn unroll(tasks: &[PeriodicTask], horizon: Time) -> (Vec<Time>, Vec<Time>, Vec<Time>) {
+	let jobs = instances(tasks, horizon);
+	let release = |&(i, instance): &(usize, usize)| tasks[i].period * instance as Time;
+	let ptimes = jobs.iter().map(|&(i, _)| tasks[i].wcet).collect();
+	let release_times = jobs.iter().map(release).collect();
+	let due_times = jobs.iter().map(|job| release(job) + tasks[job.0].deadline).collect();
+	(ptimes, release_times, due_times)
+}
+
+
+/// Simulates preemptive fixed-priority scheduling of the periodic tasks on a single machine,
+/// e.g. rate-monotonic scheduling if the priorities are ordered by period.
+/// At any time, the released unfinished job of the task with the highest priority is processed,
+/// where a smaller value in `priorities` means a higher priority, with ties broken in favor of the smaller task index.
+/// The jobs are those of `unroll(tasks, horizon)`, so job j is the instance `instances(tasks, horizon)[j]`.
+/// Every job runs until it is completed, even after `horizon`.
+/// Jobs with processing time 0 are completed at their release without any run.
+///
+/// # Arguments
+/// * `tasks`: The periodic tasks
+/// * `priorities`: `priorities[i]` is the priority of task i, where smaller values are higher priorities
+/// * `horizon`: Only jobs released before this time are scheduled, or None for the hyperperiod
+///
+/// # Returns
+/// The schedule, in which every preempted job has one run per uninterrupted fragment,
+/// and the largest response time (completion time minus release time) of a job of each task,
+/// or 0 for a task without jobs or with processing time 0.
+///
+/// # Panics
+/// If the number of priorities differs from the number of tasks, a processing time is negative,
+/// a period is not positive, or `horizon` is None and the hyperperiod does not fit into `Time`.
+pub fn fixed_priority_schedule(
+	tasks: &[PeriodicTask],
+	priorities: &[usize],
+	horizon: Option<Time>
+) -> (MachineSchedule, Vec<Time>)
+{
+	assert_eq!(tasks.len(), priorities.len(), "There must be one priority per task");
+	let horizon = horizon.unwrap_or_else(|| hyperperiod(tasks).expect("The hyperperiod of the tasks does not fit into Time"));
+	let jobs = instances(tasks, horizon);
+	let (mut remaining, release_times, _) = unroll(tasks, horizon);
+	assert_valid_ptimes(&remaining);
+	let mut response_times = vec![0; tasks.len()];
+	let mut clock = EventClock::with_releases(0, &release_times);
+	let mut ready: BinaryHeap<Reverse<(usize, usize, Job)>> = BinaryHeap::new();
+	let mut schedule: Vec<JobRun> = Vec::new();
+	loop {
+		while let Some(job) = clock.pop_release() {
+			let task = jobs[job].0;
+			if remaining[job] > 0 {
+				ready.push(Reverse((priorities[task], task, job)));
+			}
+		}
+		let Some(&Reverse((_, task, job))) = ready.peek() else {
+			match clock.next_event_time() {
+				Some(t) => {
+					clock.advance_to(t);
+					continue;
+				},
+				None => break,
+			}
+		};
+		// run until the job is completed or the next release, which might preempt it
+		let time = clock.time();
+		let end = time + remaining[job];
+		let end = clock.next_event_time().map_or(end, |release| min(end, release));
+		match schedule.last_mut() {
+			Some(last) if last.job == job && last.time + last.duration == time => last.duration += end - time,
+			_ => schedule.push(JobRun{ time, job, duration: end - time }),
+		}
+		remaining[job] -= end - time;
+		if remaining[job] == 0 {
+			ready.pop();
+			response_times[task] = max(response_times[task], end - release_times[job]);
+		}
+		clock.advance_to(end);
+	}
+	(MachineSchedule{ schedule }, response_times)
+}
+
+
+/// Response time analysis for preemptive fixed-priority scheduling of periodic tasks with deadlines at most their periods,
+/// see `fixed_priority_schedule` for the meaning of the priorities.
+/// The worst-case response time of a task is the smallest fixed point R of
+/// R = wcet_i + sum over tasks j with higher priority of ceil(R / period_j) * wcet_j,
+/// which is the response time of its first job when all tasks are released at time 0.
+///
+/// See Joseph, Pandya: "Finding response times in a real-time system" (1986)
+///
+/// # Returns
+/// The worst-case response time of every task, or None for a task whose response time exceeds its deadline.
+///
+/// # Panics
+/// If the number of priorities differs from the number of tasks, a processing time is negative,
+/// or a period is not positive.
+pub fn response_times(tasks: &[PeriodicTask], priorities: &[usize]) -> Vec<Option<Time>> {
+	assert_eq!(tasks.len(), priorities.len(), "There must be one priority per task");
+	for task in tasks {
+		assert!(task.wcet >= 0, "Processing time {} is negative", task.wcet);
+		assert!(task.period > 0, "Period {} is not positive", task.period);
+	}
+	(0..tasks.len()).map(|i| {
+		let higher: Vec<&PeriodicTask> = (0..tasks.len())
+			.filter(|&j| (priorities[j], j) < (priorities[i], i))
+			.map(|j| &tasks[j])
+			.collect();
+		let mut response = tasks[i].wcet;
+		loop {
+			let next = tasks[i].wcet + higher.iter()
+				.map(|task| (response + task.period - 1) / task.period * task.wcet)
+				.sum::<Time>();
+			if next > tasks[i].deadline {
+				return None;
+			}
+			if next == response {
+				return Some(response);
+			}
+			response = next;
+		}
+	}).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::single_machine::edd_preemptive;
+	use proptest::prelude::*;
+
+	fn task(wcet: Time, period: Time, deadline: Time) -> PeriodicTask {
+		PeriodicTask{ wcet, period, deadline }
+	}
+
+	/// Returns whether preemptive EDF on the jobs released before `horizon` meets every deadline up to `horizon`.
+	fn edf_meets_deadlines(tasks: &[PeriodicTask], horizon: Time) -> bool {
+		let (p, r, d) = unroll(tasks, horizon);
+		let schedule = edd_preemptive(p, &r, &d);
+		schedule.completion_times().into_iter().all(|(job, c)| d[job] > horizon || c <= d[job])
+	}
+
+	/// Panics unless every job of `unroll(tasks, horizon)` runs for its processing time after its release,
+	/// and the runs do not overlap.
+	fn assert_valid_runs(tasks: &[PeriodicTask], schedule: &MachineSchedule, horizon: Time) {
+		let (p, r, _) = unroll(tasks, horizon);
+		let mut processed = vec![0; p.len()];
+		let mut previous_end = 0;
+		for run in schedule.schedule.iter() {
+			assert!(run.time >= previous_end && run.time >= r[run.job]);
+			processed[run.job] += run.duration;
+			previous_end = run.time + run.duration;
+		}
+		assert_eq!(processed, p);
+	}
+
+	#[test]
+	fn test_over_utilized() {
+		let tasks = vec![task(2, 4, 4), task(3, 5, 5), task(1, 10, 10)];
+		let report = edf_analysis(&tasks);
+		assert!((report.utilization - 1.2).abs() < 1e-9);
+		assert!(!report.is_schedulable());
+		// 2 jobs of task 0, 2 of task 1 and 1 of task 2 are due by 10
+		assert_eq!(report.first_violation, Some(DemandViolation{ time: 10, demand: 11 }));
+		assert!(!edf_meets_deadlines(&tasks, 10));
+		// constrained deadlines can fail at low utilization
+		let report = edf_analysis(&[task(2, 10, 2), task(2, 10, 3)]);
+		assert!(report.utilization < 0.5);
+		assert_eq!(report.first_violation, Some(DemandViolation{ time: 3, demand: 4 }));
+	}
+
+	#[test]
+	fn test_schedulable() {
+		let tasks = vec![task(1, 4, 4), task(2, 6, 6), task(3, 12, 12)];
+		let report = edf_analysis(&tasks);
+		assert!(report.is_schedulable());
+		assert_eq!(hyperperiod(&tasks), Some(12));
+		let (p, r, d) = unroll(&tasks, 12);
+		assert_eq!(instances(&tasks, 12), vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (2, 0)]);
+		assert_eq!(p, vec![1, 1, 1, 2, 2, 3]);
+		assert_eq!(r, vec![0, 4, 8, 0, 6, 0]);
+		assert_eq!(d, vec![4, 8, 12, 6, 12, 12]);
+		let schedule = edd_preemptive(p, &r, &d);
+		assert!(schedule.respects_releases(&r));
+		assert!(schedule.max_lateness(&d) <= 0);
+		assert!(edf_analysis(&[]).is_schedulable());
+	}
+
+	#[test]
+	fn test_fixed_priority_schedule() {
+		let tasks = vec![task(1, 4, 4), task(2, 6, 6), task(3, 12, 12)];
+		let (schedule, observed) = fixed_priority_schedule(&tasks, &[0, 1, 2], None);
+		assert_valid_runs(&tasks, &schedule, 12);
+		// task 2 is preempted by the releases at 4 and 6
+		let runs: Vec<(Time, Job, Time)> = schedule.schedule.iter().map(|run| (run.time, run.job, run.duration)).collect();
+		assert_eq!(runs, vec![(0, 0, 1), (1, 3, 2), (3, 5, 1), (4, 1, 1), (5, 5, 1), (6, 4, 2), (8, 2, 1), (9, 5, 1)]);
+		assert_eq!(observed, vec![1, 3, 10]);
+		assert_eq!(response_times(&tasks, &[0, 1, 2]), vec![Some(1), Some(3), Some(10)]);
+		// with reversed priorities, task 0 misses its deadline
+		assert_eq!(response_times(&tasks, &[2, 1, 0]), vec![None, Some(5), Some(3)]);
+		let (_, observed) = fixed_priority_schedule(&tasks, &[2, 1, 0], Some(1));
+		assert_eq!(observed, vec![6, 5, 3]);
+	}
+
+	proptest! {
+		#[test]
+		fn test_edf_simulation(
+			params in proptest::collection::vec((1..=3 as Time, 1..=6 as Time, 1..=8 as Time), 1..=3)
+		) {
+			let tasks: Vec<PeriodicTask> = params.iter().map(|&(c, t, d)| task(c, t, d)).collect();
+			let report = edf_analysis(&tasks);
+			let max_deadline = tasks.iter().map(|task| task.deadline).max().unwrap();
+			let horizon = report.first_violation.map_or(hyperperiod(&tasks).unwrap() + max_deadline, |v| v.time);
+			prop_assert_eq!(edf_meets_deadlines(&tasks, horizon), report.is_schedulable());
+			if report.utilization > 1.0 {
+				prop_assert!(!report.is_schedulable());
+			}
+		}
+
+		#[test]
+		fn test_rate_monotonic_response_times(
+			params in proptest::collection::vec((0..=3 as Time, 1..=8 as Time), 1..=4)
+		) {
+			// implicit deadlines and rate-monotonic priorities
+			let tasks: Vec<PeriodicTask> = params.iter().map(|&(c, t)| task(c.min(t), t, t)).collect();
+			let priorities: Vec<usize> = tasks.iter().map(|task| task.period as usize).collect();
+			let analysis = response_times(&tasks, &priorities);
+			prop_assume!(analysis.iter().all(|r| r.is_some()));
+			let (schedule, observed) = fixed_priority_schedule(&tasks, &priorities, None);
+			assert_valid_runs(&tasks, &schedule, hyperperiod(&tasks).unwrap());
+			prop_assert_eq!(observed, analysis.into_iter().map(Option::unwrap).collect::<Vec<Time>>());
+		}
+	}
+}
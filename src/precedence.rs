@@ -0,0 +1,206 @@
+use crate::{Time, Job, MachineSchedule, JobRun, ScheduleError};
+use crate::schedule::validate_ptimes;
+
+
+/// A precedence constraint with time lags `(pred, min_lag, max_lag)`.
+/// If it is given for job `j`, then `j` has to start at least `min_lag`
+/// and, if `max_lag` is set, at most `max_lag` time units after job `pred` is completed.
+/// Lags may be negative, which allows `j` to start before `pred` is completed.
+pub type Lag = (Job, Time, Option<Time>);
+
+
+/// Computes the earliest start times of the jobs such that all time lags are respected
+/// and no job starts before time 0.
+/// Jobs may be processed in parallel, i.e. there is no machine constraint.
+///
+/// Maximum lags are backward edges in the constraint graph,
+/// so the earliest start times are longest paths in a possibly cyclic graph,
+/// which are computed with the Bellman-Ford algorithm in O(n m) time for n jobs and m constraints.
+///
+/// # Arguments
+/// * `durations`: The processing times of the jobs
+/// * `lags`: `lags[j]` are the constraints of job `j` with respect to its predecessors
+///
+/// # Returns
+/// The earliest start time of every job, or
+/// `ScheduleError::InfeasibleLags` with a cycle of jobs whose lags contradict each other.
+pub fn feasible_start_times(durations: &[Time], lags: &[Vec<Lag>]) -> Result<Vec<Time>, ScheduleError> {
+	assert_eq!(durations.len(), lags.len(), "durations and lags must have the same length");
+	validate_ptimes(durations)?;
+	longest_paths(durations.len(), &constraint_edges(durations, lags))
+}
+
+
+/// Schedules the jobs on a single machine such that all time lags are respected.
+/// The jobs are sequenced by their earliest start times from `feasible_start_times`
+/// (ties are broken by job id), and then every job is started as early as possible
+/// with respect to both the lags and the sequence.
+/// This is a heuristic: if the sequence conflicts with some maximum lag,
+/// an error is returned even if a different sequence would be feasible.
+///
+/// # Arguments
+/// * `durations`: The processing times of the jobs
+/// * `lags`: `lags[j]` are the constraints of job `j` with respect to its predecessors
+///
+/// # Returns
+/// The schedule, or `ScheduleError::InfeasibleLags` with a cycle of jobs
+/// that cannot be scheduled in the chosen sequence.
+pub fn lag_list_schedule(durations: &[Time], lags: &[Vec<Lag>]) -> Result<MachineSchedule, ScheduleError> {
+	let earliest = feasible_start_times(durations, lags)?;
+	let mut order: Vec<Job> = (0..durations.len()).collect();
+	order.sort_by_key(|&job| (earliest[job], job));
+	let mut edges = constraint_edges(durations, lags);
+	// the machine processes one job at a time
+	for pair in order.windows(2) {
+		edges.push((pair[0], pair[1], durations[pair[0]]));
+	}
+	let start = longest_paths(durations.len(), &edges)?;
+	Ok(MachineSchedule{
+		schedule: order.iter().map(|&job| JobRun{
+			time: start[job],
+			job,
+			duration: durations[job]
+		}).collect()
+	})
+}
+
+
+/// Translates the lags to edges `(from, to, length)`,
+/// each meaning that `to` starts at least `length` time units after `from` starts.
+fn constraint_edges(durations: &[Time], lags: &[Vec<Lag>]) -> Vec<(Job, Job, Time)> {
+	let mut edges = Vec::new();
+	for (job, job_lags) in lags.iter().enumerate() {
+		for &(pred, min_lag, max_lag) in job_lags.iter() {
+			edges.push((pred, job, durations[pred] + min_lag));
+			if let Some(max_lag) = max_lag {
+				edges.push((job, pred, -durations[pred] - max_lag));
+			}
+		}
+	}
+	edges
+}
+
+
+/// Bellman-Ford for longest paths from a virtual source that has an edge of length 0 to every node.
+fn longest_paths(n: usize, edges: &[(Job, Job, Time)]) -> Result<Vec<Time>, ScheduleError> {
+	let mut distance = vec![0; n];
+	let mut parent = vec![None; n];
+	// including the source, the graph has n+1 nodes,
+	// so n rounds find all longest paths unless there is a positive cycle
+	let mut last_updated = None;
+	for _ in 0..=n {
+		last_updated = None;
+		for &(from, to, length) in edges.iter() {
+			if distance[from] + length > distance[to] {
+				distance[to] = distance[from] + length;
+				parent[to] = Some(from);
+				last_updated = Some(to);
+			}
+		}
+		if last_updated.is_none() {
+			return Ok(distance);
+		}
+	}
+	// a node updated in round n+1 has a positive cycle among its ancestors,
+	// walking back n steps ends up on that cycle
+	let mut node = last_updated.unwrap();
+	for _ in 0..n {
+		node = parent[node].unwrap();
+	}
+	let mut cycle = vec![node];
+	let mut current = parent[node].unwrap();
+	while current != node {
+		cycle.push(current);
+		current = parent[current].unwrap();
+	}
+	// parent pointers lead backwards, the cycle should be in the direction of the constraints
+	cycle.reverse();
+	Err(ScheduleError::InfeasibleLags{ cycle })
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_min_lag_chain() {
+		let durations = vec![3, 2, 4];
+		let lags = vec![
+			vec![],
+			vec![(0, 1, None)],
+			vec![(1, 5, None)],
+		];
+		assert_eq!(feasible_start_times(&durations, &lags), Ok(vec![0, 4, 11]));
+		let schedule = lag_list_schedule(&durations, &lags).unwrap();
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0,  job: 0, duration: 3 },
+			JobRun{ time: 4,  job: 1, duration: 2 },
+			JobRun{ time: 11, job: 2, duration: 4 },
+		]);
+	}
+
+	#[test]
+	fn test_min_max_window() {
+		// job 2 has to start exactly 1 time unit after job 0 completes,
+		// but also after job 1, which is released late through a negative lag from job 0
+		let durations = vec![4, 2, 3];
+		let lags = vec![
+			vec![],
+			vec![(0, -3, None)],
+			vec![(0, 1, Some(1)), (1, 0, None)],
+		];
+		// job 1 starts at 1 and ends at 3, job 2 starts at 5
+		assert_eq!(feasible_start_times(&durations, &lags), Ok(vec![0, 1, 5]));
+		// on a single machine, job 1 has to wait for job 0, which pushes job 2 beyond its window
+		match lag_list_schedule(&durations, &lags) {
+			Err(ScheduleError::InfeasibleLags{ mut cycle }) => {
+				cycle.sort_unstable();
+				assert_eq!(cycle, vec![0, 1, 2]);
+			},
+			result => panic!("Expected an infeasible cycle, got {:?}", result),
+		}
+		// with a wider window it works
+		let lags = vec![
+			vec![],
+			vec![(0, -3, None)],
+			vec![(0, 1, Some(4)), (1, 0, None)],
+		];
+		let schedule = lag_list_schedule(&durations, &lags).unwrap();
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 4 },
+			JobRun{ time: 4, job: 1, duration: 2 },
+			JobRun{ time: 6, job: 2, duration: 3 },
+		]);
+	}
+
+	#[test]
+	fn test_infeasible_window() {
+		// job 1 must start at least 5 but at most 3 time units after job 0
+		let durations = vec![2, 2];
+		let lags = vec![
+			vec![],
+			vec![(0, 5, Some(3))],
+		];
+		let result = feasible_start_times(&durations, &lags);
+		assert!(matches!(&result, Err(ScheduleError::InfeasibleLags{ cycle }) if cycle.len() == 2));
+	}
+
+	#[test]
+	fn test_infeasible_cycle() {
+		// jobs 0 -> 1 -> 2 must be started close to each other, but job 2 must start late
+		let durations = vec![1, 1, 1];
+		let lags = vec![
+			vec![],
+			vec![(0, 0, Some(1))],
+			vec![(1, 0, Some(1)), (0, 10, None)],
+		];
+		match feasible_start_times(&durations, &lags) {
+			Err(ScheduleError::InfeasibleLags{ mut cycle }) => {
+				cycle.sort_unstable();
+				assert_eq!(cycle, vec![0, 1, 2]);
+			},
+			result => panic!("Expected an infeasible cycle, got {:?}", result),
+		}
+	}
+}
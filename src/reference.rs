@@ -0,0 +1,291 @@
+use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, ScheduleError};
+use crate::objective::{Objective, Makespan};
+
+
+/// Maximum number of jobs accepted by the brute-force solvers that enumerate permutations.
+pub const MAX_BRUTE_FORCE_JOBS: usize = 10;
+
+/// Maximum number of candidate solutions `brute_force_parallel` is willing to enumerate.
+pub const MAX_BRUTE_FORCE_CANDIDATES: usize = 4_000_000;
+
+
+/// Calls `f` once for every permutation of the jobs 0..n-1.
+/// Uses Heap's algorithm, i.e. successive permutations differ by a single swap.
+pub fn for_each_permutation<F>(n: usize, mut f: F)
+where
+	F: FnMut(&[Job]),
+{
+	let mut perm: Vec<Job> = (0..n).collect();
+	let mut counters = vec![0; n];
+	f(&perm);
+	let mut i = 1;
+	while i < n {
+		if counters[i] < i {
+			if i % 2 == 0 {
+				perm.swap(0, i);
+			} else {
+				perm.swap(counters[i], i);
+			}
+			f(&perm);
+			counters[i] += 1;
+			i = 1;
+		} else {
+			counters[i] = 0;
+			i += 1;
+		}
+	}
+}
+
+
+/// Solves a single machine problem without preemptions optimally by enumerating all job orders.
+/// Every order is scheduled without unforced idle time, which is optimal for the objectives of this crate.
+///
+/// # Arguments
+/// * `ptimes`: ptimes[i] is the processing time of job i.
+/// * `release_times`: release_times[i] is the release time of job i.
+/// * `due_times`: due_times[i] is the due time of job i.
+/// * `objective`: The objective to minimize.
+///
+/// # Returns
+/// An optimal job order and its objective value,
+/// or an error if there are more than `MAX_BRUTE_FORCE_JOBS` jobs.
+pub fn brute_force_single_machine(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	objective: &dyn Objective
+) -> Result<(Vec<Job>, Time), ScheduleError>
+{
+	let n = ptimes.len();
+	check_num_jobs(n, MAX_BRUTE_FORCE_JOBS)?;
+	let mut best: Option<(Vec<Job>, Time)> = None;
+	for_each_permutation(n, |order| {
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes(
+			order.iter().copied(),
+			ptimes,
+			release_times
+		);
+		let value = objective.evaluate_schedule(&schedule, due_times);
+		if best.as_ref().is_none_or(|(_, v)| value < *v) {
+			best = Some((order.to_vec(), value));
+		}
+	});
+	Ok(best.unwrap())
+}
+
+
+/// Solves a scheduling problem on identical parallel machines optimally
+/// by enumerating all assignments of jobs to machines together with the job order on each machine.
+///
+/// # Arguments
+/// * `ptimes`: ptimes[i] is the processing time of job i (on any machine).
+/// * `num_machines`: The number of machines.
+/// * `due_times`: due_times[i] is the due time of job i.
+/// * `objective`: The objective to minimize.
+///
+/// # Returns
+/// An optimal schedule and its objective value,
+/// or an error if there are more than `MAX_BRUTE_FORCE_CANDIDATES` candidate solutions.
+pub fn brute_force_parallel(
+	ptimes: &[Time],
+	num_machines: usize,
+	due_times: &[Time],
+	objective: &dyn Objective
+) -> Result<(MultiMachineSchedule, Time), ScheduleError>
+{
+	assert!(num_machines > 0, "Instance must have at least one machine");
+	let n = ptimes.len();
+	check_num_jobs(n, max_parallel_jobs(num_machines))?;
+	let mut best: Option<(MultiMachineSchedule, Time)> = None;
+	let mut cuts = vec![0; num_machines + 1];
+	cuts[num_machines] = n;
+	for_each_permutation(n, |order| {
+		// every non-decreasing choice of cuts splits the order into the job sequences of the machines
+		for_each_cut(&mut cuts, 1, &mut |cuts| {
+			let schedule = MultiMachineSchedule {
+				machine_schedules: cuts.windows(2).map(|w|
+					MachineSchedule::from_order_ptimes(order[w[0]..w[1]].iter().copied(), ptimes)
+				).collect()
+			};
+			let value = objective.evaluate_multi_schedule(&schedule, due_times);
+			if best.as_ref().is_none_or(|(_, v)| value < *v) {
+				best = Some((schedule, value));
+			}
+		});
+	});
+	Ok(best.unwrap())
+}
+
+/// Enumerates all non-decreasing values for cuts[index..cuts.len()-1],
+/// bounded by the preceding and the last entry.
+fn for_each_cut<F>(cuts: &mut Vec<usize>, index: usize, f: &mut F)
+where
+	F: FnMut(&[usize]),
+{
+	if index == cuts.len() - 1 {
+		f(cuts);
+		return;
+	}
+	for c in cuts[index-1]..=cuts[cuts.len()-1] {
+		cuts[index] = c;
+		for_each_cut(cuts, index + 1, f);
+	}
+}
+
+/// Returns the largest number of jobs for which `brute_force_parallel` enumerates
+/// at most `MAX_BRUTE_FORCE_CANDIDATES` candidates on the given number of machines.
+fn max_parallel_jobs(num_machines: usize) -> usize {
+	// with n jobs there are n! orders and binomial(n+m-1, m-1) ways to split each of them
+	let mut n = 0;
+	loop {
+		let next = n + 1;
+		let splits = (1..num_machines).fold(1usize, |acc, k|
+			acc.saturating_mul(next + k) / k
+		);
+		let factorial = (1..=next).fold(1usize, |acc, k| acc.saturating_mul(k));
+		if factorial.saturating_mul(splits) > MAX_BRUTE_FORCE_CANDIDATES {
+			return n;
+		}
+		n = next;
+	}
+}
+
+
+/// Solves the permutation flow shop problem F|prmu|C_max optimally by enumerating all job orders.
+///
+/// # Arguments
+/// * `ptimes`: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// An optimal job order and its makespan,
+/// or an error if there are more than `MAX_BRUTE_FORCE_JOBS` jobs.
+pub fn brute_force_permutation_flowshop(ptimes: &[Vec<Time>]) -> Result<(Vec<Job>, Time), ScheduleError> {
+	let transport = vec![0; ptimes.len().saturating_sub(1)];
+	brute_force_permutation_flowshop_with_transport(ptimes, &transport)
+}
+
+
+/// Solves the two-machine flow shop F2||C_max optimally by enumerating all job orders,
+/// as a reference for `flow_shop::johnson`.
+///
+/// # Arguments
+/// * `ptimes`: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Returns
+/// An optimal job order.
+///
+/// # Panics
+/// If the instance does not have exactly 2 machines or has more than `MAX_BRUTE_FORCE_JOBS` jobs.
+pub fn brute_force_flow_shop_2(ptimes: &[Vec<Time>]) -> Vec<Job> {
+	assert!(ptimes.len() == 2, "Instance must have exactly 2 machines");
+	brute_force_permutation_flowshop(ptimes).expect("Too many jobs for brute force").0
+}
+
+/// Like `brute_force_permutation_flowshop`, but with transport times between the machines
+/// as in `MultiMachineSchedule::from_order_ptimes_with_transport`.
+///
+/// # Arguments
+/// * `ptimes`: The processing times, where `ptimes[i][j]` is the time needed by machine i for job j.
+/// * `transport`: `transport[i]` is the time needed to get a job from machine i to machine i+1.
+///
+/// # Returns
+/// An optimal job order and its makespan,
+/// or an error if there are more than `MAX_BRUTE_FORCE_JOBS` jobs.
+pub fn brute_force_permutation_flowshop_with_transport(
+	ptimes: &[Vec<Time>],
+	transport: &[Time]
+) -> Result<(Vec<Job>, Time), ScheduleError> {
+	let n = ptimes.first().map_or(0, |p| p.len());
+	check_num_jobs(n, MAX_BRUTE_FORCE_JOBS)?;
+	let mut best: Option<(Vec<Job>, Time)> = None;
+	for_each_permutation(n, |order| {
+		let schedule = MultiMachineSchedule::from_order_ptimes_with_transport(order, ptimes, transport);
+		let value = Makespan.evaluate_multi_schedule(&schedule, &[]);
+		if best.as_ref().is_none_or(|(_, v)| value < *v) {
+			best = Some((order.to_vec(), value));
+		}
+	});
+	Ok(best.unwrap())
+}
+
+
+fn check_num_jobs(num_jobs: usize, max_jobs: usize) -> Result<(), ScheduleError> {
+	if num_jobs > max_jobs {
+		Err(ScheduleError::TooManyJobs{ num_jobs, max_jobs })
+	} else {
+		Ok(())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::objective::{MaxLateness, TotalCompletionTime};
+
+	#[test]
+	fn test_for_each_permutation() {
+		let mut perms = Vec::new();
+		for_each_permutation(4, |p| perms.push(p.to_vec()));
+		assert_eq!(perms.len(), 24);
+		perms.sort();
+		perms.dedup();
+		assert_eq!(perms.len(), 24);
+
+		let mut count = 0;
+		for_each_permutation(0, |p| {
+			assert!(p.is_empty());
+			count += 1;
+		});
+		assert_eq!(count, 1);
+	}
+
+	#[test]
+	fn test_brute_force_single_machine() {
+		let p = vec![4, 2, 3];
+		let r = vec![0, 1, 0];
+		let d = vec![9, 3, 4];
+		// optimum: job 2 (0-3), job 1 (3-5), job 0 (5-9)
+		let (order, lateness) = brute_force_single_machine(&p, &r, &d, &MaxLateness).unwrap();
+		assert_eq!(lateness, 2);
+		assert_eq!(
+			MachineSchedule::from_order_ptimes_releasetimes(order.into_iter(), &p, &r).max_lateness(&d),
+			2
+		);
+	}
+
+	#[test]
+	fn test_brute_force_parallel() {
+		let p = vec![5, 4, 3, 3, 3];
+		let (schedule, makespan) = brute_force_parallel(&p, 2, &[], &Makespan).unwrap();
+		assert_eq!(makespan, 9);
+		assert_eq!(schedule.makespan(), 9);
+		// SPT on two machines: 3, 3 | 3, 4 | 5 -> completions 3, 3, 6, 7, 11
+		let (_, total) = brute_force_parallel(&p, 2, &[], &TotalCompletionTime).unwrap();
+		assert_eq!(total, 3 + 3 + 6 + 7 + 11);
+	}
+
+	#[test]
+	fn test_brute_force_permutation_flowshop() {
+		let ptimes = vec![
+			vec![3, 4, 10],
+			vec![11, 1, 5],
+			vec![7, 9, 13],
+			vec![10, 12, 2],
+		];
+		let (order, makespan) = brute_force_permutation_flowshop(&ptimes).unwrap();
+		assert_eq!(makespan, 40);
+		assert_eq!(MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(), 40);
+	}
+
+	#[test]
+	fn test_brute_force_refuses_large_instances() {
+		let p = vec![1; MAX_BRUTE_FORCE_JOBS + 1];
+		assert_eq!(
+			brute_force_single_machine(&p, &p, &p, &MaxLateness),
+			Err(ScheduleError::TooManyJobs{ num_jobs: MAX_BRUTE_FORCE_JOBS + 1, max_jobs: MAX_BRUTE_FORCE_JOBS })
+		);
+		let max_jobs = max_parallel_jobs(3);
+		assert!(brute_force_parallel(&vec![1; max_jobs + 1], 3, &[], &Makespan).is_err());
+	}
+}
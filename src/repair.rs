@@ -0,0 +1,312 @@
+use crate::{Time, Job, Machine, MultiMachineSchedule, JobRun};
+use crate::audit::AuditInstance;
+use crate::evaluate::{penalized_cost, PenaltyWeights};
+use crate::tracking::TrackedRun;
+
+
+/// The parts of a schedule that `local_search_frozen` must not change.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrozenParts<'a> {
+	/// Machines whose sequence of runs is fixed: no run is moved onto or off them, and their runs keep their order
+	pub machines: &'a [Machine],
+	/// Jobs whose runs stay on their machines, while other runs may still be moved past them
+	pub jobs: &'a [Job],
+	/// Whether the runs of frozen machines and jobs also keep their start times
+	pub timing: bool,
+}
+
+impl FrozenParts<'_> {
+	/// Returns whether `run` on `machine` is frozen.
+	fn contains(&self, machine: Machine, run: &JobRun) -> bool {
+		self.machines.contains(&machine) || self.jobs.contains(&run.job)
+	}
+}
+
+
+/// Repairs a schedule that violates the constraints of an instance by steepest descent on `penalized_cost`.
+/// Every iteration considers moving each run to each machine, starting at time 0, at the release time
+/// of its job, at the completion of a predecessor of its job or at the end of another run on that machine,
+/// which shifts runs as well as reorders them. The move reducing the penalized cost the most is applied.
+/// The search stops after `iterations` moves or when no move reduces the cost.
+/// Runs keep their jobs and durations, and the runs of every machine stay sorted by time.
+/// Every iteration takes O(m k^4) time for k runs on m machines.
+///
+/// # Arguments
+/// * `schedule`: The schedule to repair
+/// * `instance`: The constraints, see `audit::AuditInstance`
+/// * `penalties`: The weights of the violations, which should exceed 1 to prefer feasibility over a small makespan
+/// * `iterations`: The maximum number of moves
+///
+/// # Returns
+/// The repaired schedule, whose penalized cost is at most that of `schedule`.
+///
+/// # Panics
+/// If a run processes a job that is not part of the instance.
+pub fn local_search(
+	schedule: &MultiMachineSchedule,
+	instance: &AuditInstance,
+	penalties: &PenaltyWeights,
+	iterations: usize
+) -> MultiMachineSchedule
+{
+	local_search_frozen(schedule, instance, penalties, iterations, &FrozenParts::default())
+}
+
+
+/// Like `local_search`, but also records the penalized cost of the schedule after each move.
+/// If no move reduces the cost any more, there are fewer entries than iterations.
+pub fn local_search_tracked(
+	schedule: &MultiMachineSchedule,
+	instance: &AuditInstance,
+	penalties: &PenaltyWeights,
+	iterations: usize
+) -> TrackedRun<MultiMachineSchedule>
+{
+	local_search_frozen_tracked(schedule, instance, penalties, iterations, &FrozenParts::default())
+}
+
+
+/// `local_search` that keeps the given parts of the schedule, e.g. a machine whose order is contractual.
+/// Moves of runs onto or off frozen machines and of runs of frozen jobs to other machines are skipped.
+/// Unless `frozen.timing` is set, frozen runs may still be shifted in time on their machine,
+/// but not past other runs there, e.g. to make room for a predecessor.
+/// Frozen jobs stay on their machines, but other runs may be moved before or after them.
+///
+/// # Returns
+/// The repaired schedule, whose penalized cost is at most that of `schedule`.
+///
+/// # Panics
+/// If a run processes a job that is not part of the instance, or a frozen machine does not exist.
+pub fn local_search_frozen(
+	schedule: &MultiMachineSchedule,
+	instance: &AuditInstance,
+	penalties: &PenaltyWeights,
+	iterations: usize,
+	frozen: &FrozenParts
+) -> MultiMachineSchedule
+{
+	local_search_recorded(schedule, instance, penalties, iterations, frozen, None)
+}
+
+
+/// Like `local_search_frozen`, but also records the penalized cost of the schedule after each move.
+/// If no move reduces the cost any more, there are fewer entries than iterations.
+pub fn local_search_frozen_tracked(
+	schedule: &MultiMachineSchedule,
+	instance: &AuditInstance,
+	penalties: &PenaltyWeights,
+	iterations: usize,
+	frozen: &FrozenParts
+) -> TrackedRun<MultiMachineSchedule>
+{
+	let mut best_per_iter = Vec::new();
+	let result = local_search_recorded(schedule, instance, penalties, iterations, frozen, Some(&mut best_per_iter));
+	TrackedRun{ result, best_per_iter }
+}
+
+
+fn local_search_recorded(
+	schedule: &MultiMachineSchedule,
+	instance: &AuditInstance,
+	penalties: &PenaltyWeights,
+	iterations: usize,
+	frozen: &FrozenParts,
+	mut recorder: Option<&mut Vec<Time>>
+) -> MultiMachineSchedule
+{
+	for &machine in frozen.machines {
+		assert!(machine < schedule.num_machines(), "Frozen machine {} does not exist", machine);
+	}
+	let mut current = schedule.clone();
+	let mut cost = penalized_cost(&current, instance, penalties).0;
+	for _ in 0..iterations {
+		let mut best: Option<(Time, MultiMachineSchedule)> = None;
+		for machine in 0..current.num_machines() {
+			for index in 0..current.machine_schedules[machine].schedule.len() {
+				let mut without = current.clone();
+				let run = without.machine_schedules[machine].schedule.remove(index);
+				let is_frozen = frozen.contains(machine, &run);
+				if is_frozen && frozen.timing {
+					continue;
+				}
+				for target in 0..current.num_machines() {
+					if (is_frozen && target != machine) || (!is_frozen && frozen.machines.contains(&target)) {
+						continue;
+					}
+					for time in candidate_times(&without, instance, target, run) {
+						if (target, time) == (machine, run.time) {
+							continue;
+						}
+						let mut moved = without.clone();
+						insert_sorted(&mut moved, target, JobRun{ time, ..run });
+						if is_frozen && job_sequence(&moved, machine) != job_sequence(&current, machine) {
+							continue;
+						}
+						let c = penalized_cost(&moved, instance, penalties).0;
+						if c < best.as_ref().map_or(cost, |b| b.0) {
+							best = Some((c, moved));
+						}
+					}
+				}
+			}
+		}
+		match best {
+			Some((c, moved)) => {
+				cost = c;
+				current = moved;
+				if let Some(recorder) = recorder.as_mut() {
+					recorder.push(cost);
+				}
+			},
+			None => break,
+		}
+	}
+	for &machine in frozen.machines {
+		assert!(job_sequence(&current, machine) == job_sequence(schedule, machine), "Frozen machine {} was reordered", machine);
+	}
+	if frozen.timing {
+		for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+			for run in s.schedule.iter().filter(|run| frozen.contains(machine, run)) {
+				assert!(current.machine_schedules[machine].schedule.contains(run), "Frozen run {:?} was moved", run);
+			}
+		}
+	}
+	current
+}
+
+
+/// Returns the jobs of the runs of `machine` in order.
+fn job_sequence(schedule: &MultiMachineSchedule, machine: Machine) -> Vec<Job> {
+	schedule.machine_schedules[machine].schedule.iter().map(|run| run.job).collect()
+}
+
+
+/// Returns the sorted distinct start times considered for moving `run` to machine `target` of `schedule`,
+/// from which `run` has been removed.
+fn candidate_times(schedule: &MultiMachineSchedule, instance: &AuditInstance, target: Machine, run: JobRun) -> Vec<Time> {
+	let mut times = vec![0, run.time];
+	if let Some(release_times) = instance.release_times {
+		times.push(release_times[run.job]);
+	}
+	if let Some(predecessors) = instance.predecessors {
+		for s in schedule.machine_schedules.iter() {
+			times.extend(s.schedule.iter().filter(|r| predecessors[run.job].contains(&r.job)).map(|r| r.time + r.duration));
+		}
+	}
+	times.extend(schedule.machine_schedules[target].schedule.iter().map(|r| r.time + r.duration));
+	times.sort_unstable();
+	times.dedup();
+	times
+}
+
+
+/// Inserts `run` into the schedule of `machine` after all runs starting at or before it.
+fn insert_sorted(schedule: &mut MultiMachineSchedule, machine: Machine, run: JobRun) {
+	let runs = &mut schedule.machine_schedules[machine].schedule;
+	let position = runs.partition_point(|r| r.time <= run.time);
+	runs.insert(position, run);
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::MachineSchedule;
+	use crate::audit::report;
+
+	fn run(time: Time, job: Job, duration: Time) -> JobRun {
+		JobRun{ time, job, duration }
+	}
+
+	#[test]
+	fn test_local_search() {
+		let ptimes = [3, 2, 2];
+		let releases = [0, 0, 4];
+		let deadlines = [9, 9, 9];
+		let predecessors = vec![vec![], vec![0], vec![]];
+		let instance = AuditInstance{
+			ptimes: &ptimes,
+			release_times: Some(&releases),
+			deadlines: Some(&deadlines),
+			predecessors: Some(&predecessors),
+		};
+		let penalties = PenaltyWeights::uniform(10);
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![run(0, 1, 2), run(1, 0, 3), run(3, 2, 2)] },
+		]};
+		assert!(penalized_cost(&schedule, &instance, &penalties).1.total_penalty() > 0);
+		let repaired = local_search(&schedule, &instance, &penalties, 20);
+		let (cost, breakdown) = penalized_cost(&repaired, &instance, &penalties);
+		assert!(breakdown.terms.is_empty());
+		assert!(report(&repaired, &instance).is_valid());
+		assert_eq!(cost, repaired.makespan());
+		// no iterations leave the schedule unchanged
+		assert_eq!(local_search(&schedule, &instance, &penalties, 0), schedule);
+		let tracked = local_search_tracked(&schedule, &instance, &penalties, 20);
+		assert_eq!(tracked.result, repaired);
+		assert!(!tracked.best_per_iter.is_empty() && tracked.best_per_iter.len() <= 20);
+		assert!(tracked.best_per_iter.windows(2).all(|w| w[1] < w[0]));
+		assert_eq!(tracked.final_value(), Some(cost));
+		assert_eq!(local_search_tracked(&schedule, &instance, &penalties, 0).final_value(), None);
+	}
+
+	#[test]
+	fn test_local_search_machines() {
+		// two overlapping runs on one machine are spread over both machines
+		let ptimes = [4, 4];
+		let instance = AuditInstance::new(&ptimes);
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![run(0, 0, 4), run(0, 1, 4)] },
+			MachineSchedule::new(),
+		]};
+		let repaired = local_search(&schedule, &instance, &PenaltyWeights::uniform(2), 10);
+		assert!(report(&repaired, &instance).is_valid());
+		assert_eq!(repaired.makespan(), 4);
+	}
+
+	#[test]
+	fn test_local_search_frozen() {
+		let ptimes = [4, 4, 2, 2, 2];
+		let instance = AuditInstance::new(&ptimes);
+		// machine 0 is the bottleneck, and jobs 3 and 4 overlap on machine 1
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![run(0, 0, 4), run(4, 1, 4), run(8, 2, 2)] },
+			MachineSchedule{ schedule: vec![run(0, 3, 2), run(1, 4, 2)] },
+		]};
+		let penalties = PenaltyWeights::uniform(2);
+		let repaired = local_search(&schedule, &instance, &penalties, 10);
+		assert!(report(&repaired, &instance).is_valid());
+		assert_eq!(repaired.makespan(), 8);
+		// with machine 0 frozen, only the overlap on machine 1 is repaired
+		let frozen = FrozenParts{ machines: &[0], jobs: &[], timing: true };
+		let repaired = local_search_frozen(&schedule, &instance, &penalties, 10, &frozen);
+		assert!(report(&repaired, &instance).is_valid());
+		assert_eq!(repaired.machine_schedules[0], schedule.machine_schedules[0]);
+		assert_eq!(repaired.makespan(), 10);
+		// with job 4 frozen as well, it stays in place and job 3 moves behind it
+		let frozen = FrozenParts{ machines: &[0], jobs: &[4], timing: true };
+		let repaired = local_search_frozen(&schedule, &instance, &penalties, 10, &frozen);
+		assert!(report(&repaired, &instance).is_valid());
+		assert_eq!(repaired.machine_schedules[1].schedule, vec![run(1, 4, 2), run(3, 3, 2)]);
+		let tracked = local_search_frozen_tracked(&schedule, &instance, &penalties, 10, &frozen);
+		assert_eq!(tracked.result, repaired);
+		assert_eq!(tracked.final_value(), Some(penalized_cost(&repaired, &instance, &penalties).0));
+	}
+
+	#[test]
+	fn test_local_search_frozen_timing() {
+		// the runs of the frozen machine overlap, which is only repaired by shifting them
+		let ptimes = [4, 4];
+		let instance = AuditInstance::new(&ptimes);
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![run(0, 0, 4), run(2, 1, 4)] },
+			MachineSchedule::new(),
+		]};
+		let penalties = PenaltyWeights::uniform(2);
+		let frozen = FrozenParts{ machines: &[0], jobs: &[], timing: false };
+		let repaired = local_search_frozen(&schedule, &instance, &penalties, 10, &frozen);
+		assert_eq!(repaired.machine_schedules[0].schedule, vec![run(0, 0, 4), run(4, 1, 4)]);
+		let frozen = FrozenParts{ timing: true, ..frozen };
+		assert_eq!(local_search_frozen(&schedule, &instance, &penalties, 10, &frozen), schedule);
+	}
+}
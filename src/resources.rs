@@ -0,0 +1,235 @@
+use std::cmp::max;
+
+use crate::{Time, JobRun, MachineSchedule};
+
+
+/// Identifies a resource that is shared by several machines, e.g. an operator tending them.
+pub type ResourceId = usize;
+
+
+/// An interval in which more runs of machines sharing a resource are active than the resource's capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+	/// The resource that is overloaded
+	pub resource: ResourceId,
+	/// The start of the interval
+	pub start: Time,
+	/// The end of the interval (exclusive)
+	pub end: Time,
+	/// The runs active during the whole interval, with the index of their schedule in the input,
+	/// sorted by that index
+	pub runs: Vec<(usize, JobRun)>,
+}
+
+
+/// Overlays schedules on a common timeline and returns the intervals in which more than `capacity` runs
+/// of schedules sharing a resource are active at the same time.
+/// Runs are active during [start, start + duration), so runs of duration 0 never conflict.
+/// The conflicts are maximal intervals with the same active runs, sorted by resource and start time,
+/// so adjacent conflicts of a resource differ in their runs.
+/// Runs in O(n (k + log n)) time for n runs and at most k runs active at the same time.
+///
+/// # Arguments
+/// * `schedules`: The schedules together with the resource their machine needs while processing a run
+/// * `capacity`: The number of runs every resource can serve at the same time
+pub fn conflicts(schedules: &[(&MachineSchedule, ResourceId)], capacity: usize) -> Vec<Conflict> {
+	let mut result: Vec<Conflict> = Vec::new();
+	let num_resources = schedules.iter().map(|&(_, resource)| resource + 1).max().unwrap_or(0);
+	for resource in 0..num_resources {
+		// (time, is start, index of the schedule, run), where ends sort before starts at the same time
+		let mut events: Vec<(Time, bool, usize, JobRun)> = Vec::new();
+		for (index, &(schedule, r)) in schedules.iter().enumerate() {
+			if r == resource {
+				for &run in schedule.schedule.iter().filter(|run| run.duration > 0) {
+					events.push((run.time, true, index, run));
+					events.push((run.time + run.duration, false, index, run));
+				}
+			}
+		}
+		events.sort_unstable_by_key(|&(t, start, index, run)| (t, start, index, run.time, run.job));
+		let mut active: Vec<(usize, JobRun)> = Vec::new();
+		let mut i = 0;
+		while i < events.len() {
+			let t = events[i].0;
+			while i < events.len() && events[i].0 == t {
+				let (_, start, index, run) = events[i];
+				if start {
+					let position = active.partition_point(|&(j, r)| (j, r.time) < (index, run.time));
+					active.insert(position, (index, run));
+				} else {
+					active.retain(|&(j, r)| (j, r) != (index, run));
+				}
+				i += 1;
+			}
+			if active.len() > capacity {
+				let end = events[i].0;
+				match result.last_mut() {
+					Some(last) if last.resource == resource && last.end == t && last.runs == active => last.end = end,
+					_ => result.push(Conflict{ resource, start: t, end, runs: active.clone() }),
+				}
+			}
+		}
+	}
+	result
+}
+
+
+/// Resolves all conflicts reported by `conflicts` by delaying runs.
+/// The runs of all schedules are placed greedily in order of their original start time,
+/// each at the earliest time that is not before its original start, not before the end of the previous run
+/// of its schedule, and at which fewer than `capacity` already placed runs of the same resource are active
+/// during its whole duration.
+/// Delaying a run may delay later runs of the same schedule and thus cause further conflicts,
+/// which are resolved in the same way.
+/// Runs in O(n^3) time for n runs.
+///
+/// # Arguments
+/// * `schedules`: The schedules together with the resource their machine needs while processing a run
+/// * `capacity`: The number of runs every resource can serve at the same time
+///
+/// # Returns
+/// The schedules with delayed runs, in the same order as `schedules`.
+///
+/// # Panics
+/// If `capacity` is 0 and a run has a positive duration.
+pub fn resolve_by_shifting(schedules: &[(&MachineSchedule, ResourceId)], capacity: usize) -> Vec<MachineSchedule> {
+	let mut runs: Vec<(Time, usize, usize)> = schedules.iter().enumerate()
+		.flat_map(|(index, (schedule, _))| schedule.schedule.iter().enumerate().map(move |(k, run)| (run.time, index, k)))
+		.collect();
+	runs.sort_unstable();
+	let mut result: Vec<MachineSchedule> = schedules.iter().map(|&(schedule, _)| schedule.clone()).collect();
+	let mut ready: Vec<Time> = vec![Time::MIN; schedules.len()];
+	let num_resources = schedules.iter().map(|&(_, resource)| resource + 1).max().unwrap_or(0);
+	let mut placed: Vec<Vec<(Time, Time)>> = vec![Vec::new(); num_resources];
+	for (time, index, k) in runs {
+		let resource = schedules[index].1;
+		let duration = result[index].schedule[k].duration;
+		let earliest = max(time, ready[index]);
+		let start = if duration == 0 {
+			earliest
+		} else {
+			assert!(capacity > 0, "A resource with capacity 0 cannot serve any run");
+			let busy = &placed[resource];
+			// the earliest feasible start is either `earliest` or the end of a placed run
+			let mut candidates: Vec<Time> = busy.iter().map(|&(_, end)| end).filter(|&end| end > earliest).collect();
+			candidates.push(earliest);
+			candidates.sort_unstable();
+			let fits = |start: Time| max_active(busy, start, start + duration) < capacity;
+			candidates.into_iter().find(|&start| fits(start)).unwrap()
+		};
+		result[index].schedule[k].time = start;
+		ready[index] = start + duration;
+		if duration > 0 {
+			placed[resource].push((start, start + duration));
+		}
+	}
+	result
+}
+
+
+/// Returns the maximum number of the given intervals active at the same time within [start, end).
+fn max_active(intervals: &[(Time, Time)], start: Time, end: Time) -> usize {
+	let mut events: Vec<(Time, isize)> = intervals.iter()
+		.filter(|&&(s, e)| s < end && e > start)
+		.flat_map(|&(s, e)| [(max(s, start), 1), (e, -1)])
+		.collect();
+	events.sort_unstable();
+	let mut active = 0;
+	let mut result = 0;
+	for (_, delta) in events {
+		active += delta;
+		result = max(result, active);
+	}
+	result as usize
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	fn schedule(runs: &[(Time, usize, Time)]) -> MachineSchedule {
+		MachineSchedule{ schedule: runs.iter().map(|&(time, job, duration)| JobRun{ time, job, duration }).collect() }
+	}
+
+	#[test]
+	fn test_capacity_1() {
+		let a = schedule(&[(0, 0, 3)]);
+		let b = schedule(&[(1, 1, 3)]);
+		let c = schedule(&[(0, 2, 5)]);
+		let input = [(&a, 0), (&b, 0), (&c, 1)];
+		assert_eq!(conflicts(&input, 1), vec![Conflict{
+			resource: 0,
+			start: 1,
+			end: 3,
+			runs: vec![(0, a.schedule[0]), (1, b.schedule[0])],
+		}]);
+		let resolved = resolve_by_shifting(&input, 1);
+		assert_eq!(resolved, vec![a.clone(), schedule(&[(3, 1, 3)]), c.clone()]);
+		assert!(conflicts(&[(&resolved[0], 0), (&resolved[1], 0), (&resolved[2], 1)], 1).is_empty());
+	}
+
+	#[test]
+	fn test_capacity_2() {
+		let a = schedule(&[(0, 0, 4)]);
+		let b = schedule(&[(1, 1, 4)]);
+		let c = schedule(&[(2, 2, 4), (6, 3, 0)]);
+		let input = [(&a, 0), (&b, 0), (&c, 0)];
+		let found = conflicts(&input, 2);
+		assert_eq!(found.len(), 1);
+		assert_eq!((found[0].start, found[0].end, found[0].runs.len()), (2, 4, 3));
+		assert!(conflicts(&input, 3).is_empty());
+		let resolved = resolve_by_shifting(&input, 2);
+		assert_eq!(resolved[2], schedule(&[(4, 2, 4), (8, 3, 0)]));
+	}
+
+	#[test]
+	fn test_cascade() {
+		// delaying job 1 delays job 2 on the same machine, which then collides with job 3
+		let a = schedule(&[(0, 0, 4)]);
+		let b = schedule(&[(2, 1, 3), (5, 2, 2)]);
+		let c = schedule(&[(7, 3, 2)]);
+		let input = [(&a, 0), (&b, 0), (&c, 0)];
+		assert_eq!(conflicts(&input, 1).len(), 1);
+		let resolved = resolve_by_shifting(&input, 1);
+		assert_eq!(resolved[1], schedule(&[(4, 1, 3), (7, 2, 2)]));
+		assert_eq!(resolved[2], schedule(&[(9, 3, 2)]));
+		let output: Vec<(&MachineSchedule, ResourceId)> = resolved.iter().map(|s| (s, 0)).collect();
+		assert!(conflicts(&output, 1).is_empty());
+	}
+
+	proptest! {
+		#[test]
+		fn test_resolve_by_shifting(
+			machines in proptest::collection::vec(
+				(proptest::collection::vec((0..4 as Time, 0..4 as Time), 0..5), 0..2usize),
+				1..5
+			),
+			capacity in 1..3usize
+		) {
+			let schedules: Vec<MachineSchedule> = machines.iter().map(|(runs, _)| {
+				let mut time = 0;
+				MachineSchedule{ schedule: runs.iter().enumerate().map(|(job, &(gap, duration))| {
+					time += gap;
+					let run = JobRun{ time, job, duration };
+					time += duration;
+					run
+				}).collect() }
+			}).collect();
+			let input: Vec<(&MachineSchedule, ResourceId)> = schedules.iter().zip(machines.iter()).map(|(s, m)| (s, m.1)).collect();
+			let resolved = resolve_by_shifting(&input, capacity);
+			let output: Vec<(&MachineSchedule, ResourceId)> = resolved.iter().zip(machines.iter()).map(|(s, m)| (s, m.1)).collect();
+			prop_assert!(conflicts(&output, capacity).is_empty());
+			for (before, after) in schedules.iter().zip(resolved.iter()) {
+				prop_assert_eq!(before.job_order(), after.job_order());
+				for (b, a) in before.schedule.iter().zip(after.schedule.iter()) {
+					prop_assert!(a.time >= b.time && a.duration == b.duration);
+				}
+				for pair in after.schedule.windows(2) {
+					prop_assert!(pair[0].time + pair[0].duration <= pair[1].time);
+				}
+			}
+		}
+	}
+}
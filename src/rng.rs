@@ -0,0 +1,46 @@
+/// Small deterministic pseudo random number generator (SplitMix64) for seeded heuristics,
+/// so that results are reproducible across platforms without external dependencies.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+	state: u64,
+}
+
+impl Rng {
+	pub(crate) fn new(seed: u64) -> Rng {
+		Rng{ state: seed }
+	}
+
+	pub(crate) fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+		z ^ (z >> 31)
+	}
+
+	/// Returns a number in `0..n`.
+	///
+	/// # Panics
+	/// If `n` is 0.
+	pub(crate) fn below(&mut self, n: usize) -> usize {
+		assert!(n > 0, "Cannot choose from an empty range");
+		(self.next_u64() % n as u64) as usize
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_deterministic() {
+		let numbers = |seed| {
+			let mut rng = Rng::new(seed);
+			(0..10).map(|_| rng.below(7)).collect::<Vec<usize>>()
+		};
+		assert_eq!(numbers(42), numbers(42));
+		assert_ne!(numbers(42), numbers(43));
+		assert!(numbers(1).iter().all(|&x| x < 7));
+	}
+}
@@ -1,12 +1,17 @@
-use crate::{Time, Job};
+use crate::{Time, Job, Machine, ScheduleError, JobShopInstance, Calendar};
+use crate::display::{DisplayOptions, RowFormatter};
+use crate::json;
+use crate::fingerprint::Fingerprint;
 
-use std::cmp::max;
-use std::fmt;
+use std::cmp::{max, Reverse};
+use std::fmt::{self, Write};
 
 
-/// A job with an assigned starting time and duration
-/// Durations should be positive
-#[derive(Debug, Clone, Eq, PartialEq, Copy)]
+/// A job with an assigned starting time and duration.
+/// Durations must not be negative.
+/// A run of duration 0 completes instantly at its start time.
+#[derive(Debug, Clone, Eq, PartialEq, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JobRun {
 	pub time: Time,
 	pub job: Job,
@@ -14,18 +19,81 @@ pub struct JobRun {
 }
 
 /// A schedule of jobs on a single machine
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MachineSchedule {
 	/// List of job executions, sorted by time.
 	/// If jobs can be preempted, the same job may appear in multiple entries.
 	pub schedule: Vec<JobRun>,
 }
 
+/// Returns an error if any of the given processing times is negative.
+/// Processing times of 0 are allowed, such jobs complete instantly at their start time.
+pub fn validate_ptimes(ptimes: &[Time]) -> Result<(), ScheduleError> {
+	match ptimes.iter().position(|&p| p < 0) {
+		Some(job) => Err(ScheduleError::NegativeDuration{ job, duration: ptimes[job] }),
+		None => Ok(()),
+	}
+}
+
+/// Panics with the corresponding error message if any of the given processing times is negative.
+pub(crate) fn assert_valid_ptimes(ptimes: &[Time]) {
+	if let Err(e) = validate_ptimes(ptimes) {
+		panic!("{}", e);
+	}
+}
+
+/// A fixed setup before and teardown after every run, during which the machine is busy
+/// but the job is not processed.
+/// Schedules built with these options contain only the processing part of every run,
+/// so the lateness of a job is based on the end of its processing, before the teardown.
+/// The setup of a job starts no earlier than its release time, and the next setup starts
+/// no earlier than the end of the previous teardown.
+/// With the default options, setup and teardown take no time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScheduleBuildOptions {
+	/// The time the machine is busy before processing each job
+	pub setup: Time,
+	/// The time the machine is busy after processing each job
+	pub teardown: Time,
+}
+
+impl ScheduleBuildOptions {
+	/// Returns the makespan of a schedule built with these options,
+	/// i.e. the end of the last teardown, or 0 if the schedule is empty.
+	pub fn makespan(&self, schedule: &MachineSchedule) -> Time {
+		schedule.schedule.last().map_or(0, |run| run.time + run.duration + self.teardown)
+	}
+
+	/// Returns the time the machine is busy for each job, including setup and teardown.
+	///
+	/// # Panics
+	/// If a processing time, the setup time or the teardown time is negative.
+	pub(crate) fn busy_times(&self, ptimes: &[Time]) -> Vec<Time> {
+		assert_valid_ptimes(ptimes);
+		assert!(self.setup >= 0 && self.teardown >= 0, "Setup and teardown times must not be negative");
+		ptimes.iter().map(|p| self.setup + p + self.teardown).collect()
+	}
+
+	/// Turns a non-preemptive schedule of the `busy_times` into a schedule of the processing parts.
+	pub(crate) fn processing_runs(&self, mut schedule: MachineSchedule, ptimes: &[Time]) -> MachineSchedule {
+		for run in schedule.schedule.iter_mut() {
+			run.time += self.setup;
+			run.duration = ptimes[run.job];
+		}
+		schedule
+	}
+}
+
 impl MachineSchedule {
 	/// Construct a schedule from given processing times.
 	/// # Arguments
 	/// ptimes: ptimes[i] is the processing time of job i.
+	///
+	/// # Panics
+	/// If a processing time is negative.
 	pub fn from_ptimes(ptimes: &[Time]) -> MachineSchedule {
+		assert_valid_ptimes(ptimes);
 		let mut time = 0;
 		let schedule = ptimes.iter().enumerate().map(|(i, d)| {
 			time += d;
@@ -42,6 +110,9 @@ impl MachineSchedule {
 	/// # Arguments
 	/// order: The order of the jobs
 	/// ptimes: ptimes[i] is the processing time of job i.
+	///
+	/// # Panics
+	/// If a processing time is negative.
 	pub fn from_order_ptimes<I>(order: I, ptimes: &[Time]) -> MachineSchedule 
 	where I: Iterator<Item = Job>
 	{
@@ -52,10 +123,23 @@ impl MachineSchedule {
 		)
 	}
 
+	/// Like `from_order_ptimes`, but takes the order as a slice.
+	///
+	/// # Panics
+	/// If a processing time is negative.
+	pub fn from_order_ptimes_slice(order: &[Job], ptimes: &[Time]) -> MachineSchedule {
+		MachineSchedule::from_order_ptimes(order.iter().copied(), ptimes)
+	}
+
 	pub fn new() -> MachineSchedule {
 		MachineSchedule { schedule: Vec::new() }
 	}
 
+	/// Construct a schedule from given processing times and release times,
+	/// processing the jobs in order of their ids.
+	///
+	/// # Panics
+	/// If a processing time is negative.
 	pub fn from_ptimes_releasetimes(ptimes: &[Time], release_times: &[Time]) -> MachineSchedule {
 		MachineSchedule::from_order_ptimes_releasetimes(
 			0..ptimes.len(),
@@ -69,6 +153,9 @@ impl MachineSchedule {
 	/// order: The order of the jobs
 	/// ptimes: ptimes[i] is the processing time of job i.
 	/// release_times: release_times[i] is the release time of job i.
+	///
+	/// # Panics
+	/// If a processing time is negative.
 	pub fn from_order_ptimes_releasetimes<I>(
 		order: I,
 		ptimes: &[Time],
@@ -76,16 +163,94 @@ impl MachineSchedule {
 	) -> MachineSchedule
 	where I: Iterator<Item = Job>
 	{
+		let mut schedule = Vec::with_capacity(order.size_hint().0);
+		MachineSchedule::from_order_ptimes_releasetimes_streaming(order, ptimes, release_times, |run| schedule.push(run));
+		MachineSchedule{ schedule }
+	}
+
+	/// Like `from_order_ptimes_releasetimes`, but takes the order as a slice.
+	///
+	/// # Panics
+	/// If a processing time is negative.
+	pub fn from_order_ptimes_releasetimes_slice(order: &[Job], ptimes: &[Time], release_times: &[Time]) -> MachineSchedule {
+		MachineSchedule::from_order_ptimes_releasetimes(order.iter().copied(), ptimes, release_times)
+	}
+
+	/// Like `from_order_ptimes_releasetimes`, but with a setup before and a teardown after every job.
+	/// The runs of the schedule are the processing parts, see `ScheduleBuildOptions`.
+	///
+	/// # Panics
+	/// If a processing time, the setup time or the teardown time is negative.
+	pub fn from_order_ptimes_releasetimes_with_options<I>(
+		order: I,
+		ptimes: &[Time],
+		release_times: &[Time],
+		options: &ScheduleBuildOptions
+	) -> MachineSchedule
+	where I: Iterator<Item = Job>
+	{
+		let busy_times = options.busy_times(ptimes);
+		options.processing_runs(MachineSchedule::from_order_ptimes_releasetimes(order, &busy_times, release_times), ptimes)
+	}
+
+	/// Like `from_order_ptimes_releasetimes`, but the machine only processes jobs during the working time
+	/// of `calendar`. A job starts at the first working time after its release and the completion of
+	/// the previous job, and its processing is split into one run per interval of working time it needs.
+	/// Jobs with processing time 0 have a single run at the first working time after their release
+	/// and the completion of the previous job.
+	/// With `Calendar::always()`, the schedule is the same as from `from_order_ptimes_releasetimes`.
+	///
+	/// # Panics
+	/// If a processing time is negative, or the calendar has no working time.
+	pub fn from_order_ptimes_releasetimes_calendar<I>(
+		order: I,
+		ptimes: &[Time],
+		release_times: &[Time],
+		calendar: &Calendar
+	) -> MachineSchedule
+	where I: Iterator<Item = Job>
+	{
+		assert_valid_ptimes(ptimes);
+		let mut schedule = Vec::with_capacity(order.size_hint().0);
+		let mut time = 0;
+		for job in order {
+			time = calendar.next_working_time(max(time, release_times[job]));
+			if ptimes[job] == 0 {
+				schedule.push(JobRun{ time, job, duration: 0 });
+			}
+			for (start, end) in calendar.working_intervals(time, ptimes[job]) {
+				schedule.push(JobRun{ time: start, job, duration: end - start });
+				time = end;
+			}
+		}
+		MachineSchedule{ schedule }
+	}
+
+	/// Like `from_order_ptimes_releasetimes`, but passes every run to `on_run` instead of storing it,
+	/// e.g. to a `ScheduleStats`.
+	///
+	/// # Panics
+	/// If a processing time is negative.
+	pub fn from_order_ptimes_releasetimes_streaming<I, F>(
+		order: I,
+		ptimes: &[Time],
+		release_times: &[Time],
+		mut on_run: F
+	)
+	where
+		I: Iterator<Item = Job>,
+		F: FnMut(JobRun),
+	{
+		assert_valid_ptimes(ptimes);
 		let mut time = 0;
-		let schedule = order.map(|job| {
+		for job in order {
 			time = max(time, release_times[job]) + ptimes[job];
-			JobRun{
+			on_run(JobRun{
 				time: time - ptimes[job],
-				job: job,
+				job,
 				duration: ptimes[job],
-			}
-		}).collect();
-		MachineSchedule{ schedule }
+			});
+		}
 	}
 
 	/// Returns the makespan of this MachineSchedule.
@@ -103,37 +268,440 @@ impl MachineSchedule {
 		}).max().expect("MachineSchedule is empty")
 	}
 
+	/// Returns the maximum lateness after swapping the runs at positions `i` and `i + 1`,
+	/// without building the swapped schedule.
+	/// This schedule has to process every job in one run as early as possible in the order of its runs,
+	/// e.g. built by `from_order_ptimes_releasetimes`.
+	/// The runs before position `i` are unchanged, so only the runs from position `i` on are simulated,
+	/// until a run completes at the same time as before the swap, after which the latenesses are unchanged as well.
+	/// Runs in O(n) time for n runs without allocating, and is faster the earlier the simulation stops.
+	///
+	/// # Arguments
+	/// * `i`: The position of the first of the two adjacent runs to swap
+	/// * `ptimes`: `ptimes[j]` is the processing time of job j
+	/// * `release_times`: `release_times[j]` is the release time of job j
+	/// * `due_times`: `due_times[j]` is the due time of job j
+	///
+	/// # Panics
+	/// If there is no run at position `i + 1`.
+	pub fn lateness_after_swap(&self, i: usize, ptimes: &[Time], release_times: &[Time], due_times: &[Time]) -> Time {
+		let runs = &self.schedule;
+		assert!(i + 1 < runs.len(), "Cannot swap run {} with run {} of {} runs", i, i + 1, runs.len());
+		let lateness = |run: &JobRun| run.time + run.duration - due_times[run.job];
+		let mut result = runs[..i].iter().map(lateness).max().unwrap_or(Time::MIN);
+		let mut time = if i == 0 { 0 } else { runs[i - 1].time + runs[i - 1].duration };
+		for job in [runs[i + 1].job, runs[i].job] {
+			time = max(time, release_times[job]) + ptimes[job];
+			result = max(result, time - due_times[job]);
+		}
+		for (k, run) in runs.iter().enumerate().skip(i + 2) {
+			if time == runs[k - 1].time + runs[k - 1].duration {
+				// the remaining runs start as before
+				return runs[k..].iter().map(lateness).fold(result, max);
+			}
+			time = max(time, release_times[run.job]) + ptimes[run.job];
+			result = max(result, time - due_times[run.job]);
+		}
+		result
+	}
+
+	/// Returns the maximum weighted lateness max w_j L_j of this MachineSchedule,
+	/// where L_j is the lateness of job j with respect to its completion time.
+	/// Early jobs have negative lateness, so their weighted lateness decreases with their weight,
+	/// and the result is negative if all jobs are early.
+	///
+	/// # Arguments:
+	/// * `due_times` A vector containing at position `i` the due date for job `i`.
+	/// * `weights` A vector containing at position `i` the non-negative weight of job `i`.
+	///
+	/// # Panics
+	/// If the schedule is empty.
+	pub fn max_weighted_lateness(&self, due_times: &[Time], weights: &[Time]) -> Time {
+		self.latenesses(due_times).into_iter()
+			.map(|(job, l)| weights[job] * l)
+			.max()
+			.expect("MachineSchedule is empty")
+	}
+
+	/// Returns the total weighted lateness sum w_j L_j of this MachineSchedule,
+	/// where L_j is the lateness of job j with respect to its completion time.
+	/// Early jobs have negative lateness and decrease the total.
+	///
+	/// # Arguments:
+	/// * `due_times` A vector containing at position `i` the due date for job `i`.
+	/// * `weights` A vector containing at position `i` the weight of job `i`.
+	pub fn total_weighted_lateness(&self, due_times: &[Time], weights: &[Time]) -> Time {
+		self.latenesses(due_times).into_iter().map(|(job, l)| weights[job] * l).sum()
+	}
+
+	/// Returns the total weighted flow time sum w_j (C_j - r_j) of this MachineSchedule,
+	/// where C_j is the completion time of job j, i.e. the end of its last run.
+	///
+	/// # Arguments:
+	/// * `release_times` A vector containing at position `i` the release time of job `i`.
+	/// * `weights` A vector containing at position `i` the weight of job `i`.
+	pub fn weighted_flow_time(&self, release_times: &[Time], weights: &[Time]) -> Time {
+		self.completion_times().into_iter().map(|(job, c)| weights[job] * (c - release_times[job])).sum()
+	}
+
+	/// Returns the mean slowdown of the jobs of this MachineSchedule, where the slowdown of job j is
+	/// its flow time C_j - r_j divided by its processing time, the total duration of its runs.
+	/// To avoid a division by zero, the flow time of a job with processing time 0 is divided by 1 instead,
+	/// so such a job has slowdown 0 if it completes at its release time.
+	///
+	/// # Arguments:
+	/// * `release_times` A vector containing at position `i` the release time of job `i`.
+	///
+	/// # Returns
+	/// The mean slowdown, or 0 if the schedule is empty.
+	pub fn mean_slowdown(&self, release_times: &[Time]) -> f64 {
+		let completion_times = self.completion_times();
+		if completion_times.is_empty() {
+			return 0.0;
+		}
+		let num_jobs = self.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0);
+		let mut ptimes = vec![0; num_jobs];
+		for run in self.schedule.iter() {
+			ptimes[run.job] += run.duration;
+		}
+		let total: f64 = completion_times.iter()
+			.map(|&(job, c)| (c - release_times[job]) as f64 / max(ptimes[job], 1) as f64)
+			.sum();
+		total / completion_times.len() as f64
+	}
+
 	/// Returns the number of tardy jobs in this MachineSchedule.
+	/// Every job counts at most once, based on its completion time,
+	/// so a preempted job is counted once even if several of its runs end after its due time.
 	pub fn num_tardy(&self, due_times: &[Time]) -> usize {
-		self.schedule.iter().filter(|&run| {
-			run.time + run.duration > due_times[run.job]
-		}).count()
+		self.completion_times().iter().filter(|&&(job, c)| c > due_times[job]).count()
 	}
-}
 
-impl fmt::Display for MachineSchedule {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	/// Brings this schedule into normal form:
+	/// runs are sorted by start time, and consecutive runs of the same job
+	/// without idle time between them are merged.
+	///
+	/// # Arguments
+	/// * `drop_zero_durations`: If set, runs of duration 0 are removed.
+	///   Note that this removes jobs with processing time 0 from the schedule entirely.
+	pub fn normalize(&mut self, drop_zero_durations: bool) {
+		if drop_zero_durations {
+			self.schedule.retain(|run| run.duration != 0);
+		}
+		self.schedule.sort_by_key(|run| run.time);
+		let mut normalized: Vec<JobRun> = Vec::with_capacity(self.schedule.len());
+		for run in self.schedule.drain(..) {
+			match normalized.last_mut() {
+				Some(last) if last.job == run.job && last.time + last.duration == run.time => {
+					last.duration += run.duration;
+				},
+				_ => normalized.push(run),
+			}
+		}
+		self.schedule = normalized;
+	}
+
+	/// Formats this MachineSchedule as text according to the given options.
+	/// `Display` uses the default options.
+	///
+	/// # Panics
+	/// If `opts.time_format` is a `Decimal` format with a scale that is not positive.
+	pub fn format(&self, opts: &DisplayOptions) -> String {
+		let mut result = String::new();
+		self.write_formatted(&mut result, opts).expect("Writing to a String cannot fail");
+		result
+	}
+
+	fn write_formatted<W: Write>(&self, w: &mut W, opts: &DisplayOptions) -> fmt::Result {
 		if self.schedule.is_empty() {
-			write!(f, "(Empty MachineSchedule)")
+			write!(w, "(Empty MachineSchedule)")
 		} else {
-			let maxlen = self.makespan().to_string().len();
-			for run in self.schedule.iter(){
-				writeln!(f,
-					"{:len$}-{:len$}: Job #{}",
-					run.time,
-					run.time + run.duration,
-					run.job,
-					len = maxlen
-				)?;
+			RowFormatter::new(opts, self.schedule.iter()).write_runs(w, &self.schedule)
+		}
+	}
+
+	/// Returns every job of this MachineSchedule together with its completion time,
+	/// sorted by completion time.
+	/// For preemptive schedules, the completion time of a job is the end of its last run.
+	pub fn completion_times(&self) -> Vec<(Job, Time)> {
+		let num_jobs = self.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0);
+		let mut seen = vec![false; num_jobs];
+		let mut result: Vec<(Job, Time)> = self.schedule.iter().rev().filter_map(|run| {
+			if seen[run.job] {
+				None
+			} else {
+				seen[run.job] = true;
+				Some((run.job, run.time + run.duration))
+			}
+		}).collect();
+		result.reverse();
+		result
+	}
+
+	/// Returns the job of every run in order of start time.
+	/// For preemptive schedules, a job occurs once for each of its runs,
+	/// see `distinct_job_order` for an order containing every job once.
+	pub fn job_order(&self) -> Vec<Job> {
+		let mut runs: Vec<&JobRun> = self.schedule.iter().collect();
+		runs.sort_by_key(|run| run.time);
+		runs.into_iter().map(|run| run.job).collect()
+	}
+
+	/// Returns every job once, in order of the start time of its first run.
+	pub fn distinct_job_order(&self) -> Vec<Job> {
+		let num_jobs = self.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0);
+		let mut seen = vec![false; num_jobs];
+		self.job_order().into_iter().filter(|&job| !std::mem::replace(&mut seen[job], true)).collect()
+	}
+
+	/// Returns whether this schedule is preemptive, i.e. whether some job has more than one run.
+	/// Metrics based on completion times, such as `latenesses`, use the last run of every job,
+	/// while per-run views such as `job_order` contain a preempted job several times.
+	/// Note that `normalize` merges consecutive runs of a job, which may make a schedule non-preemptive.
+	pub fn is_preemptive(&self) -> bool {
+		let num_jobs = self.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0);
+		let mut seen = vec![false; num_jobs];
+		self.schedule.iter().any(|run| std::mem::replace(&mut seen[run.job], true))
+	}
+
+	/// Returns every job of this MachineSchedule together with its lateness,
+	/// i.e. its completion time minus its due time, sorted by completion time.
+	/// For preemptive schedules, the completion time of a job is the end of its last run.
+	pub fn latenesses(&self, due_times: &[Time]) -> Vec<(Job, Time)> {
+		self.completion_times().into_iter().map(|(job, c)| (job, c - due_times[job])).collect()
+	}
+
+	/// Returns every job of this MachineSchedule together with its tardiness,
+	/// i.e. its lateness or 0 if the job is on time, sorted by completion time.
+	pub fn tardinesses(&self, due_times: &[Time]) -> Vec<(Job, Time)> {
+		self.latenesses(due_times).into_iter().map(|(job, l)| (job, max(0, l))).collect()
+	}
+
+	/// Returns whether no run starts before the release time of its job.
+	/// Metrics such as `max_lateness` do not check this, so a schedule built without release times
+	/// should be checked before its metrics are trusted for an instance with release times.
+	///
+	/// # Arguments
+	/// * `release_times`: `release_times[i]` is the release time of job `i`
+	pub fn respects_releases(&self, release_times: &[Time]) -> bool {
+		self.schedule.iter().all(|run| run.time >= release_times[run.job])
+	}
+
+	/// Moves every run of this schedule by `delta`, which may be negative.
+	/// Useful for appending a schedule after a prefix that ends at time `delta`.
+	pub fn shift_by(&mut self, delta: Time) {
+		for run in self.schedule.iter_mut() {
+			run.time += delta;
+		}
+	}
+
+	/// Returns the part of this schedule up to time `t`, e.g. the work committed in rolling-horizon planning.
+	/// Runs completing by `t` (including runs ending exactly at `t`) are kept,
+	/// a run in progress at `t` is shortened to end at `t`, and runs starting at or after `t` are dropped,
+	/// except for runs of duration 0 at `t`, which complete at `t`.
+	/// The order of the kept runs is unchanged.
+	pub fn truncate_at(&self, t: Time) -> MachineSchedule {
+		let schedule = self.schedule.iter()
+			.filter(|run| run.time < t || (run.time == t && run.duration == 0))
+			.map(|run| JobRun{ duration: run.duration.min(t - run.time), ..*run })
+			.collect();
+		MachineSchedule{ schedule }
+	}
+
+	/// Returns a fingerprint of this schedule that is stable across platforms and Rust versions,
+	/// e.g. as a key for caching solver results.
+	/// Schedules that are equal after `normalize(false)` have the same fingerprint.
+	///
+	/// The fingerprint is the 64-bit FNV-1a hash of the number of runs of the normalized schedule
+	/// followed by the start time, job and duration of every run,
+	/// each written as 8 bytes in little-endian two's complement.
+	pub fn canonical_hash(&self) -> u64 {
+		let mut normalized = self.clone();
+		normalized.normalize(false);
+		let mut fingerprint = Fingerprint::new();
+		fingerprint.write_i64(normalized.schedule.len() as i64);
+		for run in normalized.schedule.iter() {
+			fingerprint.write_i64(run.time as i64);
+			fingerprint.write_i64(run.job as i64);
+			fingerprint.write_i64(run.duration as i64);
+		}
+		fingerprint.finish()
+	}
+
+	/// Checks whether two schedules are equivalent with respect to completion-time based objectives:
+	/// every job completes at the same time in both schedules, jobs complete in the same order,
+	/// and every job is processed for the same total time.
+	/// Unlike `==`, schedules may differ in how the processing of a preempted job is split into runs.
+	/// For non-preemptive schedules, this requires every job to start at the same time in both schedules.
+	pub fn semantically_equal(&self, other: &Self) -> bool {
+		let processing_times = |s: &MachineSchedule| {
+			let mut result = vec![0; s.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0)];
+			for run in s.schedule.iter() {
+				result[run.job] += run.duration;
+			}
+			result
+		};
+		self.completion_times() == other.completion_times()
+			&& processing_times(self) == processing_times(other)
+	}
+
+	/// Writes this schedule in the JSON exchange format for solutions, a list of runs:
+	/// ```text
+	/// {"schedule": [
+	///   {"time": 0, "job": 1, "duration": 2},
+	///   {"time": 2, "job": 0, "duration": 3}
+	/// ]}
+	/// ```
+	/// `time` is the start time of the run.
+	/// With the `serde` feature, serializing with serde_json yields the same fields.
+	pub fn to_json(&self) -> String {
+		let mut out = String::from("{\"schedule\": [");
+		for (i, run) in self.schedule.iter().enumerate() {
+			out.push_str(if i == 0 { "\n  " } else { ",\n  " });
+			write!(out, "{{\"time\": {}, \"job\": {}, \"duration\": {}}}", run.time, run.job, run.duration).unwrap();
+		}
+		if !self.schedule.is_empty() {
+			out.push('\n');
+		}
+		out.push_str("]}\n");
+		out
+	}
+
+	/// Returns the runs of this schedule as CSV with a header, one row per run in the order of the schedule:
+	/// ```text
+	/// job,start,end,duration
+	/// 1,0,2,2
+	/// 0,2,5,3
+	/// ```
+	pub fn to_csv(&self) -> String {
+		let mut out = String::from("job,start,end,duration\n");
+		for run in self.schedule.iter() {
+			writeln!(out, "{},{},{},{}", run.job, run.time, run.time + run.duration, run.duration).unwrap();
+		}
+		out
+	}
+
+	/// Reads a schedule in the JSON exchange format described at `to_json`.
+	///
+	/// # Returns
+	/// The schedule, or an error if the document does not match the format or a run has a negative duration.
+	pub fn from_json(text: &str) -> Result<MachineSchedule, ScheduleError> {
+		let value = json::Value::parse(text)?;
+		let [runs] = json::fields(value.as_object("schedule")?, ["schedule"], "schedule")?;
+		let Some(json::Value::Array(runs)) = runs else {
+			return Err(json::invalid("field \"schedule\" must be an array of runs".to_string()));
+		};
+		let keys = ["time", "job", "duration"];
+		let schedule = runs.iter().map(|run| {
+			let fields = json::fields(run.as_object("run")?, keys, "run")?;
+			let mut values = [0; 3];
+			for ((value, field), key) in values.iter_mut().zip(fields).zip(keys) {
+				let field = field.ok_or_else(|| json::invalid(format!("missing field \"{}\" in run", key)))?;
+				*value = field.as_time(key)?;
+			}
+			let [time, job, duration] = values;
+			let job = Job::try_from(job).map_err(|_| json::invalid("job must not be negative".to_string()))?;
+			if duration < 0 {
+				return Err(ScheduleError::NegativeDuration{ job, duration });
 			}
-			Ok(())
+			Ok(JobRun{ time, job, duration })
+		}).collect::<Result<_, _>>()?;
+		Ok(MachineSchedule{ schedule })
+	}
+}
+
+/// Aggregates of a single machine schedule, computed run by run without storing the runs.
+/// Pass `|run| stats.record(run)` to a streaming function such as `schrage_streaming`.
+#[derive(Debug, Clone)]
+pub struct ScheduleStats<'a> {
+	due_times: &'a [Time],
+	/// The number of recorded runs
+	pub num_runs: usize,
+	/// The latest completion of any run, or 0 if there are none
+	pub makespan: Time,
+	/// The maximum lateness of any run, or None if there are none
+	pub max_lateness: Option<Time>,
+	/// The sum of the completion times of all runs,
+	/// which is the total completion time if every job has a single run
+	pub total_completion_time: Time,
+}
+
+impl<'a> ScheduleStats<'a> {
+	/// Returns the aggregates of an empty schedule, where `due_times[i]` is the due time of job i.
+	pub fn new(due_times: &'a [Time]) -> ScheduleStats<'a> {
+		ScheduleStats{ due_times, num_runs: 0, makespan: 0, max_lateness: None, total_completion_time: 0 }
+	}
+
+	/// Adds a run to the aggregates.
+	pub fn record(&mut self, run: JobRun) {
+		let end = run.time + run.duration;
+		let lateness = end - self.due_times[run.job];
+		self.num_runs += 1;
+		self.makespan = max(self.makespan, end);
+		self.max_lateness = Some(self.max_lateness.map_or(lateness, |l| max(l, lateness)));
+		self.total_completion_time += end;
+	}
+}
+
+impl Default for MachineSchedule {
+	fn default() -> Self {
+		MachineSchedule::new()
+	}
+}
+
+impl fmt::Display for MachineSchedule {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.write_formatted(f, &DisplayOptions::default())
+	}
+}
+
+
+/// Checks whether two schedules are identical after renaming the jobs of `a` by a bijection,
+/// e.g. when comparing solutions of instances that only differ in the order of their jobs.
+/// Both schedules are compared in their `normalize(false)` form.
+/// Runs that start at the same time are matched in the order in which they appear in the schedules.
+///
+/// # Arguments
+/// * `mapping_out`: Is cleared. If the schedules are equivalent, `mapping_out[j]` is then the job of `b`
+///   that job `j` of `a` corresponds to, or None if `j` does not occur in `a`.
+///
+/// # Returns
+/// Whether such a bijection between the jobs of both schedules exists.
+pub fn equivalent_up_to_job_relabeling(
+	a: &MachineSchedule,
+	b: &MachineSchedule,
+	mapping_out: &mut Vec<Option<Job>>
+) -> bool
+{
+	mapping_out.clear();
+	let (mut a, mut b) = (a.clone(), b.clone());
+	a.normalize(false);
+	b.normalize(false);
+	if a.schedule.len() != b.schedule.len() {
+		return false;
+	}
+	let num_jobs_b = b.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0);
+	let mut inverse: Vec<Option<Job>> = vec![None; num_jobs_b];
+	mapping_out.resize(a.schedule.iter().map(|run| run.job + 1).max().unwrap_or(0), None);
+	for (x, y) in a.schedule.iter().zip(b.schedule.iter()) {
+		if x.time != y.time || x.duration != y.duration {
+			return false;
+		}
+		match (mapping_out[x.job], inverse[y.job]) {
+			(None, None) => {
+				mapping_out[x.job] = Some(y.job);
+				inverse[y.job] = Some(x.job);
+			},
+			(Some(j), Some(i)) if j == y.job && i == x.job => {},
+			_ => return false,
 		}
 	}
+	true
 }
 
 
 /// A schedule of jobs on a set of mutliple machines
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct MultiMachineSchedule {
 	/// The schedule for each machine
 	pub machine_schedules: Vec<MachineSchedule>,
@@ -145,18 +713,485 @@ impl MultiMachineSchedule {
 		self.machine_schedules.iter().map( |s| s.makespan() ).max().unwrap_or(0)
 	}
 
+	/// Returns the maximum lateness over all runs on all machines for the given due dates.
+	/// Since the last run of a job has the largest lateness, this is the maximum lateness
+	/// with respect to each job's completion time, also if jobs are processed on several machines
+	/// (e.g. in a flow shop, where a job completes on the last machine).
+	///
+	/// # Arguments:
+	/// * `due_times` A vector containing at position `i` the due date for job `i`.
+	pub fn max_lateness(&self, due_times: &[Time]) -> Time {
+		self.machine_schedules.iter().flat_map(|s| s.schedule.iter()).map(|run| {
+			run.time + run.duration - due_times[run.job]
+		}).max().expect("MultiMachineSchedule is empty")
+	}
+
+	/// Returns the total tardiness for the given due dates.
+	/// Every job counts once with its completion time, i.e. the end of its last run on any machine.
+	/// In a flow shop schedule, this is the completion on the last machine.
+	///
+	/// # Arguments:
+	/// * `due_times` A vector containing at position `i` the due date for job `i`.
+	pub fn total_tardiness(&self, due_times: &[Time]) -> Time {
+		self.completion_times().iter().map(|&(job, c)| max(0, c - due_times[job])).sum()
+	}
+
+	/// Returns the total completion time sum C_j,
+	/// where jobs complete with their last run on any machine.
+	/// In a flow shop schedule, these are the completions on the last machine.
+	pub fn total_completion_time(&self) -> Time {
+		self.completion_times().iter().map(|&(_, c)| c).sum()
+	}
+
+	/// Returns the weighted machine cost sum cost_i * load_i,
+	/// where the load of a machine is the total duration of its runs.
+	///
+	/// # Arguments
+	/// * `costs`: `costs[i]` is the cost of machine i per time unit it processes a job.
+	pub fn weighted_machine_cost(&self, costs: &[Time]) -> Time {
+		self.machine_schedules.iter().zip(costs.iter())
+			.map(|(s, &cost)| cost * s.schedule.iter().map(|run| run.duration).sum::<Time>())
+			.sum()
+	}
+
+	/// Returns the number of tardy jobs for the given due dates.
+	/// Every job counts at most once, based on its completion time, i.e. the end of its last run on any machine.
+	/// In a flow shop schedule, this is the completion on the last machine.
+	///
+	/// # Arguments:
+	/// * `due_times` A vector containing at position `i` the due date for job `i`.
+	pub fn num_tardy(&self, due_times: &[Time]) -> usize {
+		self.completion_times().iter().filter(|&&(job, c)| c > due_times[job]).count()
+	}
+
+	/// Returns every job of this schedule together with its completion time,
+	/// sorted by completion time.
+	/// If a job is processed on several machines (e.g. in a flow shop),
+	/// its completion time is the end of its last run on any machine.
+	pub fn completion_times(&self) -> Vec<(Job, Time)> {
+		let runs = || self.machine_schedules.iter().flat_map(|s| s.schedule.iter());
+		let num_jobs = runs().map(|run| run.job + 1).max().unwrap_or(0);
+		let mut completion = vec![None; num_jobs];
+		for run in runs() {
+			completion[run.job] = max(completion[run.job], Some(run.time + run.duration));
+		}
+		let mut result: Vec<(Job, Time)> = completion.into_iter().enumerate()
+			.filter_map(|(job, c)| c.map(|c| (job, c)))
+			.collect();
+		result.sort_unstable_by_key(|&(job, c)| (c, job));
+		result
+	}
+
+	/// Returns every job of a job shop schedule together with its tardiness,
+	/// i.e. the time by which the last operation of the job completes after the job's due date, or 0 if it is on time.
+	/// The jobs are sorted by completion time.
+	///
+	/// # Arguments
+	/// * `instance`: The job shop instance, whose due times are used
+	///
+	/// # Panics
+	/// If the instance has no due times.
+	pub fn job_shop_tardiness(&self, instance: &JobShopInstance) -> Vec<(Job, Time)> {
+		let due_times = instance.due_times.as_ref().expect("Instance has no due times");
+		self.completion_times().into_iter().map(|(job, c)| (job, max(0, c - due_times[job]))).collect()
+	}
+
+	/// Returns all runs of the given job on any machine, together with the machine, sorted by start time.
+	/// In a flow shop schedule, this is the path of the job through the machines.
+	/// Runs starting at the same time are ordered by machine.
+	pub fn job_timeline(&self, job: Job) -> Vec<(Machine, JobRun)> {
+		let mut result: Vec<(Machine, JobRun)> = self.machine_schedules.iter().enumerate()
+			.flat_map(|(machine, s)| s.schedule.iter().filter(|run| run.job == job).map(move |run| (machine, *run)))
+			.collect();
+		result.sort_by_key(|&(machine, run)| (run.time, machine));
+		result
+	}
+
+	/// Returns for every job how often it migrates in a preemptive schedule,
+	/// i.e. the number of consecutive runs of the job (in order of start time) on different machines.
+	/// The result has an entry for every job up to the largest job of the schedule.
+	pub fn count_migrations(&self) -> Vec<usize> {
+		let runs = || self.machine_schedules.iter().enumerate()
+			.flat_map(|(machine, s)| s.schedule.iter().map(move |run| (machine, run)));
+		let num_jobs = runs().map(|(_, run)| run.job + 1).max().unwrap_or(0);
+		let mut fragments: Vec<(Time, Time, Job, Machine)> = runs()
+			.map(|(machine, run)| (run.time, run.duration, run.job, machine))
+			.collect();
+		fragments.sort_unstable();
+		let mut last_machine = vec![None; num_jobs];
+		let mut migrations = vec![0; num_jobs];
+		for (_, _, job, machine) in fragments {
+			if last_machine[job].is_some_and(|m| m != machine) {
+				migrations[job] += 1;
+			}
+			last_machine[job] = Some(machine);
+		}
+		migrations
+	}
+
+	/// Re-packs a preemptive schedule to reduce the migrations counted by `count_migrations`,
+	/// aiming for at most `max_per_job` migrations of every job.
+	/// The time is split into slots at the start and end of every run,
+	/// and within each slot the jobs processed at that time are permuted among the machines,
+	/// so every job is processed at exactly the same times as before and the makespan does not change.
+	/// Slots are assigned in order of time, where a job keeps the machine of its previous fragment if possible.
+	/// If several jobs were last on the same machine, the jobs that already migrated `max_per_job` times
+	/// and then the job that was processed most recently keep it.
+	/// Other jobs stay on their original machine if it is free, or go to the free machine with the smallest index.
+	/// Consecutive fragments of a job on the same machine are merged into one run,
+	/// and runs of duration 0 stay on their original machine.
+	///
+	/// This is a heuristic, which cannot remove migrations that are unavoidable,
+	/// e.g. the job split at the wrap-around of McNaughton's algorithm.
+	/// If the re-packed schedule has a larger maximum or a larger total number of migrations,
+	/// a copy of this schedule is returned.
+	/// Runs in O(k m log m) time for k slots and m machines.
+	///
+	/// # Panics
+	/// If a job is processed on several machines at the same time.
+	pub fn reduce_migrations(&self, max_per_job: usize) -> MultiMachineSchedule {
+		let m = self.num_machines();
+		let runs = || self.machine_schedules.iter().flat_map(|s| s.schedule.iter());
+		let num_jobs = runs().map(|run| run.job + 1).max().unwrap_or(0);
+		let mut points: Vec<Time> = runs().flat_map(|run| [run.time, run.time + run.duration]).collect();
+		points.sort_unstable();
+		points.dedup();
+		let index = |t: Time| points.binary_search(&t).unwrap();
+		let num_slots = points.len().saturating_sub(1);
+		// occupant[k][i] is the job processed by machine i in slot k of the original schedule
+		let mut occupant: Vec<Vec<Option<Job>>> = vec![vec![None; m]; num_slots];
+		for (machine, s) in self.machine_schedules.iter().enumerate() {
+			for run in s.schedule.iter() {
+				for slot in occupant[index(run.time)..index(run.time + run.duration)].iter_mut() {
+					slot[machine] = Some(run.job);
+				}
+			}
+		}
+		let mut result = MultiMachineSchedule::with_machines(m);
+		let mut last_machine: Vec<Option<Machine>> = vec![None; num_jobs];
+		let mut last_end = vec![Time::MIN; num_jobs];
+		let mut migrations = vec![0; num_jobs];
+		for (k, slot) in occupant.iter().enumerate() {
+			let mut jobs: Vec<(Job, Machine)> = slot.iter().enumerate()
+				.filter_map(|(machine, job)| job.map(|job| (job, machine)))
+				.collect();
+			jobs.sort_unstable_by_key(|&(job, _)| (migrations[job] < max_per_job, Reverse(last_end[job]), job));
+			for pair in jobs.windows(2) {
+				assert_ne!(pair[0].0, pair[1].0, "Job #{} is processed on several machines at time {}", pair[0].0, points[k]);
+			}
+			let mut assigned: Vec<Option<Job>> = vec![None; m];
+			let mut unassigned = Vec::new();
+			for &(job, original) in jobs.iter() {
+				match last_machine[job] {
+					Some(machine) if assigned[machine].is_none() => assigned[machine] = Some(job),
+					_ => unassigned.push((job, original)),
+				}
+			}
+			for (job, original) in unassigned {
+				let machine = if assigned[original].is_none() {
+					original
+				} else {
+					assigned.iter().position(|a| a.is_none()).unwrap()
+				};
+				assigned[machine] = Some(job);
+				if last_machine[job].is_some() {
+					migrations[job] += 1;
+				}
+			}
+			for (machine, job) in assigned.into_iter().enumerate() {
+				let Some(job) = job else { continue };
+				let (start, end) = (points[k], points[k + 1]);
+				let schedule = &mut result.machine_schedules[machine].schedule;
+				match schedule.last_mut() {
+					Some(last) if last.job == job && last.time + last.duration == start => last.duration += end - start,
+					_ => schedule.push(JobRun{ time: start, job, duration: end - start }),
+				}
+				last_machine[job] = Some(machine);
+				last_end[job] = end;
+			}
+		}
+		for (machine, s) in self.machine_schedules.iter().enumerate() {
+			let schedule = &mut result.machine_schedules[machine].schedule;
+			schedule.extend(s.schedule.iter().filter(|run| run.duration == 0));
+			schedule.sort_by_key(|run| (run.time, run.duration));
+		}
+		if migrations_increase(&result.count_migrations(), &self.count_migrations()) {
+			return self.clone();
+		}
+		result
+	}
+
+	/// Delays every run as much as possible without changing the makespan or making a job later than it is,
+	/// e.g. to complete jobs just in time instead of early.
+	/// A backward pass over the runs, latest start first, moves every run to end at the earliest of
+	/// the start of the next run on its machine, the start of the next run of its job, and the makespan.
+	/// The last run of a job also ends no later than the due time of the job, or its current end if the job is tardy,
+	/// and no later than the first run of every job it precedes.
+	/// For a feasible schedule, the result is feasible, runs only move later,
+	/// and the total earliness is minimal among such delays, while the tardiness of every job is unchanged.
+	/// The runs of every machine must be sorted by start time.
+	///
+	/// # Arguments
+	/// * `due_times`: `due_times[j]` is the due time of job j
+	/// * `precedents`: If given, `precedents[j]` are the jobs that need to be completed before job j can be started
+	///
+	/// # Panics
+	/// If a run processes a job without a due time.
+	pub fn right_shift(&self, due_times: &[Time], precedents: Option<&[Vec<Job>]>) -> MultiMachineSchedule {
+		let makespan = self.makespan();
+		let num_jobs = due_times.len();
+		let mut successors: Vec<Vec<Job>> = vec![Vec::new(); num_jobs];
+		for (job, predecessors) in precedents.unwrap_or(&[]).iter().enumerate() {
+			for &predecessor in predecessors {
+				successors[predecessor].push(job);
+			}
+		}
+		let mut runs: Vec<(Machine, usize)> = self.machine_schedules.iter().enumerate()
+			.flat_map(|(machine, s)| (0..s.schedule.len()).map(move |i| (machine, i)))
+			.collect();
+		runs.sort_unstable_by_key(|&(machine, i)| {
+			let run = self.machine_schedules[machine].schedule[i];
+			Reverse((run.time, run.time + run.duration, machine, i))
+		});
+		let mut result = self.clone();
+		// the new start of the earliest run of every job shifted so far
+		let mut first_start: Vec<Option<Time>> = vec![None; num_jobs];
+		for (machine, i) in runs {
+			let run = self.machine_schedules[machine].schedule[i];
+			let mut end = makespan;
+			if let Some(next) = result.machine_schedules[machine].schedule.get(i + 1) {
+				end = end.min(next.time);
+			}
+			match first_start[run.job] {
+				Some(next) => end = end.min(next),
+				None => {
+					end = end.min(max(due_times[run.job], run.time + run.duration));
+					for &successor in successors[run.job].iter() {
+						if let Some(start) = first_start[successor] {
+							end = end.min(start);
+						}
+					}
+				},
+			}
+			let time = max(run.time, end - run.duration);
+			result.machine_schedules[machine].schedule[i].time = time;
+			first_start[run.job] = Some(time);
+		}
+		result
+	}
+
+	/// Returns the runs of this schedule as CSV with a header, one row per run,
+	/// ordered by machine and then by the order of the runs on the machine:
+	/// ```text
+	/// machine,job,start,end,duration
+	/// 0,1,0,2,2
+	/// 1,0,0,3,3
+	/// ```
+	pub fn to_csv(&self) -> String {
+		let mut out = String::from("machine,job,start,end,duration\n");
+		for (machine, s) in self.machine_schedules.iter().enumerate() {
+			for run in s.schedule.iter() {
+				writeln!(out, "{},{},{},{},{}", machine, run.job, run.time, run.time + run.duration, run.duration).unwrap();
+			}
+		}
+		out
+	}
+
+	/// Formats this schedule as text according to the given options,
+	/// printing the runs of each machine below a header with the machine's index and makespan.
+	/// All machines use the same time origin and column widths.
+	/// `Display` uses the default options.
+	///
+	/// # Panics
+	/// If `opts.time_format` is a `Decimal` format with a scale that is not positive.
+	pub fn format(&self, opts: &DisplayOptions) -> String {
+		let mut result = String::new();
+		self.write_formatted(&mut result, opts).expect("Writing to a String cannot fail");
+		result
+	}
+
+	fn write_formatted<W: Write>(&self, w: &mut W, opts: &DisplayOptions) -> fmt::Result {
+		if self.machine_schedules.is_empty() {
+			return write!(w, "(Empty MultiMachineSchedule)");
+		}
+		let rows = RowFormatter::new(opts, self.machine_schedules.iter().flat_map(|s| s.schedule.iter()));
+		for (i, s) in self.machine_schedules.iter().enumerate() {
+			writeln!(w, "Machine #{} (makespan {}):", i, rows.format_time(s.makespan()))?;
+			if s.schedule.is_empty() {
+				writeln!(w, "(Empty MachineSchedule)")?;
+			} else {
+				rows.write_runs(w, &s.schedule)?;
+			}
+		}
+		write!(w, "Makespan: {}", rows.format_time(self.makespan()))
+	}
+
 	/// Returns a schedule with no machines
 	pub fn new() -> MultiMachineSchedule {
 		MultiMachineSchedule { machine_schedules: Vec::new() }
 	}
 
+	/// Returns a schedule with `num_machines` machines without any runs.
+	pub fn with_machines(num_machines: usize) -> MultiMachineSchedule {
+		MultiMachineSchedule { machine_schedules: vec![MachineSchedule::new(); num_machines] }
+	}
+
+	/// Returns a parallel machine schedule in which machine i processes the jobs of `assignment[i]`
+	/// in the given order without idle time, starting at time 0.
+	/// Every machine is built with `MachineSchedule::from_order_ptimes`,
+	/// so a job assigned to several machines is processed on each of them.
+	///
+	/// # Arguments
+	/// * assignment: assignment[i] is the order of the jobs processed by machine i.
+	/// * ptimes: ptimes[j] is the processing time of job j on any machine.
+	///
+	/// # Panics
+	/// If a processing time is negative.
+	pub fn from_assignment(assignment: &[Vec<Job>], ptimes: &[Time]) -> MultiMachineSchedule {
+		assert_valid_ptimes(ptimes);
+		MultiMachineSchedule{
+			machine_schedules: assignment.iter()
+				.map(|jobs| MachineSchedule::from_order_ptimes(jobs.iter().copied(), ptimes))
+				.collect()
+		}
+	}
+
+	/// Returns the number of machines of this schedule, including machines without runs.
+	pub fn num_machines(&self) -> usize {
+		self.machine_schedules.len()
+	}
+
+	/// Appends a run to the schedule of a machine.
+	///
+	/// # Returns
+	/// An error if the machine does not exist, the duration is negative,
+	/// or the run starts before the last run of the machine completes.
+	/// The schedule is unchanged in this case.
+	pub fn push_run(&mut self, machine: Machine, run: JobRun) -> Result<(), ScheduleError> {
+		let num_machines = self.num_machines();
+		let schedule = self.machine_schedules.get_mut(machine)
+			.ok_or(ScheduleError::MachineOutOfRange{ machine, num_machines })?;
+		if run.duration < 0 {
+			return Err(ScheduleError::NegativeDuration{ job: run.job, duration: run.duration });
+		}
+		if let Some(last) = schedule.schedule.last() {
+			let previous_end = last.time + last.duration;
+			if run.time < previous_end {
+				return Err(ScheduleError::RunOutOfOrder{ machine, time: run.time, previous_end });
+			}
+		}
+		schedule.schedule.push(run);
+		Ok(())
+	}
+
+	/// Appends a run like `push_run`, but only checks it in debug builds,
+	/// for algorithms that construct valid schedules by design.
+	///
+	/// # Panics
+	/// If `push_run` would return an error in a debug build,
+	/// or if the machine does not exist.
+	pub(crate) fn append_run(&mut self, machine: Machine, run: JobRun) {
+		if cfg!(debug_assertions) {
+			if let Err(error) = self.push_run(machine, run) {
+				panic!("{}", error);
+			}
+		} else {
+			self.machine_schedules[machine].schedule.push(run);
+		}
+	}
+
 	/// Returns a schedule in which each job is processod on machine 0, 1, 2,... in order
 	/// and every machine processes the jobs according to the given `order`.
 	///
 	/// # Arguments
 	/// * order: Order in which jobs are processed by each machine
 	/// * ptimes: ptimes[i][j] is the time taken by machine i for job j.
+	///
+	/// # Panics
+	/// If a processing time is negative.
 	pub fn from_order_ptimes(order: &[Job], ptimes: &[Vec<Time>]) -> MultiMachineSchedule {
+		let transport = vec![0; ptimes.len().saturating_sub(1)];
+		MultiMachineSchedule::from_order_ptimes_with_transport(order, ptimes, &transport)
+	}
+
+	/// Like `from_order_ptimes`, but jobs need some time to get from one machine to the next.
+	/// A job completed on machine i can start on machine i+1 only `transport[i]` time units later.
+	/// Neither machine is occupied during transport.
+	/// Since every job passes each transport stage once,
+	/// the completion times on the last machine increase by exactly the total transport time
+	/// compared to `from_order_ptimes`, for any order.
+	///
+	/// # Arguments
+	/// * order: Order in which jobs are processed by each machine
+	/// * ptimes: ptimes[i][j] is the time taken by machine i for job j.
+	/// * transport: transport[i] is the time needed to get a job from machine i to machine i+1.
+	///
+	/// # Panics
+	/// If a processing time or transport time is negative,
+	/// or if there is not exactly one transport time less than there are machines.
+	pub fn from_order_ptimes_with_transport(
+		order: &[Job],
+		ptimes: &[Vec<Time>],
+		transport: &[Time]
+	) -> MultiMachineSchedule {
+		assert!(transport.iter().all(|&t| t >= 0), "Transport times must not be negative");
+		if !ptimes.is_empty() {
+			assert_eq!(transport.len(), ptimes.len() - 1, "Expected one transport time between each pair of consecutive machines");
+		}
+		let release_times = vec![0; ptimes.first().map_or(0, |p| p.len())];
+		MultiMachineSchedule::from_order_ptimes_delays(order, ptimes, &release_times, |machine, _| transport[machine])
+	}
+
+	/// Like `from_order_ptimes`, but every job needs to wait for some time between consecutive machines.
+	/// A job j completed on machine i can start on machine i+1 only `lags[j]` time units later.
+	///
+	/// # Arguments
+	/// * order: Order in which jobs are processed by each machine
+	/// * ptimes: ptimes[i][j] is the time taken by machine i for job j.
+	/// * lags: lags[j] is the minimum delay of job j between consecutive machines.
+	///
+	/// # Panics
+	/// If a processing time or lag is negative.
+	pub fn from_order_ptimes_with_lags(
+		order: &[Job],
+		ptimes: &[Vec<Time>],
+		lags: &[Time]
+	) -> MultiMachineSchedule {
+		assert!(lags.iter().all(|&l| l >= 0), "Lags must not be negative");
+		let release_times = vec![0; ptimes.first().map_or(0, |p| p.len())];
+		MultiMachineSchedule::from_order_ptimes_delays(order, ptimes, &release_times, |_, job| lags[job])
+	}
+
+	/// Like `from_order_ptimes`, but jobs are released over time
+	/// and cannot start on machine 0 before their release time.
+	/// As in `MachineSchedule::from_order_ptimes_releasetimes`, no job starts before time 0.
+	///
+	/// # Arguments
+	/// * order: Order in which jobs are processed by each machine
+	/// * ptimes: ptimes[i][j] is the time taken by machine i for job j.
+	/// * release_times: release_times[j] is the release time of job j.
+	///
+	/// # Panics
+	/// If a processing time is negative.
+	pub fn from_order_ptimes_releasetimes(
+		order: &[Job],
+		ptimes: &[Vec<Time>],
+		release_times: &[Time]
+	) -> MultiMachineSchedule {
+		MultiMachineSchedule::from_order_ptimes_delays(order, ptimes, release_times, |_, _| 0)
+	}
+
+	/// Flow shop schedule in the given order, where `delay(i, j)` is the time job j needs
+	/// to get from machine i to machine i+1.
+	fn from_order_ptimes_delays<F>(
+		order: &[Job],
+		ptimes: &[Vec<Time>],
+		release_times: &[Time],
+		delay: F
+	) -> MultiMachineSchedule
+	where F: Fn(Machine, Job) -> Time
+	{
+		ptimes.iter().for_each(|p| assert_valid_ptimes(p));
 		let m = ptimes.len();
 		let mut result = MultiMachineSchedule{
 			machine_schedules: Vec::with_capacity(m)
@@ -165,8 +1200,8 @@ impl MultiMachineSchedule {
 			return result;
 		}
 		let n = ptimes[0].len();
-		let mut ready_times = vec![0; n]; // time when each job is ready to be processed further
-		for i in 0..m {
+		let mut ready_times = release_times.to_vec(); // time when each job is ready to be processed further
+		for (i, machine_ptimes) in ptimes.iter().enumerate() {
 			let mut time = 0;
 			let mut schedule = MachineSchedule{ schedule: Vec::with_capacity(n) };
 			for &j in order {
@@ -174,10 +1209,10 @@ impl MultiMachineSchedule {
 				schedule.schedule.push( JobRun{
 					time: start, 
 					job: j,
-					duration: ptimes[i][j],
+					duration: machine_ptimes[j],
 				});
-				time = start + ptimes[i][j];
-				ready_times[j] = time;
+				time = start + machine_ptimes[j];
+				ready_times[j] = if i + 1 < m { time + delay(i, j) } else { time };
 			}
 			result.machine_schedules.push(schedule);
 		}
@@ -185,14 +1220,33 @@ impl MultiMachineSchedule {
 	}
 }
 
+/// Returns whether the migration counts `new` have a larger maximum or a larger total than `old`.
+fn migrations_increase(new: &[usize], old: &[usize]) -> bool {
+	let max_of = |counts: &[usize]| counts.iter().copied().max().unwrap_or(0);
+	max_of(new) > max_of(old) || new.iter().sum::<usize>() > old.iter().sum::<usize>()
+}
+
+impl Default for MultiMachineSchedule {
+	fn default() -> Self {
+		MultiMachineSchedule::new()
+	}
+}
+
+impl fmt::Display for MultiMachineSchedule {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.write_formatted(f, &DisplayOptions::default())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::TimeFormat;
 
 	fn example_schedule_1() -> MachineSchedule {
 		MachineSchedule::from_ptimes_releasetimes(
-			&vec![ 5,  6,  7,  3,  6,  2],
-			&vec![10, 13, 11, 30,  0, 30]
+			&[ 5,  6,  7,  3,  6,  2],
+			&[10, 13, 11, 30,  0, 30]
 		)
 	}
 
@@ -201,6 +1255,253 @@ mod tests {
 		assert_eq!(example_schedule_1().makespan(), 41);
 	}
 
+	#[test]
+	fn test_push_run() {
+		let mut schedule = MultiMachineSchedule::with_machines(2);
+		assert_eq!(schedule.num_machines(), 2);
+		assert_eq!(schedule.push_run(1, JobRun{ time: 0, job: 0, duration: 3 }), Ok(()));
+		assert_eq!(schedule.push_run(1, JobRun{ time: 3, job: 1, duration: 0 }), Ok(()));
+		assert_eq!(schedule.push_run(0, JobRun{ time: 1, job: 2, duration: 2 }), Ok(()));
+		assert_eq!(
+			schedule.push_run(2, JobRun{ time: 5, job: 3, duration: 1 }),
+			Err(ScheduleError::MachineOutOfRange{ machine: 2, num_machines: 2 })
+		);
+		assert_eq!(
+			schedule.push_run(1, JobRun{ time: 2, job: 3, duration: 1 }),
+			Err(ScheduleError::RunOutOfOrder{ machine: 1, time: 2, previous_end: 3 })
+		);
+		assert_eq!(
+			schedule.push_run(0, JobRun{ time: 4, job: 3, duration: -1 }),
+			Err(ScheduleError::NegativeDuration{ job: 3, duration: -1 })
+		);
+		assert_eq!(schedule.machine_schedules[0].job_order(), vec![2]);
+		assert_eq!(schedule.machine_schedules[1].job_order(), vec![0, 1]);
+		assert_eq!(MultiMachineSchedule::new().num_machines(), 0);
+	}
+
+	/// McNaughton's wrap-around schedule for processing times 4, 4, 4 on 2 machines
+	fn wrap_around_schedule() -> MultiMachineSchedule {
+		MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![JobRun{ time: 0, job: 0, duration: 4 }, JobRun{ time: 4, job: 1, duration: 2 }] },
+			MachineSchedule{ schedule: vec![JobRun{ time: 0, job: 1, duration: 2 }, JobRun{ time: 2, job: 2, duration: 4 }] },
+		]}
+	}
+
+	#[test]
+	fn test_reduce_migrations() {
+		let schedule = wrap_around_schedule();
+		assert_eq!(schedule.count_migrations(), vec![0, 1, 0]);
+		// the split job cannot avoid migrating
+		assert_eq!(schedule.reduce_migrations(0), schedule);
+		// every job migrates once, which is avoidable for all but one job
+		let scrambled = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: 0, job: 0, duration: 2 },
+				JobRun{ time: 2, job: 2, duration: 2 },
+				JobRun{ time: 4, job: 1, duration: 2 },
+			]},
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: 0, job: 1, duration: 2 },
+				JobRun{ time: 2, job: 0, duration: 2 },
+				JobRun{ time: 4, job: 2, duration: 2 },
+			]},
+		]};
+		assert_eq!(scrambled.count_migrations(), vec![1, 1, 1]);
+		let reduced = scrambled.reduce_migrations(0);
+		assert_eq!(reduced.count_migrations(), vec![0, 1, 0]);
+		assert_eq!(reduced.makespan(), scrambled.makespan());
+		let timeline = |s: &MultiMachineSchedule, job| s.job_timeline(job).iter()
+			.map(|(_, run)| run.duration).sum::<Time>();
+		for job in 0..3 {
+			assert_eq!(timeline(&reduced, job), 4);
+		}
+		assert_eq!(reduced.machine_schedules[0].schedule[0], JobRun{ time: 0, job: 0, duration: 4 });
+	}
+
+	#[test]
+	fn test_migrations_increase() {
+		// a lower maximum does not make up for a larger total, and vice versa
+		assert!(migrations_increase(&[1, 1, 1], &[2, 0, 0]));
+		assert!(migrations_increase(&[2, 0, 0], &[1, 1, 0]));
+		assert!(!migrations_increase(&[1, 1, 0], &[2, 1, 0]));
+		assert!(!migrations_increase(&[0, 1, 0], &[0, 1, 0]));
+		assert!(!migrations_increase(&[], &[]));
+	}
+
+	#[test]
+	fn test_is_preemptive() {
+		let mut schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: 0, job: 1, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 1 },
+			JobRun{ time: 3, job: 0, duration: 4 },
+		]};
+		assert!(schedule.is_preemptive());
+		schedule.normalize(false);
+		assert!(!schedule.is_preemptive());
+		assert!(!MachineSchedule::new().is_preemptive());
+		assert!(!example_schedule_1().is_preemptive());
+	}
+
+	#[test]
+	fn test_from_assignment() {
+		let ptimes = vec![4, 2, 3, 5];
+		let schedule = MultiMachineSchedule::from_assignment(&[vec![3, 1], vec![], vec![2, 0]], &ptimes);
+		assert_eq!(schedule.num_machines(), 3);
+		assert_eq!(schedule.machine_schedules[0].schedule, vec![
+			JobRun{ time: 0, job: 3, duration: 5 },
+			JobRun{ time: 5, job: 1, duration: 2 },
+		]);
+		assert!(schedule.machine_schedules[1].schedule.is_empty());
+		assert_eq!(schedule.machine_schedules[2].job_order(), vec![2, 0]);
+		assert_eq!(schedule.makespan(), 7);
+	}
+
+	#[cfg(debug_assertions)]
+	#[test]
+	#[should_panic(expected = "overlaps the previous run")]
+	fn test_append_run_out_of_order() {
+		let mut schedule = MultiMachineSchedule::with_machines(1);
+		schedule.append_run(0, JobRun{ time: 0, job: 0, duration: 3 });
+		schedule.append_run(0, JobRun{ time: 2, job: 1, duration: 1 });
+	}
+
+	#[test]
+	fn test_schedule_stats() {
+		let ptimes = [5, 6, 7, 3, 6, 2];
+		let release_times = [10, 13, 11, 30, 0, 30];
+		let due_times = [19, 20, 24, 35, 17, 38];
+		let mut stats = ScheduleStats::new(&due_times);
+		assert_eq!(stats.max_lateness, None);
+		MachineSchedule::from_order_ptimes_releasetimes_streaming(0..6, &ptimes, &release_times, |run| stats.record(run));
+		let schedule = example_schedule_1();
+		assert_eq!(stats.num_runs, 6);
+		assert_eq!(stats.makespan, schedule.makespan());
+		assert_eq!(stats.max_lateness, Some(schedule.max_lateness(&due_times)));
+		assert_eq!(stats.total_completion_time, MultiMachineSchedule{ machine_schedules: vec![schedule] }.total_completion_time());
+	}
+
+	#[test]
+	fn test_shift_by() {
+		let due_times = [19, 20, 24, 35, 17, 38];
+		let mut schedule = example_schedule_1();
+		schedule.shift_by(7);
+		assert_eq!(schedule.makespan(), 41 + 7);
+		assert_eq!(schedule.max_lateness(&due_times), 22 + 7);
+		assert_eq!(schedule.schedule[0].time, example_schedule_1().schedule[0].time + 7);
+		schedule.shift_by(-7);
+		assert_eq!(schedule, example_schedule_1());
+	}
+
+	#[test]
+	fn test_truncate_at() {
+		let schedule = example_schedule_3();
+		// the second run of job 0 is split
+		assert_eq!(schedule.truncate_at(17).schedule, vec![
+			JobRun{ time: 0,  job: 0, duration: 5 },
+			JobRun{ time: 5,  job: 1, duration: 8 },
+			JobRun{ time: 13, job: 0, duration: 4 },
+		]);
+		// runs ending exactly at t are kept entirely
+		assert_eq!(schedule.truncate_at(13).schedule, schedule.schedule[..2]);
+		assert_eq!(schedule.truncate_at(30).schedule, schedule.schedule[..3]);
+		assert_eq!(schedule.truncate_at(52), schedule);
+		assert_eq!(schedule.truncate_at(100), schedule);
+		assert!(schedule.truncate_at(0).schedule.is_empty());
+		let instant = MachineSchedule{ schedule: vec![JobRun{ time: 3, job: 0, duration: 0 }, JobRun{ time: 3, job: 1, duration: 2 }] };
+		assert_eq!(instant.truncate_at(3).schedule, instant.schedule[..1]);
+	}
+
+	#[test]
+	fn test_canonical_hash() {
+		// the fingerprints are part of the interface and must not change
+		assert_eq!(MachineSchedule::new().canonical_hash(), 0xa8c7f832281a39c5);
+		assert_eq!(example_schedule_3().canonical_hash(), 0xf79d563e993796ce);
+		let mut split = example_schedule_3();
+		split.schedule.insert(3, JobRun{ time: 42, job: 2, duration: 4 });
+		split.schedule[4] = JobRun{ time: 46, job: 2, duration: 6 };
+		split.schedule.reverse();
+		assert_ne!(split, example_schedule_3());
+		assert_eq!(split.canonical_hash(), example_schedule_3().canonical_hash());
+		split.shift_by(1);
+		assert_ne!(split.canonical_hash(), example_schedule_3().canonical_hash());
+	}
+
+	#[test]
+	fn test_equivalent_up_to_job_relabeling() {
+		// relabeling the jobs of an instance relabels the schedule that Schrage computes for it
+		let p = [5, 6, 7, 4, 3];
+		let r = [10, 13, 11, 20, 30];
+		let d = [15, 25, 32, 24, 36];
+		let permutation = [3, 0, 4, 1, 2];
+		let mut q = [0; 5];
+		let mut s = [0; 5];
+		let mut e = [0; 5];
+		for (j, &k) in permutation.iter().enumerate() {
+			(q[k], s[k], e[k]) = (p[j], r[j], d[j]);
+		}
+		let a = crate::single_machine::schrage(&p, &r, &d);
+		let b = crate::single_machine::schrage(&q, &s, &e);
+		assert_ne!(a, b);
+		let mut mapping = vec![Some(17)];
+		assert!(equivalent_up_to_job_relabeling(&a, &b, &mut mapping));
+		assert_eq!(mapping, permutation.iter().map(|&k| Some(k)).collect::<Vec<_>>());
+
+		let mut c = b.clone();
+		c.schedule[0].job = c.schedule[1].job;
+		assert!(!equivalent_up_to_job_relabeling(&a, &c, &mut mapping));
+		assert!(!equivalent_up_to_job_relabeling(&c, &a, &mut mapping));
+		assert!(!equivalent_up_to_job_relabeling(&a, &MachineSchedule::new(), &mut mapping));
+		// preempted jobs must map to the same job in every run
+		let schedule = example_schedule_3();
+		let mut relabeled = schedule.clone();
+		for run in relabeled.schedule.iter_mut() {
+			run.job = 2 - run.job;
+		}
+		assert!(equivalent_up_to_job_relabeling(&schedule, &relabeled, &mut mapping));
+		assert_eq!(mapping, vec![Some(2), Some(1), Some(0)]);
+		relabeled.schedule[2].job = 0;
+		assert!(!equivalent_up_to_job_relabeling(&schedule, &relabeled, &mut mapping));
+	}
+
+	#[test]
+	fn test_respects_releases() {
+		let release_times = [10, 13, 11, 30, 0, 30];
+		assert!(example_schedule_1().respects_releases(&release_times));
+		let schedule = MachineSchedule::from_ptimes(&[5, 6, 7, 3, 6, 2]);
+		// job 0 starts at 0
+		assert!(!schedule.respects_releases(&release_times));
+		assert!(schedule.respects_releases(&[0; 6]));
+	}
+
+	#[test]
+	fn test_json_round_trip() {
+		let schedule = MachineSchedule::from_order_ptimes([1, 0].into_iter(), &[3, 2]);
+		let json = schedule.to_json();
+		assert_eq!(json, concat!(
+			"{\"schedule\": [\n",
+			"  {\"time\": 0, \"job\": 1, \"duration\": 2},\n",
+			"  {\"time\": 2, \"job\": 0, \"duration\": 3}\n",
+			"]}\n",
+		));
+		assert_eq!(MachineSchedule::from_json(&json), Ok(schedule));
+		assert_eq!(MachineSchedule::from_json(&example_schedule_1().to_json()), Ok(example_schedule_1()));
+		assert_eq!(MachineSchedule::from_json(&MachineSchedule::new().to_json()), Ok(MachineSchedule::new()));
+		assert!(MachineSchedule::from_json(r#"{"schedule": [{"time": 0, "job": -1, "duration": 2}]}"#).is_err());
+		assert!(MachineSchedule::from_json(r#"{"schedule": [{"time": 0, "job": 1}]}"#).is_err());
+		assert_eq!(
+			MachineSchedule::from_json(r#"{"schedule": [{"time": 0, "job": 1, "duration": -2}]}"#),
+			Err(ScheduleError::NegativeDuration{ job: 1, duration: -2 })
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_json_matches_serde() {
+		let schedule = example_schedule_1();
+		let ours: serde_json::Value = serde_json::from_str(&schedule.to_json()).unwrap();
+		assert_eq!(ours, serde_json::to_value(&schedule).unwrap());
+	}
+
 	#[test]
 	fn test_lateness_1() {
 		let due_times = vec![19, 20, 24, 35, 17, 38];
@@ -209,8 +1510,8 @@ mod tests {
 
 	fn example_schedule_2() -> MachineSchedule {
 		MachineSchedule::from_ptimes_releasetimes(
-			&vec![ 6,  5,  6,  7,  4,  3,  2],
-			&vec![ 0, 10, 13, 11, 20, 30, 30]
+			&[ 6,  5,  6,  7,  4,  3,  2],
+			&[ 0, 10, 13, 11, 20, 30, 30]
 		)
 	}
 
@@ -280,4 +1581,499 @@ mod tests {
 		assert_eq!(result.machine_schedules[1].schedule[3].time, 23);
 
 	}
+
+	#[test]
+	#[should_panic(expected = "Job #1 has negative processing time -2")]
+	fn test_negative_duration_rejected() {
+		MachineSchedule::from_ptimes(&[3, -2, 4]);
+	}
+
+	#[test]
+	fn test_zero_duration() {
+		let schedule = MachineSchedule::from_ptimes_releasetimes(&[3, 0, 4], &[0, 5, 0]);
+		// the zero-duration job completes instantly at its release time, delaying job 2
+		assert_eq!(schedule.schedule[1], JobRun{ time: 5, job: 1, duration: 0 });
+		assert_eq!(schedule.makespan(), 9);
+		assert_eq!(schedule.completion_times(), vec![(0, 3), (1, 5), (2, 9)]);
+		assert_eq!(schedule.num_tardy(&[3, 4, 9]), 1);
+	}
+
+	#[test]
+	fn test_from_order_calendar() {
+		// working from 0 to 8 every 10 time units
+		let calendar = Calendar::new(10, &[(0, 8)]);
+		// job 1 is released after closing, job 0 is split at closing
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes_calendar([1, 0, 2].into_iter(), &[4, 5, 0], &[0, 8, 19], &calendar);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 10, job: 1, duration: 5 },
+			JobRun{ time: 15, job: 0, duration: 3 },
+			JobRun{ time: 20, job: 0, duration: 1 },
+			JobRun{ time: 21, job: 2, duration: 0 },
+		]);
+		// job 1 without processing time is released after closing and waits for the next opening
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes_calendar([0, 1].into_iter(), &[2, 0], &[0, 9], &calendar);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 10, job: 1, duration: 0 },
+		]);
+		let (p, r) = (vec![3, 0, 4, 2], vec![0, 5, -2, 30]);
+		assert_eq!(
+			MachineSchedule::from_order_ptimes_releasetimes_calendar(0..4, &p, &r, &Calendar::always()),
+			MachineSchedule::from_order_ptimes_releasetimes(0..4, &p, &r)
+		);
+	}
+
+	#[test]
+	fn test_num_tardy_preemptive() {
+		// both runs of job 0 end after its due time 1
+		let schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 1 },
+			JobRun{ time: 3, job: 0, duration: 2 },
+		]};
+		assert_eq!(schedule.num_tardy(&[1, 3]), 1);
+		assert_eq!(schedule.num_tardy(&[1, 2]), 2);
+		assert_eq!(schedule.num_tardy(&[5, 3]), 0);
+	}
+
+	#[test]
+	fn test_normalize() {
+		let mut schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: 4, job: 0, duration: 2 },
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 0 },
+			JobRun{ time: 2, job: 0, duration: 2 },
+			JobRun{ time: 7, job: 2, duration: 1 },
+		]};
+		let mut dropped = schedule.clone();
+		schedule.normalize(false);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 0 },
+			JobRun{ time: 2, job: 0, duration: 4 },
+			JobRun{ time: 7, job: 2, duration: 1 },
+		]);
+		dropped.normalize(true);
+		assert_eq!(dropped.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 6 },
+			JobRun{ time: 7, job: 2, duration: 1 },
+		]);
+	}
+
+	#[test]
+	fn test_display() {
+		assert_eq!(example_schedule_4().to_string(), concat!(
+			" 3-16: Job #0\n",
+			"16-24: Job #1\n",
+			"24-31: Job #0\n",
+		));
+		assert_eq!(MachineSchedule::new().to_string(), "(Empty MachineSchedule)");
+	}
+
+	#[test]
+	fn test_display_negative_times() {
+		let schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: -12, job: 0, duration: 4 },
+			JobRun{ time: -8, job: 1, duration: 13 },
+		]};
+		assert_eq!(schedule.to_string(), concat!(
+			"-12- -8: Job #0\n",
+			" -8-  5: Job #1\n",
+		));
+	}
+
+	#[test]
+	fn test_multischedule_display() {
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: -3, job: 0, duration: 5 },
+				JobRun{ time: 2, job: 2, duration: 3 },
+			]},
+			MachineSchedule::new(),
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: 0, job: 1, duration: 120 },
+			]},
+		]};
+		assert_eq!(schedule.to_string(), concat!(
+			"Machine #0 (makespan 5):\n",
+			" -3-  2: Job #0\n",
+			"  2-  5: Job #2\n",
+			"Machine #1 (makespan 0):\n",
+			"(Empty MachineSchedule)\n",
+			"Machine #2 (makespan 120):\n",
+			"  0-120: Job #1\n",
+			"Makespan: 120",
+		));
+		assert_eq!(MultiMachineSchedule::new().to_string(), "(Empty MultiMachineSchedule)");
+	}
+
+	#[test]
+	fn test_format_idle_gaps() {
+		let opts = DisplayOptions{ show_idle: true, ..DisplayOptions::default() };
+		assert_eq!(example_schedule_3().format(&opts), concat!(
+			" 0- 5: Job #0\n",
+			" 5-13: Job #1\n",
+			"13-22: Job #0\n",
+			"22-42: -- idle --\n",
+			"42-52: Job #2\n",
+		));
+	}
+
+	#[test]
+	fn test_format_durations_unaligned() {
+		let opts = DisplayOptions{ align: false, show_duration: true, ..DisplayOptions::default() };
+		assert_eq!(example_schedule_3().format(&opts), concat!(
+			"0+5: Job #0\n",
+			"5+8: Job #1\n",
+			"13+9: Job #0\n",
+			"42+10: Job #2\n",
+		));
+	}
+
+	#[test]
+	fn test_format_negative_times() {
+		let schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: -12, job: 0, duration: 7 },
+			JobRun{ time: -5, job: 1, duration: 10 },
+		]};
+		let due_times = vec![-10, 0];
+		let opts = DisplayOptions{
+			due_times: Some(&due_times),
+			time_format: TimeFormat::Signed,
+			..DisplayOptions::default()
+		};
+		assert_eq!(schedule.format(&opts), concat!(
+			"-12- -5: Job #0, lateness +5\n",
+			" -5- +5: Job #1, lateness +5\n",
+		));
+		let opts = DisplayOptions{
+			time_format: TimeFormat::Decimal{ scale: 10, precision: 1 },
+			..DisplayOptions::default()
+		};
+		assert_eq!(schedule.format(&opts), concat!(
+			"-1.2--0.5: Job #0\n",
+			"-0.5- 0.5: Job #1\n",
+		));
+	}
+
+	#[test]
+	fn test_format_lateness_and_labels() {
+		let due_times = vec![20, 15, 52];
+		let labels: Vec<String> = vec!["drill".into(), "mill".into(), "paint".into()];
+		let opts = DisplayOptions{
+			due_times: Some(&due_times),
+			job_labels: Some(&labels),
+			..DisplayOptions::default()
+		};
+		assert_eq!(example_schedule_3().format(&opts), concat!(
+			" 0- 5: Job #0 (drill), lateness -15\n",
+			" 5-13: Job #1 (mill), lateness -2\n",
+			"13-22: Job #0 (drill), lateness 2\n",
+			"42-52: Job #2 (paint), lateness 0\n",
+		));
+	}
+
+	#[test]
+	fn test_multischedule_format_relative() {
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			example_schedule_4(),
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: 5, job: 2, duration: 5 },
+				JobRun{ time: 20, job: 3, duration: 2 },
+			]},
+		]};
+		let opts = DisplayOptions{ show_idle: true, relative_times: true, ..DisplayOptions::default() };
+		assert_eq!(schedule.format(&opts), concat!(
+			"Machine #0 (makespan 28):\n",
+			" 0-13: Job #0\n",
+			"13-21: Job #1\n",
+			"21-28: Job #0\n",
+			"Machine #1 (makespan 19):\n",
+			" 2- 7: Job #2\n",
+			" 7-17: -- idle --\n",
+			"17-19: Job #3\n",
+			"Makespan: 28",
+		));
+		let opts = DisplayOptions{ time_format: TimeFormat::Decimal{ scale: 10, precision: 1 }, ..opts };
+		assert_eq!(schedule.format(&opts), concat!(
+			"Machine #0 (makespan 2.8):\n",
+			"0.0-1.3: Job #0\n",
+			"1.3-2.1: Job #1\n",
+			"2.1-2.8: Job #0\n",
+			"Machine #1 (makespan 1.9):\n",
+			"0.2-0.7: Job #2\n",
+			"0.7-1.7: -- idle --\n",
+			"1.7-1.9: Job #3\n",
+			"Makespan: 2.8",
+		));
+	}
+
+	#[test]
+	#[should_panic(expected = "scale")]
+	fn test_format_decimal_zero_scale() {
+		let opts = DisplayOptions{
+			time_format: TimeFormat::Decimal{ scale: 0, precision: 1 },
+			..DisplayOptions::default()
+		};
+		example_schedule_3().format(&opts);
+	}
+
+	#[test]
+	fn test_completion_times_preemptive() {
+		assert_eq!(example_schedule_3().completion_times(), vec![(1, 13), (0, 22), (2, 52)]);
+	}
+
+	#[test]
+	fn test_weighted_lateness() {
+		let schedule = example_schedule_1();
+		let due_times = vec![19, 20, 24, 35, 17, 38];
+		let unit = vec![1; 6];
+		assert_eq!(schedule.max_weighted_lateness(&due_times, &unit), schedule.max_lateness(&due_times));
+		// latenesses are -4, 1, 4, -2, 22, 3
+		assert_eq!(schedule.total_weighted_lateness(&due_times, &unit), 24);
+		let weights = vec![5, 10, 2, 1, 0, 3];
+		assert_eq!(schedule.max_weighted_lateness(&due_times, &weights), 10);
+		assert_eq!(schedule.total_weighted_lateness(&due_times, &weights), -20 + 10 + 8 - 2 + 9);
+	}
+
+	#[test]
+	fn test_flow_time_and_slowdown() {
+		// job 1 preempts job 0
+		let schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: 0, job: 0, duration: 1 },
+			JobRun{ time: 1, job: 1, duration: 2 },
+			JobRun{ time: 3, job: 0, duration: 3 },
+		]};
+		let release_times = vec![0, 1];
+		// flow times are 6 and 2
+		assert_eq!(schedule.weighted_flow_time(&release_times, &[1, 1]), 8);
+		assert_eq!(schedule.weighted_flow_time(&release_times, &[2, 5]), 12 + 10);
+		// slowdowns are 6 / 4 and 2 / 2
+		assert_eq!(schedule.mean_slowdown(&release_times), 1.25);
+		// a job with processing time 0 has the flow time as slowdown
+		let schedule = MachineSchedule{ schedule: vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 0 },
+		]};
+		assert_eq!(schedule.mean_slowdown(&[0, 1]), 1.0);
+		assert_eq!(MachineSchedule::new().mean_slowdown(&[]), 0.0);
+	}
+
+	#[test]
+	fn test_from_order_slice() {
+		let order = vec![2, 0, 1];
+		let ptimes = vec![3, 1, 2];
+		assert_eq!(
+			MachineSchedule::from_order_ptimes_slice(&order, &ptimes),
+			MachineSchedule::from_order_ptimes(order.iter().copied(), &ptimes)
+		);
+		let release_times = vec![0, 6, 1];
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes_slice(&order, &ptimes, &release_times);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 1, job: 2, duration: 2 },
+			JobRun{ time: 3, job: 0, duration: 3 },
+			JobRun{ time: 6, job: 1, duration: 1 },
+		]);
+	}
+
+	#[test]
+	fn test_to_csv() {
+		let schedule = example_schedule_3();
+		let csv = schedule.to_csv();
+		let lines: Vec<&str> = csv.lines().collect();
+		assert_eq!(lines[0], "job,start,end,duration");
+		assert_eq!(lines.len(), schedule.schedule.len() + 1);
+		let run = schedule.schedule[0];
+		assert_eq!(lines[1], format!("{},{},{},{}", run.job, run.time, run.time + run.duration, run.duration));
+		let multi = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![JobRun{ time: 0, job: 1, duration: 2 }] },
+			MachineSchedule::new(),
+			MachineSchedule{ schedule: vec![JobRun{ time: -1, job: 0, duration: 3 }, JobRun{ time: 4, job: 2, duration: 1 }] },
+		]};
+		assert_eq!(multi.to_csv(), "machine,job,start,end,duration\n0,1,0,2,2\n2,0,-1,2,3\n2,2,4,5,1\n");
+		assert_eq!(MultiMachineSchedule::new().to_csv().lines().count(), 1);
+	}
+
+	#[test]
+	fn test_lateness_after_swap() {
+		for seed in 0..5 {
+			let instance = crate::generators::random_single_machine(12, 10, seed);
+			let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+			let order: Vec<Job> = (0..12).collect();
+			let schedule = MachineSchedule::from_order_ptimes_releasetimes_slice(&order, p, r);
+			for i in 0..11 {
+				let mut swapped = order.clone();
+				swapped.swap(i, i + 1);
+				let expected = MachineSchedule::from_order_ptimes_releasetimes_slice(&swapped, p, r).max_lateness(d);
+				assert_eq!(schedule.lateness_after_swap(i, p, r, d), expected, "seed {} position {}", seed, i);
+			}
+		}
+		// the swap delays job 0 by 1, and job 2 starts at its release time as before
+		let (p, r, d) = ([2, 1, 1], [0, 0, 6], [2, 5, 7]);
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes_slice(&[0, 1, 2], &p, &r);
+		assert_eq!(schedule.lateness_after_swap(0, &p, &r, &d), 1);
+	}
+
+	#[test]
+	fn test_job_order() {
+		assert_eq!(example_schedule_1().job_order(), vec![0, 1, 2, 3, 4, 5]);
+		assert_eq!(example_schedule_3().job_order(), vec![0, 1, 0, 2]);
+		assert_eq!(example_schedule_3().distinct_job_order(), vec![0, 1, 2]);
+		let unsorted = MachineSchedule{ schedule: vec![
+			JobRun{ time: 5, job: 1, duration: 2 },
+			JobRun{ time: 0, job: 0, duration: 5 },
+		]};
+		assert_eq!(unsorted.job_order(), vec![0, 1]);
+	}
+
+	#[test]
+	fn test_latenesses_preemptive() {
+		let schedule = example_schedule_3();
+		let due_times = vec![25, 14, 50];
+		assert_eq!(schedule.latenesses(&due_times), vec![(1, -1), (0, -3), (2, 2)]);
+		assert_eq!(schedule.tardinesses(&due_times), vec![(1, 0), (0, 0), (2, 2)]);
+	}
+
+	#[test]
+	fn test_semantically_equal() {
+		let schedule = example_schedule_3();
+		let shifted = MachineSchedule{ schedule: vec![
+			JobRun{ time: 0,  job: 0, duration: 3 },
+			JobRun{ time: 3,  job: 0, duration: 2 },
+			JobRun{ time: 5,  job: 1, duration: 8 },
+			JobRun{ time: 13, job: 0, duration: 9 },
+			JobRun{ time: 30, job: 2, duration: 5 },
+			JobRun{ time: 47, job: 2, duration: 5 },
+		]};
+		assert_ne!(schedule, shifted);
+		assert!(schedule.semantically_equal(&shifted));
+		assert!(shifted.semantically_equal(&schedule));
+
+		let mut earlier = example_schedule_3();
+		earlier.schedule[3].time -= 1;
+		assert!(!schedule.semantically_equal(&earlier));
+
+		let mut shorter = example_schedule_3();
+		shorter.schedule[0].duration -= 1;
+		assert!(!schedule.semantically_equal(&shorter));
+	}
+
+	#[test]
+	fn test_multischedule_max_lateness() {
+		let schedule = MultiMachineSchedule{
+			machine_schedules: vec![example_schedule_3(), example_schedule_4()]
+		};
+		let due_times = vec![25, 24, 52];
+		// job 0 completes at 31 on the second machine
+		assert_eq!(schedule.max_lateness(&due_times), 6);
+	}
+
+	#[test]
+	fn test_multischedule_tardiness_flow_shop() {
+		let ptimes = vec![
+			vec![9, 1, 9, 4],
+			vec![6, 3, 5, 5],
+		];
+		let result = MultiMachineSchedule::from_order_ptimes(&[2, 1, 3, 0], &ptimes);
+		// jobs complete at 29, 17, 14, 22 on the last machine
+		let due_times = vec![20, 10, 14, 25];
+		assert_eq!(result.total_tardiness(&due_times), 9 + 7);
+		assert_eq!(result.num_tardy(&due_times), 2);
+		assert_eq!(result.max_lateness(&due_times), 9);
+	}
+
+	#[test]
+	fn test_right_shift() {
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: 0, job: 0, duration: 2 },
+				JobRun{ time: 2, job: 1, duration: 2 },
+				JobRun{ time: 4, job: 2, duration: 1 },
+			]},
+			MachineSchedule{ schedule: vec![
+				JobRun{ time: 2, job: 0, duration: 3 },
+				JobRun{ time: 5, job: 1, duration: 2 },
+				JobRun{ time: 7, job: 3, duration: 1 },
+			]},
+		]};
+		let due_times = vec![10, 12, 6, 4];
+		let earliness = |s: &MultiMachineSchedule| -> Time {
+			s.completion_times().iter().map(|&(job, c)| max(0, due_times[job] - c)).sum()
+		};
+		let shifted = schedule.right_shift(&due_times, None);
+		// job 2 completes at its due time, making room for job 1
+		assert_eq!(shifted.machine_schedules[0].schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 3, job: 1, duration: 2 },
+			JobRun{ time: 5, job: 2, duration: 1 },
+		]);
+		// job 3 is tardy and stays
+		assert_eq!(shifted.machine_schedules[1], schedule.machine_schedules[1]);
+		assert_eq!(shifted.makespan(), schedule.makespan());
+		assert_eq!(shifted.total_tardiness(&due_times), schedule.total_tardiness(&due_times));
+		assert_eq!((earliness(&schedule), earliness(&shifted)), (11, 10));
+		assert_eq!(shifted.right_shift(&due_times, None), shifted);
+	}
+
+	#[test]
+	fn test_right_shift_precedents() {
+		let schedule = MultiMachineSchedule{ machine_schedules: vec![
+			MachineSchedule{ schedule: vec![JobRun{ time: 0, job: 0, duration: 2 }] },
+			MachineSchedule{ schedule: vec![JobRun{ time: 3, job: 1, duration: 2 }] },
+		]};
+		let due_times = vec![10, 5];
+		// job 0 ends with the makespan, unless it has to precede job 1
+		let shifted = schedule.right_shift(&due_times, None);
+		assert_eq!(shifted.machine_schedules[0].schedule[0].time, 3);
+		let precedents = vec![vec![], vec![0]];
+		let shifted = schedule.right_shift(&due_times, Some(&precedents));
+		assert_eq!(shifted.machine_schedules[0].schedule[0].time, 1);
+		assert_eq!(shifted.machine_schedules[1], schedule.machine_schedules[1]);
+	}
+
+	#[test]
+	fn test_multischedule_completion_times() {
+		let ptimes = vec![
+			vec![9, 1, 9, 4],
+			vec![6, 3, 5, 5],
+		];
+		let result = MultiMachineSchedule::from_order_ptimes(&[2, 1, 3, 0], &ptimes);
+		assert_eq!(result.completion_times(), vec![(2, 14), (1, 17), (3, 22), (0, 29)]);
+	}
+
+	#[test]
+	fn test_multischedule_job_timeline() {
+		let ptimes = vec![
+			vec![9, 1, 9, 4],
+			vec![6, 3, 5, 5],
+		];
+		let result = MultiMachineSchedule::from_order_ptimes(&[2, 1, 3, 0], &ptimes);
+		// job 0 waits for machine 1 until 23
+		assert_eq!(result.job_timeline(0), vec![
+			(0, JobRun{ time: 14, job: 0, duration: 9 }),
+			(1, JobRun{ time: 23, job: 0, duration: 6 }),
+		]);
+		assert_eq!(result.job_timeline(4), vec![]);
+	}
+
+	#[test]
+	fn test_multischedule_transport() {
+		let ptimes = vec![
+			vec![9, 1, 9, 4],
+			vec![6, 3, 5, 5],
+		];
+		let order = [2, 1, 3, 0];
+		let result = MultiMachineSchedule::from_order_ptimes_with_transport(&order, &ptimes, &[3]);
+		assert_eq!(result.machine_schedules[1].schedule, vec![
+			JobRun{ time: 12, job: 2, duration: 5 },
+			JobRun{ time: 17, job: 1, duration: 3 },
+			JobRun{ time: 20, job: 3, duration: 5 },
+			JobRun{ time: 26, job: 0, duration: 6 },
+		]);
+		assert_eq!(result.completion_times(), vec![(2, 17), (1, 20), (3, 25), (0, 32)]);
+		assert_eq!(
+			MultiMachineSchedule::from_order_ptimes_with_transport(&order, &ptimes, &[0]),
+			MultiMachineSchedule::from_order_ptimes(&order, &ptimes)
+		);
+	}
 }
@@ -0,0 +1,223 @@
+use crate::{Time, Machine, JobRun, MultiMachineSchedule};
+
+
+/// An index over the runs of a `MultiMachineSchedule` for answering many point and range queries,
+/// e.g. "which runs are being processed at time t?", faster than scanning all runs.
+///
+/// A run occupies the half-open interval [time, time + duration),
+/// so a run ending at t and a run starting at t do not overlap, and runs of duration 0 never overlap anything.
+///
+/// The index stores copies of the runs and does not borrow the schedule.
+/// It is a snapshot: changes to the schedule after `build` are not reflected, so rebuild the index after each change.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleIndex {
+	all: RunTree,
+	machines: Vec<RunTree>,
+	/// Sorted, distinct start and end times of all runs
+	events: Vec<Time>,
+}
+
+impl ScheduleIndex {
+	/// Builds the index for a schedule in O(r log r) time for r runs.
+	pub fn build(schedule: &MultiMachineSchedule) -> ScheduleIndex {
+		let machines: Vec<RunTree> = schedule.machine_schedules.iter().enumerate()
+			.map(|(machine, s)| RunTree::new(s.schedule.iter().map(|run| (machine, *run)).collect()))
+			.collect();
+		let all = RunTree::new(machines.iter().flat_map(|tree| tree.runs.iter().copied()).collect());
+		let mut events: Vec<Time> = all.runs.iter().flat_map(|(_, run)| [run.time, run.time + run.duration]).collect();
+		events.sort_unstable();
+		events.dedup();
+		ScheduleIndex{ all, machines, events }
+	}
+
+	/// Returns the number of indexed runs.
+	pub fn len(&self) -> usize {
+		self.all.runs.len()
+	}
+
+	/// Returns whether the schedule has no runs.
+	pub fn is_empty(&self) -> bool {
+		self.all.runs.is_empty()
+	}
+
+	/// Returns the runs on any machine that are being processed at time `t`, i.e. time <= t < time + duration,
+	/// together with their machine and ordered by start time and machine.
+	/// Runs in O(log r + k log r) time for r runs and k results.
+	pub fn query_point(&self, t: Time) -> Vec<(Machine, JobRun)> {
+		self.query_range(t, t.saturating_add(1))
+	}
+
+	/// Returns the runs on any machine that overlap the half-open interval [t1, t2),
+	/// together with their machine and ordered by start time and machine.
+	/// The result is empty if t2 <= t1.
+	/// Runs in O(log r + k log r) time for r runs and k results.
+	pub fn query_range(&self, t1: Time, t2: Time) -> Vec<(Machine, JobRun)> {
+		let mut result = Vec::new();
+		self.all.query(t1, t2, &mut result);
+		result
+	}
+
+	/// Like `query_range`, but only returns runs of the given machine.
+	///
+	/// # Panics
+	/// If the machine does not exist.
+	pub fn query_machine_range(&self, machine: Machine, t1: Time, t2: Time) -> Vec<JobRun> {
+		let mut result = Vec::new();
+		self.machines[machine].query(t1, t2, &mut result);
+		result.into_iter().map(|(_, run)| run).collect()
+	}
+
+	/// Returns the first time after `t` at which a run starts or ends, or None if there is no such time.
+	/// The set of runs being processed can only change at these times.
+	pub fn next_change_after(&self, t: Time) -> Option<Time> {
+		let i = self.events.partition_point(|&e| e <= t);
+		self.events.get(i).copied()
+	}
+}
+
+
+/// Runs sorted by start time, as an implicit binary search tree in which the node of the subarray [lo, hi)
+/// is its middle element, augmented with the maximum end time of each subarray.
+#[derive(Debug, Clone, Default)]
+struct RunTree {
+	runs: Vec<(Machine, JobRun)>,
+	/// max_end[mid] is the maximum end time of the runs in the subarray whose middle element is mid
+	max_end: Vec<Time>,
+}
+
+impl RunTree {
+	fn new(mut runs: Vec<(Machine, JobRun)>) -> RunTree {
+		runs.sort_by_key(|&(machine, run)| (run.time, machine));
+		let mut tree = RunTree{ max_end: vec![Time::MIN; runs.len()], runs };
+		tree.compute_max_end(0, tree.runs.len());
+		tree
+	}
+
+	fn compute_max_end(&mut self, lo: usize, hi: usize) -> Time {
+		if lo >= hi {
+			return Time::MIN;
+		}
+		let mid = lo + (hi - lo) / 2;
+		let (_, run) = self.runs[mid];
+		let end = (run.time + run.duration)
+			.max(self.compute_max_end(lo, mid))
+			.max(self.compute_max_end(mid + 1, hi));
+		self.max_end[mid] = end;
+		end
+	}
+
+	/// Appends the runs overlapping [t1, t2) to `result` in order of the runs.
+	fn query(&self, t1: Time, t2: Time, result: &mut Vec<(Machine, JobRun)>) {
+		if t1 < t2 {
+			self.query_subtree(0, self.runs.len(), t1, t2, result);
+		}
+	}
+
+	fn query_subtree(&self, lo: usize, hi: usize, t1: Time, t2: Time, result: &mut Vec<(Machine, JobRun)>) {
+		if lo >= hi {
+			return;
+		}
+		let mid = lo + (hi - lo) / 2;
+		if self.max_end[mid] <= t1 {
+			// every run of this subarray ends before the interval
+			return;
+		}
+		self.query_subtree(lo, mid, t1, t2, result);
+		let (_, run) = self.runs[mid];
+		if run.time < t2 {
+			if run.time + run.duration > t1 {
+				result.push(self.runs[mid]);
+			}
+			// the runs after mid only start later
+			self.query_subtree(mid + 1, hi, t1, t2, result);
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	fn naive_range(schedule: &MultiMachineSchedule, t1: Time, t2: Time) -> Vec<(Machine, JobRun)> {
+		let mut result: Vec<(Machine, JobRun)> = schedule.machine_schedules.iter().enumerate()
+			.flat_map(|(machine, s)| s.schedule.iter().map(move |run| (machine, *run)))
+			.filter(|(_, run)| t1 < t2 && run.time < t2 && run.time + run.duration > t1)
+			.collect();
+		result.sort_by_key(|&(machine, run)| (run.time, machine));
+		result
+	}
+
+	fn run(time: Time, job: usize, duration: Time) -> JobRun {
+		JobRun{ time, job, duration }
+	}
+
+	#[test]
+	fn test_touching_runs() {
+		let mut schedule = MultiMachineSchedule::with_machines(3);
+		for (machine, r) in [(0, run(0, 0, 3)), (0, run(3, 1, 2)), (0, run(5, 2, 0)), (0, run(5, 3, 4)), (1, run(3, 4, 3))] {
+			schedule.push_run(machine, r).unwrap();
+		}
+		let index = ScheduleIndex::build(&schedule);
+		assert_eq!(index.len(), 5);
+		assert_eq!(index.query_point(2), vec![(0, run(0, 0, 3))]);
+		// at 3, job 0 has just ended and jobs 1 and 4 start
+		assert_eq!(index.query_point(3), vec![(0, run(3, 1, 2)), (1, run(3, 4, 3))]);
+		// the run of duration 0 is never reported
+		assert_eq!(index.query_point(5), vec![(1, run(3, 4, 3)), (0, run(5, 3, 4))]);
+		assert_eq!(index.query_point(9), vec![]);
+		assert_eq!(index.query_range(3, 3), vec![]);
+		assert_eq!(index.query_range(-5, 0), vec![]);
+		assert_eq!(index.query_range(2, 4), naive_range(&schedule, 2, 4));
+		assert_eq!(index.query_machine_range(1, 0, 10), vec![run(3, 4, 3)]);
+		assert_eq!(index.query_machine_range(2, 0, 10), vec![]);
+
+		assert_eq!(index.next_change_after(-1), Some(0));
+		assert_eq!(index.next_change_after(0), Some(3));
+		assert_eq!(index.next_change_after(3), Some(5));
+		assert_eq!(index.next_change_after(5), Some(6));
+		assert_eq!(index.next_change_after(8), Some(9));
+		assert_eq!(index.next_change_after(9), None);
+
+		let empty = ScheduleIndex::build(&MultiMachineSchedule::new());
+		assert!(empty.is_empty());
+		assert_eq!(empty.query_point(0), vec![]);
+		assert_eq!(empty.next_change_after(0), None);
+	}
+
+	proptest! {
+		#[test]
+		fn test_queries_match_naive_scan(
+			machines in proptest::collection::vec(proptest::collection::vec((0..5 as Time, 0..6 as Time), 0..12), 0..4),
+			t1 in -2..40 as Time,
+			length in 0..10 as Time,
+		) {
+			let mut schedule = MultiMachineSchedule::with_machines(machines.len());
+			let mut job = 0;
+			for (machine, runs) in machines.iter().enumerate() {
+				let mut time = 0;
+				for &(gap, duration) in runs.iter() {
+					time += gap;
+					schedule.push_run(machine, run(time, job, duration)).unwrap();
+					time += duration;
+					job += 1;
+				}
+			}
+			let index = ScheduleIndex::build(&schedule);
+			let t2 = t1 + length;
+			prop_assert_eq!(index.query_range(t1, t2), naive_range(&schedule, t1, t2));
+			prop_assert_eq!(index.query_point(t1), naive_range(&schedule, t1, t1 + 1));
+			for machine in 0..machines.len() {
+				let expected: Vec<JobRun> = naive_range(&schedule, t1, t2).into_iter()
+					.filter(|&(m, _)| m == machine).map(|(_, run)| run).collect();
+				prop_assert_eq!(index.query_machine_range(machine, t1, t2), expected);
+			}
+			let next = schedule.machine_schedules.iter()
+				.flat_map(|s| s.schedule.iter().flat_map(|run| [run.time, run.time + run.duration]))
+				.filter(|&e| e > t1)
+				.min();
+			prop_assert_eq!(index.next_change_after(t1), next);
+		}
+	}
+}
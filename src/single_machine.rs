@@ -1,5 +1,13 @@
 mod releasetimes_maxlateness;
 mod num_tardy;
+mod completion_time;
+mod families;
+mod batch;
+mod earliness_tardiness;
 
 pub use self::releasetimes_maxlateness::*;
-pub use self::num_tardy::*;
\ No newline at end of file
+pub use self::num_tardy::*;
+pub use self::completion_time::*;
+pub use self::families::*;
+pub use self::batch::*;
+pub use self::earliness_tardiness::*;
\ No newline at end of file
@@ -0,0 +1,133 @@
+use crate::{MachineSchedule, SingleMachineInstance};
+use super::releasetimes_maxlateness::{CarlierSearch, SchrageScratch, TieBreak, schrage_with_scratch};
+
+
+/// The algorithm `solve_batch` applies to every instance.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SolverKind {
+	/// Schrage's heuristic for 1|r_j|L_max, see `schrage`
+	Schrage,
+	/// Carlier's branch and bound for 1|r_j|L_max, see `carlier`
+	Carlier,
+}
+
+
+/// Limits on the effort spent on a single instance by a branch and bound,
+/// such as `SolverKind::Carlier` or `flow_shop::f2_release_bnb`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SearchLimits {
+	/// The maximum number of subproblems explored per instance.
+	/// If the limit is reached, the best schedule found so far is returned.
+	pub max_nodes: usize,
+}
+
+impl Default for SearchLimits {
+	fn default() -> Self {
+		SearchLimits{ max_nodes: usize::MAX }
+	}
+}
+
+
+/// Solves many 1|r_j|L_max instances, reusing buffers across instances instead of allocating them for every call.
+/// With the `rayon` feature, the instances are solved in parallel, with buffers for every thread.
+/// The schedules are the same as those of individual calls of the solver.
+///
+/// # Arguments
+/// * `instances`: The instances to solve
+/// * `solver`: The algorithm to apply
+/// * `limits`: Limits per instance
+///
+/// # Returns
+/// The schedule of every instance, in the order of the instances.
+pub fn solve_batch(
+	instances: &[SingleMachineInstance],
+	solver: SolverKind,
+	limits: &SearchLimits
+) -> Vec<MachineSchedule>
+{
+	#[cfg(feature = "rayon")]
+	{
+		use rayon::prelude::*;
+		instances.par_iter()
+			.map_init(BatchSolver::default, |batch, instance| batch.solve(instance, solver, limits))
+			.collect()
+	}
+	#[cfg(not(feature = "rayon"))]
+	{
+		let mut batch = BatchSolver::default();
+		instances.iter().map(|instance| batch.solve(instance, solver, limits)).collect()
+	}
+}
+
+
+/// The buffers reused by `solve_batch`
+#[derive(Debug, Default)]
+struct BatchSolver {
+	scratch: SchrageScratch,
+	search: Option<CarlierSearch>,
+}
+
+impl BatchSolver {
+	fn solve(&mut self, instance: &SingleMachineInstance, solver: SolverKind, limits: &SearchLimits) -> MachineSchedule {
+		let SingleMachineInstance{ ptimes, release_times, due_times } = instance;
+		match solver {
+			SolverKind::Schrage => schrage_with_scratch(ptimes, release_times, due_times, TieBreak::default(), &mut self.scratch),
+			SolverKind::Carlier => {
+				let search = match self.search.as_mut() {
+					Some(search) => {
+						search.reset(ptimes, release_times, due_times);
+						search
+					},
+					None => self.search.insert(CarlierSearch::new(ptimes, release_times, due_times)),
+				};
+				search.step(limits.max_nodes);
+				// without an explored node, Schrage's schedule is the best known one
+				match search.best_schedule() {
+					Some(schedule) => schedule.clone(),
+					None => schrage_with_scratch(ptimes, release_times, due_times, TieBreak::default(), &mut self.scratch),
+				}
+			},
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::single_machine::{schrage, carlier};
+	use crate::generators::random_single_machine;
+
+	#[test]
+	fn test_solve_batch_matches_individual_calls() {
+		let mut instances: Vec<SingleMachineInstance> = (0..50)
+			.map(|seed| random_single_machine(20, 30, seed))
+			.collect();
+		instances.push(SingleMachineInstance::new(&[], &[], &[]).unwrap());
+		let schedules = solve_batch(&instances, SolverKind::Schrage, &SearchLimits::default());
+		for (instance, schedule) in instances.iter().zip(schedules.iter()) {
+			assert_eq!(*schedule, schrage(&instance.ptimes, &instance.release_times, &instance.due_times));
+		}
+		let schedules = solve_batch(&instances, SolverKind::Carlier, &SearchLimits::default());
+		for (instance, schedule) in instances.iter().zip(schedules.iter()) {
+			assert_eq!(*schedule, carlier(&instance.ptimes, &instance.release_times, &instance.due_times));
+		}
+	}
+
+	#[test]
+	fn test_solve_batch_node_limit() {
+		let instances: Vec<SingleMachineInstance> = (0..10)
+			.map(|seed| random_single_machine(50, 50, seed))
+			.collect();
+		for max_nodes in [0, 1, 5] {
+			let schedules = solve_batch(&instances, SolverKind::Carlier, &SearchLimits{ max_nodes });
+			for (instance, schedule) in instances.iter().zip(schedules.iter()) {
+				let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+				let mut search = CarlierSearch::new(p, r, d);
+				search.step(max_nodes);
+				let expected = search.best_schedule().cloned().unwrap_or_else(|| schrage(p, r, d));
+				assert_eq!(*schedule, expected);
+			}
+		}
+	}
+}
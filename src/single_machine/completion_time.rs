@@ -0,0 +1,456 @@
+use crate::{Time, Job, MachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+
+use std::cmp::{max, Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+
+/// Shortest Remaining Processing Time scheduler.
+/// Produces an optimum schedule for 1|pmtn,r_j|sum C_j.
+/// Whenever a job is released, the job with the shortest remaining processing time is run,
+/// a running job is only preempted for a job with strictly shorter remaining processing time.
+/// Runs in O(n log n) time for n jobs.
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+///
+/// # Panics
+/// If a processing time is negative.
+///
+pub fn srpt(ptimes: &[Time], release_times: &[Time]) -> MachineSchedule {
+	srpt_detailed(ptimes, release_times).0
+}
+
+
+/// Like `srpt`, but additionally returns the number of preemptions,
+/// i.e. how often the run of a job is interrupted and later resumed.
+///
+/// # Panics
+/// If a processing time is negative.
+///
+pub fn srpt_detailed(ptimes: &[Time], release_times: &[Time]) -> (MachineSchedule, usize) {
+	assert_valid_ptimes(ptimes);
+	let mut remaining = ptimes.to_vec();
+	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
+	// sort by descending release time
+	// because we want to pop the jobs with lowest release time first
+	jobs.sort_unstable_by_key(|&job| -release_times[job]);
+	// jobs that are ready to run, shortest remaining processing time first
+	let mut ready_to_run = BinaryHeap::new();
+	let mut started = vec![false; ptimes.len()];
+	let mut num_preemptions = 0;
+	let mut t: Time = 0;
+	let mut schedule: Vec<JobRun> = Vec::new();
+	while !jobs.is_empty() || !ready_to_run.is_empty() {
+		// Find all jobs that are available
+		while !jobs.is_empty()
+			&& release_times[*jobs.last().unwrap()] <= t
+		{
+			let job = jobs.pop().unwrap();
+			ready_to_run.push(Reverse((remaining[job], job)));
+		}
+		let job = match ready_to_run.pop() {
+			Some(Reverse((_, job))) => job,
+			None => {
+				// If there aren't any jobs that can be run,
+				// skip to when the nearest job is available
+				t = release_times[*jobs.last().unwrap()];
+				continue;
+			}
+		};
+		match schedule.last() {
+			// the job continues after the release of another job
+			Some(last) if last.job == job => {},
+			_ => {
+				if started[job] {
+					num_preemptions += 1;
+				}
+				started[job] = true;
+				schedule.push(JobRun{ time: t, job, duration: 0 });
+			}
+		}
+		// run the job until it completes or a job with shorter processing time is released
+		let mut end = t + remaining[job];
+		while let Some(&next) = jobs.last() {
+			let release = release_times[next];
+			if release >= end {
+				break;
+			}
+			if release > t && remaining[next] < end - release {
+				end = release;
+				break;
+			}
+			jobs.pop();
+			ready_to_run.push(Reverse((remaining[next], next)));
+		}
+		schedule.last_mut().unwrap().duration += end - t;
+		remaining[job] -= end - t;
+		t = end;
+		if remaining[job] > 0 {
+			ready_to_run.push(Reverse((remaining[job], job)));
+		}
+	}
+	(MachineSchedule{ schedule }, num_preemptions)
+}
+
+
+/// Lower bound for 1|r_j|sum w_j C_j by the job splitting relaxation of Belouadah, Potts and Posner.
+/// Jobs are scheduled preemptively from time 0, always processing the released job
+/// with the largest ratio w_j/p_j of its full processing time and weight.
+/// Every maximal piece of a job is then treated as a job of its own with a proportional share of the weight,
+/// so a job j split into pieces of lengths p_jk completing at C_jk contributes sum_k w_j p_jk C_jk / p_j.
+/// A job of processing time 0 contributes w_j max(r_j, 0).
+/// Since the pieces of a job complete no later than the job itself in any schedule without preemption,
+/// the result never exceeds the optimum, and it is exact if no job is preempted.
+/// The contribution of each job is rounded down.
+/// Runs in O(n log n) time for n jobs.
+///
+/// See Belouadah, Posner, Potts: "Scheduling with release dates on a single machine
+/// to minimize total weighted completion time" (1992)
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `weights`: The non-negative weights of the jobs
+///
+/// # Panics
+/// If a processing time or weight is negative.
+///
+pub fn wct_release_lower_bound(ptimes: &[Time], release_times: &[Time], weights: &[Time]) -> Time {
+	assert_valid_ptimes(ptimes);
+	assert!(weights.iter().all(|&w| w >= 0), "Weights must not be negative");
+	let mut bound: Time = (0..ptimes.len())
+		.filter(|&job| ptimes[job] == 0)
+		.map(|job| weights[job] * max(release_times[job], 0))
+		.sum();
+	let mut jobs: Vec<Job> = (0..ptimes.len()).filter(|&job| ptimes[job] > 0).collect();
+	// sort by descending release time, so that the next released job is last
+	jobs.sort_unstable_by_key(|&job| Reverse(release_times[job]));
+	let mut ready_to_run = BinaryHeap::new();
+	let mut remaining = ptimes.to_vec();
+	// sum_k p_jk C_jk for every job j
+	let mut weighted_ends = vec![0i128; ptimes.len()];
+	// the job currently being processed and the start of its current piece
+	let mut piece: Option<(Job, Time)> = None;
+	let mut t: Time = 0;
+	loop {
+		while let Some(&next) = jobs.last() {
+			if release_times[next] > t {
+				break;
+			}
+			jobs.pop();
+			ready_to_run.push(RatioOrder{ ptime: ptimes[next], weight: weights[next], job: next });
+		}
+		let Some(entry) = ready_to_run.pop() else {
+			match jobs.last() {
+				Some(&next) => {
+					t = release_times[next];
+					continue;
+				},
+				None => break,
+			}
+		};
+		let job = entry.job;
+		match piece {
+			Some((current, _)) if current == job => {},
+			Some((current, start)) => {
+				weighted_ends[current] += (t - start) as i128 * t as i128;
+				piece = Some((job, t));
+			},
+			None => piece = Some((job, t)),
+		}
+		let mut end = t + remaining[job];
+		if let Some(&next) = jobs.last() {
+			end = end.min(release_times[next]);
+		}
+		remaining[job] -= end - t;
+		t = end;
+		if remaining[job] == 0 {
+			let (_, start) = piece.take().unwrap();
+			weighted_ends[job] += (t - start) as i128 * t as i128;
+		} else {
+			ready_to_run.push(entry);
+		}
+	}
+	for (job, &weighted_end) in weighted_ends.iter().enumerate() {
+		if ptimes[job] > 0 {
+			bound += (weights[job] as i128 * weighted_end).div_euclid(ptimes[job] as i128) as Time;
+		}
+	}
+	bound
+}
+
+
+/// Orders jobs with positive processing time by ascending ratio w_j/p_j, and by descending index for equal ratios,
+/// so that a max-heap yields the job with the largest ratio and the smallest index first.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct RatioOrder {
+	ptime: Time,
+	weight: Time,
+	job: Job,
+}
+
+impl Ord for RatioOrder {
+	fn cmp(&self, other: &Self) -> Ordering {
+		let lhs = self.weight as i128 * other.ptime as i128;
+		let rhs = other.weight as i128 * self.ptime as i128;
+		lhs.cmp(&rhs).then_with(|| other.job.cmp(&self.job))
+	}
+}
+
+impl PartialOrd for RatioOrder {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+
+/// Solves 1|reject|sum w_j C_j + sum e_j optimally:
+/// every job is either processed, contributing its weighted completion time,
+/// or rejected at cost `e_j`.
+/// Accepted jobs are processed in the order of Smith's rule (shortest p_j/w_j first),
+/// and a dynamic program over the jobs in this order and the total processing time of accepted jobs
+/// decides which jobs to reject.
+/// Runs in O(n log n + n P) time and uses O(n P) memory, where P is the sum of all processing times.
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `weights`: The non-negative weights of the jobs
+/// * `rejection_costs`: The costs of rejecting the jobs
+///
+/// # Returns
+/// The schedule of the accepted jobs and the rejected jobs in ascending order.
+///
+/// # Panics
+/// If a processing time or weight is negative.
+///
+pub fn schedule_with_rejection(
+	ptimes: &[Time],
+	weights: &[Time],
+	rejection_costs: &[Time]
+) -> (MachineSchedule, Vec<Job>)
+{
+	assert_valid_ptimes(ptimes);
+	assert!(weights.iter().all(|&w| w >= 0), "Weights must not be negative");
+	let n = ptimes.len();
+	let order = smith_order(ptimes, weights);
+	let total: Time = ptimes.iter().sum();
+	let total = total as usize;
+	// cost[k][t]: minimum cost of the first k jobs in order
+	// if the accepted ones among them have total processing time t
+	let mut cost = vec![vec![Time::MAX; total + 1]; n + 1];
+	cost[0][0] = 0;
+	for (k, &job) in order.iter().enumerate() {
+		let p = ptimes[job] as usize;
+		for t in 0..=total {
+			let previous = cost[k][t];
+			if previous == Time::MAX {
+				continue;
+			}
+			let reject = previous + rejection_costs[job];
+			cost[k + 1][t] = cost[k + 1][t].min(reject);
+			let accept = previous + weights[job] * (t + p) as Time;
+			cost[k + 1][t + p] = cost[k + 1][t + p].min(accept);
+		}
+	}
+	let (mut t, _) = cost[n].iter().enumerate().min_by_key(|&(_, &c)| c).unwrap();
+	// reconstruct the decisions backwards
+	let mut accepted = Vec::new();
+	let mut rejected = Vec::new();
+	for k in (0..n).rev() {
+		let job = order[k];
+		let p = ptimes[job] as usize;
+		let current = cost[k + 1][t];
+		if cost[k][t] != Time::MAX && cost[k][t] + rejection_costs[job] == current {
+			rejected.push(job);
+		} else {
+			accepted.push(job);
+			t -= p;
+		}
+	}
+	accepted.reverse();
+	rejected.sort_unstable();
+	(MachineSchedule::from_order_ptimes(accepted.into_iter(), ptimes), rejected)
+}
+
+
+/// Sorts the jobs by Smith's rule, i.e. by ascending p_j/w_j.
+/// Jobs with weight 0 come last, and jobs with processing time and weight 0 first.
+fn smith_order(ptimes: &[Time], weights: &[Time]) -> Vec<Job> {
+	let ratio = |job: Job| match (ptimes[job], weights[job]) {
+		(0, 0) => (0, 1),
+		(p, w) => (p, w),
+	};
+	let mut order: Vec<Job> = (0..ptimes.len()).collect();
+	order.sort_by(|&a, &b| {
+		let (pa, wa) = ratio(a);
+		let (pb, wb) = ratio(b);
+		match (pa * wb).cmp(&(pb * wa)) {
+			Ordering::Equal => a.cmp(&b),
+			ordering => ordering,
+		}
+	});
+	order
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::SingleMachineInstance;
+	use crate::objective::{Objective, TotalCompletionTime, WeightedCompletionTime};
+	use crate::reference::{brute_force_single_machine, for_each_permutation};
+	use crate::testing::{assert_valid_schedule, SingleMachineParams};
+	use proptest::prelude::*;
+
+	#[test]
+	fn test_srpt_detailed() {
+		//           0  1  2
+		let p = vec![10, 4, 1];
+		let r = vec![ 0, 2, 3];
+		let (schedule, num_preemptions) = srpt_detailed(&p, &r);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 1 },
+			JobRun{ time: 3, job: 2, duration: 1 },
+			JobRun{ time: 4, job: 1, duration: 3 },
+			JobRun{ time: 7, job: 0, duration: 8 },
+		]);
+		// job 0 and job 1 are both resumed once
+		assert_eq!(num_preemptions, 2);
+		assert_eq!(TotalCompletionTime.evaluate_schedule(&schedule, &[0; 3]), 4 + 7 + 15);
+	}
+
+	#[test]
+	fn test_srpt_no_releases() {
+		let (schedule, num_preemptions) = srpt_detailed(&[3, 1, 2], &[0, 0, 0]);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 1, duration: 1 },
+			JobRun{ time: 1, job: 2, duration: 2 },
+			JobRun{ time: 3, job: 0, duration: 3 },
+		]);
+		assert_eq!(num_preemptions, 0);
+	}
+
+	#[test]
+	fn test_srpt_no_preemption_for_equal_remaining_time() {
+		let (schedule, num_preemptions) = srpt_detailed(&[4, 2], &[0, 2]);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 4 },
+			JobRun{ time: 4, job: 1, duration: 2 },
+		]);
+		assert_eq!(num_preemptions, 0);
+	}
+
+	/// Computes the optimal cost of scheduling with rejection by trying all sets of rejected jobs.
+	/// The minimum cost over all sets of rejected jobs and all orders of the accepted jobs.
+	fn brute_force_rejection(ptimes: &[Time], weights: &[Time], rejection_costs: &[Time]) -> Time {
+		let n = ptimes.len();
+		(0..1usize << n).map(|rejected| {
+			let accepted: Vec<Job> = (0..n).filter(|&j| rejected & (1 << j) == 0).collect();
+			let rejection: Time = (0..n).filter(|&j| rejected & (1 << j) != 0).map(|j| rejection_costs[j]).sum();
+			let mut best = Time::MAX;
+			for_each_permutation(accepted.len(), |order| {
+				let mut time = 0;
+				let cost: Time = order.iter().map(|&k| {
+					time += ptimes[accepted[k]];
+					weights[accepted[k]] * time
+				}).sum();
+				best = best.min(cost);
+			});
+			best + rejection
+		}).min().unwrap()
+	}
+
+	#[test]
+	fn test_wct_release_lower_bound_example() {
+		//           0  1  2
+		let p = vec![4, 1, 2];
+		let r = vec![0, 1, 8];
+		let w = vec![1, 3, 5];
+		// job 0 is preempted at 1 by job 1, leaving pieces [0, 1) and [2, 5) of weight 1/4 and 3/4,
+		// then job 2 runs in [8, 10): 1/4 * 1 + 3/4 * 5 + 3 * 2 + 5 * 10 = 60
+		assert_eq!(wct_release_lower_bound(&p, &r, &w), 60);
+		let (_, optimum) = brute_force_single_machine(&p, &r, &[0; 3], &WeightedCompletionTime(w.clone())).unwrap();
+		assert_eq!(optimum, 62);
+		assert_eq!(wct_release_lower_bound(&[0, 3], &[5, -2], &[2, 1]), 10 + 3);
+		assert_eq!(wct_release_lower_bound(&[], &[], &[]), 0);
+	}
+
+	#[test]
+	fn test_schedule_with_rejection() {
+		//               0  1  2   3
+		let p =     vec![2, 3, 1, 10];
+		let w =     vec![3, 1, 2,  2];
+		let e =     vec![50, 2, 9, 15];
+		let (schedule, rejected) = schedule_with_rejection(&p, &w, &e);
+		// accepting job 3 would cost at least 2*13
+		assert_eq!(rejected, vec![1, 3]);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 2, duration: 1 },
+			JobRun{ time: 1, job: 0, duration: 2 },
+		]);
+		let cost = WeightedCompletionTime(w.clone()).evaluate_schedule(&schedule, &[]) + 2 + 15;
+		// job 2 completes at 1, job 0 at 3
+		assert_eq!(cost, 2 + 3*3 + 2 + 15);
+		assert_eq!(cost, brute_force_rejection(&p, &w, &e));
+	}
+
+	proptest! {
+		#[test]
+		fn test_schedule_with_rejection_optimal(
+			jobs in proptest::collection::vec((0..10 as Time, 0..5 as Time, 0..60 as Time), 0..=7)
+		) {
+			let p: Vec<Time> = jobs.iter().map(|j| j.0).collect();
+			let w: Vec<Time> = jobs.iter().map(|j| j.1).collect();
+			let e: Vec<Time> = jobs.iter().map(|j| j.2).collect();
+			let (schedule, rejected) = schedule_with_rejection(&p, &w, &e);
+			let mut all_jobs: Vec<Job> = schedule.schedule.iter().map(|run| run.job).chain(rejected.iter().copied()).collect();
+			all_jobs.sort_unstable();
+			prop_assert_eq!(all_jobs, (0..p.len()).collect::<Vec<Job>>());
+			let rejection: Time = rejected.iter().map(|&j| e[j]).sum();
+			let cost = WeightedCompletionTime(w.clone()).evaluate_schedule(&schedule, &[]) + rejection;
+			prop_assert_eq!(cost, brute_force_rejection(&p, &w, &e));
+		}
+
+		#[test]
+		fn test_wct_release_lower_bound(
+			instance in SingleMachineInstance::arbitrary_with(SingleMachineParams{
+				num_jobs: 1..=6,
+				..SingleMachineParams::default()
+			}),
+			weights in proptest::collection::vec(0..6 as Time, 6)
+		) {
+			let SingleMachineInstance{ ptimes, release_times, due_times } = &instance;
+			let weights = weights[..ptimes.len()].to_vec();
+			let bound = wct_release_lower_bound(ptimes, release_times, &weights);
+			let objective = WeightedCompletionTime(weights.clone());
+			let (_, optimum) = brute_force_single_machine(ptimes, release_times, due_times, &objective).unwrap();
+			prop_assert!(bound <= optimum, "bound {} exceeds optimum {}", bound, optimum);
+			// without release times, no job is preempted and the bound is the value of Smith's rule
+			let zero = vec![0; ptimes.len()];
+			let smith = MachineSchedule::from_order_ptimes(smith_order(ptimes, &weights).into_iter(), ptimes);
+			prop_assert_eq!(wct_release_lower_bound(ptimes, &zero, &weights), objective.evaluate_schedule(&smith, &[]));
+		}
+
+		#[test]
+		fn test_srpt_dominates_nonpreemptive(
+			instance in SingleMachineInstance::arbitrary_with(SingleMachineParams{
+				num_jobs: 1..=6,
+				..SingleMachineParams::default()
+			})
+		) {
+			let SingleMachineInstance{ ptimes, release_times, due_times } = &instance;
+			let schedule = srpt(ptimes, release_times);
+			assert_valid_schedule(&schedule, &instance);
+			let (_, optimum) = brute_force_single_machine(
+				ptimes, release_times, due_times, &TotalCompletionTime
+			).unwrap();
+			prop_assert!(TotalCompletionTime.evaluate_schedule(&schedule, due_times) <= optimum);
+		}
+	}
+}
@@ -0,0 +1,181 @@
+use crate::{Time, Job, MachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+
+use std::cmp::max;
+use std::collections::BinaryHeap;
+use std::collections::binary_heap::PeekMut;
+
+
+/// Solves the timing problem of 1||sum a_j E_j + b_j T_j for a fixed job order:
+/// inserts idle time before jobs such that the total weighted earliness and tardiness is minimal,
+/// where E_j = max(0, d_j - C_j) and T_j = max(0, C_j - d_j).
+/// Unlike for regular objectives, delaying a job can pay off if it would otherwise complete early.
+///
+/// With x_i = C_i - (p_1 + ... + p_i) for the i-th job of the order, the problem becomes minimizing a sum of
+/// convex piecewise linear functions of x_i subject to 0 <= x_1 <= ... <= x_n, an isotonic regression.
+/// It is solved by merging blocks of jobs along the order, where every block is kept as a heap of the
+/// breakpoints of its cost function, see Garey, Tarjan, Wilfong: "One-processor scheduling with
+/// symmetric earliness and tardiness penalties" (1988).
+/// Runs in O(n log n) time for n jobs.
+///
+/// # Arguments
+/// * `order`: The order in which the jobs are processed
+/// * `ptimes`: The processing times of the jobs
+/// * `due_times`: The due times of the jobs
+/// * `earliness_weights`: The cost per time unit a job completes before its due time
+/// * `tardiness_weights`: The cost per time unit a job completes after its due time
+///
+/// # Returns
+/// A schedule processing the jobs of `order` in that order without preemption, starting no earlier than 0,
+/// with minimal weighted earliness and tardiness. Among the optimal schedules, jobs complete as early as possible.
+///
+/// # Panics
+/// If a processing time or weight is negative.
+pub fn optimal_idle_insertion(
+	order: &[Job],
+	ptimes: &[Time],
+	due_times: &[Time],
+	earliness_weights: &[Time],
+	tardiness_weights: &[Time]
+) -> MachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	for &job in order {
+		assert!(earliness_weights[job] >= 0 && tardiness_weights[job] >= 0,
+			"Weights of job #{} must not be negative", job
+		);
+	}
+	// the breakpoints of the cost of the current block with their change of slope,
+	// where the slope right of the largest breakpoint is 0 after merging
+	let mut breakpoints: BinaryHeap<(Time, Time)> = BinaryHeap::new();
+	// the minimum of the cost of the first i + 1 jobs if the (i + 1)-th job may end arbitrarily late
+	let mut minima: Vec<Time> = Vec::with_capacity(order.len());
+	let mut total = 0;
+	for &job in order {
+		total += ptimes[job];
+		let target = due_times[job] - total;
+		let (alpha, beta) = (earliness_weights[job], tardiness_weights[job]);
+		if alpha + beta > 0 {
+			breakpoints.push((target, alpha + beta));
+		}
+		// only the non-increasing part of the cost matters, as later jobs can still end later
+		let mut remaining = beta;
+		while remaining > 0 {
+			let mut top = breakpoints.peek_mut().unwrap();
+			if top.1 > remaining {
+				top.1 -= remaining;
+				remaining = 0;
+			} else {
+				remaining -= top.1;
+				PeekMut::pop(top);
+			}
+		}
+		// the earliest minimum, where the cost of a block without breakpoints is constant
+		minima.push(breakpoints.peek().map_or(Time::MIN, |&(b, _)| b));
+	}
+	// the optimal x_i are the prefix minima from the back, clipped at 0
+	let mut shifts = vec![0; order.len()];
+	let mut bound = Time::MAX;
+	for i in (0..order.len()).rev() {
+		bound = bound.min(minima[i]);
+		shifts[i] = max(bound, 0);
+	}
+	let mut schedule = MachineSchedule::new();
+	let mut completion = 0;
+	for (i, &job) in order.iter().enumerate() {
+		completion += ptimes[job];
+		let time = shifts[i] + completion - ptimes[job];
+		schedule.schedule.push(JobRun{ time, job, duration: ptimes[job] });
+	}
+	schedule
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	fn cost(schedule: &MachineSchedule, due_times: &[Time], earliness_weights: &[Time], tardiness_weights: &[Time]) -> Time {
+		schedule.schedule.iter().map(|run| {
+			let lateness = run.time + run.duration - due_times[run.job];
+			max(lateness, 0) * tardiness_weights[run.job] + max(-lateness, 0) * earliness_weights[run.job]
+		}).sum()
+	}
+
+	/// The minimum cost over all completion times up to `horizon`, by dynamic programming over time.
+	fn brute_force(
+		order: &[Job],
+		ptimes: &[Time],
+		due_times: &[Time],
+		earliness_weights: &[Time],
+		tardiness_weights: &[Time],
+		horizon: Time
+	) -> Time
+	{
+		// best[t] is the minimum cost of the jobs so far with the last one completing at or before t
+		let mut best = vec![0; horizon as usize + 1];
+		for &job in order {
+			let mut next = vec![Time::MAX; horizon as usize + 1];
+			for c in ptimes[job]..=horizon {
+				let previous = best[(c - ptimes[job]) as usize];
+				if previous < Time::MAX {
+					let lateness = c - due_times[job];
+					let cost = max(lateness, 0) * tardiness_weights[job] + max(-lateness, 0) * earliness_weights[job];
+					next[c as usize] = previous + cost;
+				}
+				if c > 0 {
+					next[c as usize] = next[c as usize].min(next[c as usize - 1]);
+				}
+			}
+			best = next;
+		}
+		best[horizon as usize]
+	}
+
+	#[test]
+	fn test_idle_insertion() {
+		let p = [2, 3, 1];
+		let d = [5, 6, 12];
+		let a = [1, 1, 1];
+		let b = [1, 1, 1];
+		let schedule = optimal_idle_insertion(&[0, 1, 2], &p, &d, &a, &b);
+		// jobs 0 and 1 form a block, which completes job 1 on time as the earliest of the optimal positions
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 1, job: 0, duration: 2 },
+			JobRun{ time: 3, job: 1, duration: 3 },
+			JobRun{ time: 11, job: 2, duration: 1 },
+		]);
+		assert_eq!(cost(&schedule, &d, &a, &b), 2);
+		// a high earliness weight of job 0 moves the block to complete job 0 on time
+		let schedule = optimal_idle_insertion(&[0, 1, 2], &p, &d, &[5, 1, 1], &b);
+		assert_eq!(schedule.schedule[0].time, 3);
+		assert_eq!(cost(&schedule, &d, &[5, 1, 1], &b), 2);
+		// without earliness weights, no idle time is inserted
+		let schedule = optimal_idle_insertion(&[2, 1, 0], &p, &d, &[0; 3], &b);
+		assert_eq!(schedule, MachineSchedule::from_order_ptimes([2, 1, 0].into_iter(), &p));
+		assert!(optimal_idle_insertion(&[], &p, &d, &a, &b).schedule.is_empty());
+	}
+
+	proptest! {
+		#[test]
+		fn test_idle_insertion_brute_force(
+			jobs in proptest::collection::vec((0..5 as Time, 0..20 as Time, 0..4 as Time, 0..4 as Time), 0..6)
+		) {
+			let p: Vec<Time> = jobs.iter().map(|j| j.0).collect();
+			let d: Vec<Time> = jobs.iter().map(|j| j.1).collect();
+			let a: Vec<Time> = jobs.iter().map(|j| j.2).collect();
+			let b: Vec<Time> = jobs.iter().map(|j| j.3).collect();
+			let order: Vec<Job> = (0..jobs.len()).collect();
+			let schedule = optimal_idle_insertion(&order, &p, &d, &a, &b);
+			prop_assert_eq!(schedule.job_order(), order.clone());
+			let mut ready = 0;
+			for run in schedule.schedule.iter() {
+				prop_assert!(run.time >= ready && run.duration == p[run.job]);
+				ready = run.time + run.duration;
+			}
+			let horizon = 20 + p.iter().sum::<Time>();
+			prop_assert_eq!(cost(&schedule, &d, &a, &b), brute_force(&order, &p, &d, &a, &b, horizon));
+		}
+	}
+}
@@ -0,0 +1,96 @@
+use crate::{Time, Job, MachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+
+
+/// Constructs a schedule processing the jobs in the given order without idle time,
+/// except for a setup of length `family_setup` before the first job
+/// and whenever two consecutive jobs belong to different families.
+/// The machine is idle during setups, so they appear as gaps in the schedule.
+///
+/// # Arguments
+/// * `ptimes`: ptimes[i] is the processing time of job i.
+/// * `families`: families[i] is the family of job i.
+/// * `family_setup`: The time needed to set up the machine for a family.
+/// * `order`: The order of the jobs
+///
+/// # Panics
+/// If a processing time or the setup time is negative.
+pub fn family_schedule(
+	ptimes: &[Time],
+	families: &[usize],
+	family_setup: Time,
+	order: &[Job]
+) -> MachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	assert!(family_setup >= 0, "Setup time must not be negative");
+	let mut time = 0;
+	let mut previous_family = None;
+	let schedule = order.iter().map(|&job| {
+		if previous_family != Some(families[job]) {
+			time += family_setup;
+			previous_family = Some(families[job]);
+		}
+		let run = JobRun{ time, job, duration: ptimes[job] };
+		time += ptimes[job];
+		run
+	}).collect();
+	MachineSchedule{ schedule }
+}
+
+
+/// Reorders the jobs such that the jobs of each family are consecutive,
+/// which minimizes the number of family switches.
+/// Families appear in the order of their first job in `order`,
+/// and the jobs of each family keep their relative order.
+///
+/// # Arguments
+/// * `order`: The order of the jobs
+/// * `families`: families[i] is the family of job i.
+pub fn group_by_family(order: &[Job], families: &[usize]) -> Vec<Job> {
+	let mut first_position = vec![usize::MAX; families.iter().map(|&f| f + 1).max().unwrap_or(0)];
+	for (position, &job) in order.iter().enumerate() {
+		let first = &mut first_position[families[job]];
+		*first = (*first).min(position);
+	}
+	let mut result = order.to_vec();
+	// stable sort keeps the relative order within each family
+	result.sort_by_key(|&job| first_position[families[job]]);
+	result
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_family_schedule() {
+		//                  0  1  2  3
+		let p        = vec![3, 2, 4, 1];
+		let families = vec![0, 1, 0, 1];
+		let schedule = family_schedule(&p, &families, 5, &[0, 2, 1, 3]);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 5,  job: 0, duration: 3 },
+			JobRun{ time: 8,  job: 2, duration: 4 },
+			JobRun{ time: 17, job: 1, duration: 2 },
+			JobRun{ time: 19, job: 3, duration: 1 },
+		]);
+		// alternating families need a setup before every job
+		assert_eq!(family_schedule(&p, &families, 5, &[0, 1, 2, 3]).makespan(), 4*5 + 10);
+		// without setup times, the schedule has no gaps
+		assert_eq!(
+			family_schedule(&p, &families, 0, &[3, 2, 1, 0]),
+			MachineSchedule::from_order_ptimes(vec![3, 2, 1, 0].into_iter(), &p)
+		);
+	}
+
+	#[test]
+	fn test_group_by_family() {
+		let families = vec![2, 0, 2, 1, 0, 1];
+		let order = group_by_family(&[3, 0, 1, 5, 2, 4], &families);
+		assert_eq!(order, vec![3, 5, 0, 2, 1, 4]);
+		let p = vec![1; 6];
+		assert_eq!(family_schedule(&p, &families, 10, &order).makespan(), 3*10 + 6);
+	}
+}
@@ -1,4 +1,5 @@
-use crate::{Time, Job, MachineSchedule};
+use crate::{Time, Job, MachineSchedule, JobSet};
+use std::cmp::max;
 
 use std::collections::BinaryHeap;
 
@@ -44,13 +45,91 @@ pub fn schedule_hodgson(
 	jobs[0..n-num_late].sort_unstable_by_key(|&job| due_times[job]);
 	MachineSchedule::from_order_ptimes(
 		jobs.into_iter(),
-		&ptimes
+		ptimes
 	)
 }
 
+/// `schedule_hodgson` for the live jobs of `set`, ignoring their release times,
+/// with the jobs of the schedule being their ids.
+pub fn schedule_hodgson_on(set: &JobSet) -> MachineSchedule {
+	set.to_ids(schedule_hodgson(set.processing_times(), set.due_times()))
+}
+
+/// Schedules as many jobs as possible to complete by their due times, i.e. maximizes the throughput,
+/// which is equivalent to minimizing the number of tardy jobs with `schedule_hodgson`.
+///
+/// # Returns
+/// The schedule of `schedule_hodgson` and the number of jobs that complete on time.
+pub fn max_on_time(ptimes: &[Time], due_times: &[Time]) -> (MachineSchedule, usize) {
+	let schedule = schedule_hodgson(ptimes, due_times);
+	let num_on_time = ptimes.len() - schedule.num_tardy(due_times);
+	(schedule, num_on_time)
+}
+
+/// Solves 1|r_j|num_tardy optimally if the release times and due times are agreeable,
+/// i.e. r_i < r_j implies d_i <= d_j.
+/// Without this condition, the problem is strongly NP-hard, and None is returned.
+/// Jobs are added in order of release time, and whenever the jobs on time can no longer all meet their due times,
+/// the job whose removal leaves the earliest completion of the remaining jobs on time is made late.
+/// The machine is available from time 0.
+/// Takes O(n^3) time for n jobs.
+///
+/// See Kise, Ibaraki, Mine: "A solvable case of the one-machine scheduling problem
+/// with ready and due times" (1978); doi:10.1287/opre.26.1.121
+///
+/// # Returns
+/// A schedule in which the jobs on time are processed in order of release time, followed by the late jobs,
+/// or None if the release times and due times are not agreeable.
+pub fn schedule_hodgson_agreeable(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time]
+) -> Option<MachineSchedule>
+{
+	let n = ptimes.len();
+	let mut jobs: Vec<Job> = (0..n).collect();
+	jobs.sort_unstable_by_key(|&job| (release_times[job], due_times[job]));
+	if jobs.windows(2).any(|pair| due_times[pair[0]] > due_times[pair[1]]) {
+		return None;
+	}
+	// the completion time of the given jobs in the given order starting at time 0, or None if one of them is late
+	let completion = |on_time: &[Job]| on_time.iter().try_fold(0, |t, &job| {
+		let end = max(t, release_times[job]) + ptimes[job];
+		(end <= due_times[job]).then_some(end)
+	});
+	let mut on_time: Vec<Job> = Vec::new();
+	let mut late = Vec::new();
+	for &job in jobs.iter() {
+		on_time.push(job);
+		if completion(&on_time).is_some() {
+			continue;
+		}
+		// the added job itself can always be removed
+		let (_, remove) = (0..on_time.len())
+			.filter_map(|i| {
+				let mut rest = on_time.clone();
+				rest.remove(i);
+				completion(&rest).map(|c| (c, i))
+			})
+			.min()
+			.unwrap();
+		late.push(on_time.remove(remove));
+	}
+	Some(MachineSchedule::from_order_ptimes_releasetimes(
+		on_time.into_iter().chain(late),
+		ptimes,
+		release_times
+	))
+}
+
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::objective::NumTardy;
+	use crate::reference::brute_force_single_machine;
+	use crate::SingleMachineInstance;
+	use proptest::prelude::*;
 
 	fn example_1() -> (Vec<Time>, Vec<Time>) {
 		// this is example 4.3.7 in Blazewicz et al, "Handbook on Scheduling"
@@ -66,7 +145,69 @@ mod tests {
 		let (p, d) = example_1();
 		let expected_order = vec![4, 3, 2, 1, 6, 0]; // the remaining two can be in arbitrary order
 		let result = schedule_hodgson(&p, &d);
-		let order : Vec<Job> = result.schedule.iter().map(|&jr| jr.job).collect();
-		assert_eq!(order[..6], expected_order);
+		assert_eq!(result.job_order()[..6], expected_order);
+	}
+
+	#[test]
+	fn test_hodgson_zero_duration() {
+		//           0  1  2
+		let p = vec![2, 0, 3];
+		let d = vec![1, 0, 3];
+		let schedule = schedule_hodgson(&p, &d);
+		assert_eq!(schedule.job_order(), vec![1, 2, 0]);
+		assert_eq!(schedule.num_tardy(&d), 1);
+	}
+
+	#[test]
+	fn test_hodgson_example_1_brute_force() {
+		let (p, d) = example_1();
+		let (_, optimum) = brute_force_single_machine(&p, &vec![0; p.len()], &d, &NumTardy).unwrap();
+		assert_eq!(schedule_hodgson(&p, &d).num_tardy(&d) as Time, optimum);
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_max_on_time() {
+		let (p, d) = example_1();
+		let (schedule, num_on_time) = max_on_time(&p, &d);
+		assert_eq!(num_on_time, 6);
+		assert_eq!(schedule, schedule_hodgson(&p, &d));
+		assert_eq!(max_on_time(&[], &[]).1, 0);
+	}
+
+	#[test]
+	fn test_hodgson_agreeable_example() {
+		//           0  1  2  3
+		let p = vec![4, 3, 2, 3];
+		let r = vec![0, 1, 2, 5];
+		let d = vec![5, 6, 7, 9];
+		let schedule = schedule_hodgson_agreeable(&p, &r, &d).unwrap();
+		// job 0 blocks both jobs 1 and 2, dropping it leaves 1 and 2 on time until 6, and 3 until 9
+		assert_eq!(schedule.job_order(), vec![1, 2, 3, 0]);
+		assert_eq!(schedule.num_tardy(&d), 1);
+		assert!(schedule.respects_releases(&r));
+	}
+
+	#[test]
+	fn test_hodgson_agreeable_rejects() {
+		let p = vec![1, 1];
+		let r = vec![0, 1];
+		let d = vec![5, 4];
+		assert_eq!(schedule_hodgson_agreeable(&p, &r, &d), None);
+		// equal release times allow any due times
+		assert!(schedule_hodgson_agreeable(&p, &[0, 0], &d).is_some());
+	}
+
+	proptest! {
+		#[test]
+		fn test_hodgson_agreeable_brute_force(instance in any::<SingleMachineInstance>()) {
+			let SingleMachineInstance{ ptimes: p, mut release_times, mut due_times } = instance;
+			// pairing sorted release times with sorted due times makes them agreeable
+			release_times.sort_unstable();
+			due_times.sort_unstable();
+			let schedule = schedule_hodgson_agreeable(&p, &release_times, &due_times).unwrap();
+			prop_assert!(schedule.respects_releases(&release_times));
+			let (_, optimum) = brute_force_single_machine(&p, &release_times, &due_times, &NumTardy).unwrap();
+			prop_assert_eq!(schedule.num_tardy(&due_times) as Time, optimum);
+		}
+	}
+}
@@ -1,6 +1,12 @@
-use crate::{Time, Job, MachineSchedule, JobRun};
-use std::cmp::{max, min, Reverse};
-use std::collections::BinaryHeap;
+use crate::{Time, Job, MachineSchedule, JobRun, JobSet, ScheduleBuildOptions, Calendar};
+use crate::schedule::assert_valid_ptimes;
+use super::edd_preemptive;
+use crate::unrelated_machines::PrecedenceGraph;
+use crate::rng::Rng;
+use std::cmp::{max, min, Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 
 /// Schrage's heuristic for 1|r_j|L_max.
@@ -18,18 +24,440 @@ pub fn schrage(
 	due_times: &[Time]
 ) -> MachineSchedule
 {
+	schrage_with_scratch(ptimes, release_times, due_times, TieBreak::default(), &mut SchrageScratch::default())
+}
+
+
+/// `schrage` choosing among released jobs with the same due time according to `tie_break`.
+/// The policy only affects ties, so the schedule is still one of Schrage's heuristic,
+/// but its maximum lateness may differ between policies.
+/// See `CarlierSearch::set_tie_break` for a policy that keeps the optimal value.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn schrage_with_tie_break(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	tie_break: TieBreak
+) -> MachineSchedule
+{
+	schrage_with_scratch(ptimes, release_times, due_times, tie_break, &mut SchrageScratch::default())
+}
+
+
+/// How Schrage's heuristic chooses among released jobs with the same due time.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum TieBreak {
+	/// The job with the longest processing time first, then the one with the largest index.
+	#[default]
+	EarliestDueLongestProcessing,
+	/// The job with the smallest index first.
+	EarliestDueSmallestIndex,
+	/// The job with the earliest release time first, then the one with the smallest index.
+	EarliestRelease,
+	/// Job `a` before job `b` if the function returns `Ordering::Less` for `(a, b)`.
+	/// The function must be a total order of the jobs.
+	Custom(fn(Job, Job) -> Ordering),
+}
+
+impl TieBreak {
+	/// Writes a priority for every job to `priorities`, where a larger priority is chosen first,
+	/// using `jobs` as a buffer.
+	fn priorities(self, ptimes: &[Time], release_times: &[Time], priorities: &mut Vec<Time>, jobs: &mut Vec<Job>) {
+		priorities.clear();
+		jobs.clear();
+		jobs.extend(0..ptimes.len());
+		match self {
+			TieBreak::EarliestDueLongestProcessing => {
+				// ties of processing times are broken by the index in the heap
+				priorities.extend_from_slice(ptimes);
+				return;
+			},
+			TieBreak::EarliestDueSmallestIndex => (),
+			TieBreak::EarliestRelease => jobs.sort_by_key(|&job| (release_times[job], job)),
+			TieBreak::Custom(f) => jobs.sort_by(|&a, &b| f(a, b)),
+		}
+		priorities.resize(ptimes.len(), 0);
+		for (rank, &job) in jobs.iter().enumerate() {
+			priorities[job] = -(rank as Time);
+		}
+	}
+}
+
+
+/// `schrage` with a setup before and a teardown after every job, see `ScheduleBuildOptions`
+/// and `carlier_with_options`.
+///
+/// # Panics
+/// If a processing time, the setup time or the teardown time is negative.
+pub fn schrage_with_options(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	options: &ScheduleBuildOptions
+) -> MachineSchedule
+{
+	let busy_times = options.busy_times(ptimes);
+	let due_times: Vec<Time> = due_times.iter().map(|d| d + options.teardown).collect();
+	options.processing_runs(schrage(&busy_times, release_times, &due_times), ptimes)
+}
+
+
+/// `schrage` for a machine that only processes jobs during the working time of `calendar`.
+/// Whenever the machine is idle, the released job with the earliest due time
+/// (longest processing time first in case of ties) is started at the next working time,
+/// considering all jobs released by then, and is processed without interruption by other jobs.
+/// Its processing is split into one run per interval of working time it needs,
+/// see `MachineSchedule::from_order_ptimes_releasetimes_calendar`.
+/// With `Calendar::always()`, the schedule is the same as from `schrage`.
+///
+/// # Panics
+/// If a processing time is negative, or the calendar has no working time.
+pub fn schrage_calendar(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	calendar: &Calendar
+) -> MachineSchedule
+{
+	assert_valid_ptimes(ptimes);
 	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
+	jobs.sort_unstable_by_key(|&job| -release_times[job]);
+	let mut ready_to_run: BinaryHeap<(Time, Time, Job)> = BinaryHeap::new();
+	let mut schedule = Vec::with_capacity(ptimes.len());
+	let mut t: Time = 0;
+	while !jobs.is_empty() || !ready_to_run.is_empty() {
+		let start = calendar.next_working_time(t);
+		while !jobs.is_empty() && release_times[*jobs.last().unwrap()] <= start {
+			let job = jobs.pop().unwrap();
+			ready_to_run.push((-due_times[job], ptimes[job], job));
+		}
+		match ready_to_run.pop() {
+			Some((_, _, job)) if ptimes[job] == 0 => {
+				schedule.push(JobRun{ time: start, job, duration: 0 });
+				t = start;
+			},
+			Some((_, _, job)) => {
+				for (a, b) in calendar.working_intervals(start, ptimes[job]) {
+					schedule.push(JobRun{ time: a, job, duration: b - a });
+					t = b;
+				}
+			},
+			None => t = release_times[*jobs.last().unwrap()],
+		}
+	}
+	MachineSchedule{ schedule }
+}
+
+
+/// Returns the maximum lateness of processing the jobs in the given order as early as possible,
+/// i.e. of `MachineSchedule::from_order_ptimes_releasetimes(order, ptimes, release_times)`,
+/// without building the schedule.
+/// Runs in O(n) time without allocating.
+///
+/// # Arguments
+///
+/// * `order`: The jobs in the order in which they are processed
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `due_times`: The due times of the jobs
+///
+/// # Panics
+/// If a processing time is negative or `order` is empty.
+///
+pub fn lmax_of_order(order: &[Job], ptimes: &[Time], release_times: &[Time], due_times: &[Time]) -> Time {
+	assert_valid_ptimes(ptimes);
+	let mut time = 0;
+	order.iter().map(|&job| {
+		time = max(time, release_times[job]) + ptimes[job];
+		time - due_times[job]
+	}).max().expect("Order is empty")
+}
+
+
+/// Returns the makespan of processing the jobs in the given order as early as possible,
+/// i.e. of `MachineSchedule::from_order_ptimes_releasetimes(order, ptimes, release_times)`,
+/// without building the schedule.
+/// Runs in O(n) time without allocating.
+///
+/// # Arguments
+///
+/// * `order`: The jobs in the order in which they are processed
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+///
+/// # Panics
+/// If a processing time is negative.
+///
+pub fn makespan_of_order(order: &[Job], ptimes: &[Time], release_times: &[Time]) -> Time {
+	assert_valid_ptimes(ptimes);
+	order.iter().fold(0, |time, &job| max(time, release_times[job]) + ptimes[job])
+}
+
+
+/// Schrage's heuristic for 1|prec,r_j|L_max.
+/// First, release times are propagated forward along the precedence constraints,
+/// so that no job is released before each of its predecessors could be completed,
+/// and due times are propagated backward, so that every job is due early enough for its successors.
+/// Then, whenever the machine is idle, the released job with the earliest modified due time
+/// among the jobs whose predecessors are completed is started (the lowest index in case of ties).
+/// The modified due times do not change the maximum lateness of schedules respecting the precedence constraints,
+/// so the result should be evaluated with the original due times.
+/// Runs in O(n^2 + e) time for n jobs and e precedence constraints.
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `due_times`: The due times of the jobs
+/// * `predecessor`: `predecessor[j]` are the jobs that need to be completed before job `j` can be started
+///
+/// # Panics
+/// If a processing time is negative or the precedence constraints contain a cycle.
+///
+pub fn schrage_precedence(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	predecessor: &[Vec<Job>]
+) -> MachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	let n = ptimes.len();
+	// topological order
+	let mut graph = PrecedenceGraph::new(predecessor.to_vec());
+	let mut order = Vec::with_capacity(n);
+	while let Some(&job) = graph.available_jobs().first() {
+		graph.mark_job_completed(job);
+		order.push(job);
+	}
+	assert_eq!(order.len(), n, "Precedence constraints contain a cycle");
+	let mut release_times = release_times.to_vec();
+	for &job in order.iter() {
+		for &p in predecessor[job].iter() {
+			release_times[job] = max(release_times[job], release_times[p] + ptimes[p]);
+		}
+	}
+	let mut due_times = due_times.to_vec();
+	for &job in order.iter().rev() {
+		for &p in predecessor[job].iter() {
+			due_times[p] = min(due_times[p], due_times[job] - ptimes[job]);
+		}
+	}
+	let mut graph = PrecedenceGraph::new(predecessor.to_vec());
+	let mut schedule = Vec::with_capacity(n);
+	let mut t: Time = 0;
+	while schedule.len() < n {
+		let next = graph.available_jobs().iter()
+			.filter(|&&job| release_times[job] <= t)
+			.min_by_key(|&&job| (due_times[job], job));
+		match next {
+			Some(&job) => {
+				schedule.push(JobRun{ time: t, job, duration: ptimes[job] });
+				t += ptimes[job];
+				graph.mark_job_completed(job);
+			},
+			// wait for the next release of a job whose predecessors are completed
+			None => t = graph.available_jobs().iter().map(|&job| release_times[job]).min().unwrap(),
+		}
+	}
+	MachineSchedule{ schedule }
+}
+
+
+/// Like `schrage`, but passes every run to `on_run` in order of start time instead of storing it,
+/// e.g. to a `ScheduleStats`.
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn schrage_streaming<F: FnMut(JobRun)>(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	on_run: F
+)
+{
+	schrage_streaming_with_scratch(ptimes, release_times, due_times, TieBreak::default(), &mut SchrageScratch::default(), on_run);
+}
+
+
+/// `schrage` for the live jobs of `set`, with the jobs of the schedule being their ids.
+pub fn schrage_on(set: &JobSet) -> MachineSchedule {
+	set.to_ids(schrage(set.processing_times(), set.release_times(), set.due_times()))
+}
+
+/// GRASP for 1|r_j|L_max: runs a randomized version of Schrage's heuristic from several starts,
+/// improves every schedule by a local search on its critical block and returns the best schedule found.
+/// Whenever the machine is idle, the randomized heuristic starts a random released job
+/// among those with a due time of at most d_min + alpha * (d_max - d_min),
+/// where d_min and d_max are the earliest and latest due time of the released jobs.
+/// The local search moves the job of maximum lateness to the start of its critical block
+/// or swaps it with the first job of the block or the job before it, as long as this reduces the maximum lateness.
+/// The first start uses the plain `schrage` schedule, so the result is never worse than that.
+/// Runs in O(starts * n^2) time for n jobs, not counting the local search.
+///
+/// # Arguments
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `due_times`: The due times of the jobs
+/// * `starts`: The number of starts including the plain Schrage start, which is always run
+/// * `alpha`: Between 0, choosing among the jobs with the earliest due time, and 1, choosing among all released jobs
+/// * `seed`: Seed for the random choices, the result is deterministic for a given seed
+///
+/// # Panics
+/// If a processing time is negative or `alpha` is not between 0 and 1.
+pub fn grasp_lmax(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	starts: usize,
+	alpha: f64,
+	seed: u64
+) -> MachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	assert!((0.0..=1.0).contains(&alpha), "alpha must be between 0 and 1, but is {}", alpha);
+	if ptimes.is_empty() {
+		return MachineSchedule::new();
+	}
+	let mut rng = Rng::new(seed);
+	let mut best: Option<(Time, Vec<Job>)> = None;
+	for start in 0..max(starts, 1) {
+		let order = if start == 0 {
+			schrage(ptimes, release_times, due_times).job_order()
+		} else {
+			randomized_schrage_order(ptimes, release_times, due_times, alpha, &mut rng)
+		};
+		let (lateness, order) = critical_block_search(order, ptimes, release_times, due_times);
+		if best.as_ref().is_none_or(|(b, _)| lateness < *b) {
+			best = Some((lateness, order));
+		}
+	}
+	MachineSchedule::from_order_ptimes_releasetimes_slice(&best.unwrap().1, ptimes, release_times)
+}
+
+/// The order of a randomized Schrage heuristic for `grasp_lmax`.
+fn randomized_schrage_order(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	alpha: f64,
+	rng: &mut Rng
+) -> Vec<Job>
+{
+	let n = ptimes.len();
+	// sorted by descending release time to pop the earliest released job
+	let mut jobs: Vec<Job> = (0..n).collect();
+	jobs.sort_unstable_by_key(|&job| Reverse(release_times[job]));
+	let mut ready: Vec<Job> = Vec::new();
+	let mut order = Vec::with_capacity(n);
+	let mut t: Time = 0;
+	while order.len() < n {
+		while let Some(&job) = jobs.last() {
+			if release_times[job] > t {
+				break;
+			}
+			ready.push(job);
+			jobs.pop();
+		}
+		if ready.is_empty() {
+			t = release_times[*jobs.last().unwrap()];
+			continue;
+		}
+		let earliest = ready.iter().map(|&job| due_times[job]).min().unwrap();
+		let latest = ready.iter().map(|&job| due_times[job]).max().unwrap();
+		let threshold = earliest + (alpha * (latest - earliest) as f64) as Time;
+		let candidates: Vec<usize> = (0..ready.len()).filter(|&i| due_times[ready[i]] <= threshold).collect();
+		let job = ready.swap_remove(candidates[rng.below(candidates.len())]);
+		order.push(job);
+		t += ptimes[job];
+	}
+	order
+}
+
+/// The local search of `grasp_lmax`, returning the maximum lateness and the improved order.
+fn critical_block_search(
+	mut order: Vec<Job>,
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time]
+) -> (Time, Vec<Job>)
+{
+	let mut lateness = lmax_of_order(&order, ptimes, release_times, due_times);
+	loop {
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes_slice(&order, ptimes, release_times);
+		let (a, c) = critical_path(&schedule, due_times);
+		if a == c {
+			return (lateness, order);
+		}
+		let mut to_front = order.clone();
+		let job = to_front.remove(c);
+		to_front.insert(a, job);
+		let mut with_first = order.clone();
+		with_first.swap(a, c);
+		let mut with_previous = order.clone();
+		with_previous.swap(c - 1, c);
+		let improvement = [to_front, with_first, with_previous].into_iter()
+			.map(|candidate| (lmax_of_order(&candidate, ptimes, release_times, due_times), candidate))
+			.find(|(l, _)| *l < lateness);
+		match improvement {
+			Some((l, candidate)) => {
+				lateness = l;
+				order = candidate;
+			},
+			None => return (lateness, order),
+		}
+	}
+}
+
+
+/// Buffers of `schrage` that can be reused across calls to save allocations.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SchrageScratch {
+	jobs: Vec<Job>,
+	ready_to_run: BinaryHeap<(Time, Time, Job)>,
+	priorities: Vec<Time>,
+}
+
+/// `schrage` using the buffers of `scratch`.
+pub(crate) fn schrage_with_scratch(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	tie_break: TieBreak,
+	scratch: &mut SchrageScratch
+) -> MachineSchedule
+{
+	let mut schedule = Vec::with_capacity(ptimes.len());
+	schrage_streaming_with_scratch(ptimes, release_times, due_times, tie_break, scratch, |run| schedule.push(run));
+	MachineSchedule{ schedule }
+}
+
+/// `schrage_streaming` using the buffers of `scratch`.
+fn schrage_streaming_with_scratch<F: FnMut(JobRun)>(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	tie_break: TieBreak,
+	scratch: &mut SchrageScratch,
+	mut on_run: F
+)
+{
+	assert_valid_ptimes(ptimes);
+	let SchrageScratch{ jobs, ready_to_run, priorities } = scratch;
+	tie_break.priorities(ptimes, release_times, priorities, jobs);
+	jobs.clear();
+	jobs.extend(0..ptimes.len());
 	// sort by descending release time
 	// because we want to pop the jobs with lowest release time first
 	jobs.sort_unstable_by_key(|&job| -release_times[job]);
 	// A list of jobs that in a current moment are ready to run,
 	// sorted by "earliest due time first",
-	// using "longest processing time first" as tiebreaker.
-	let mut ready_to_run = BinaryHeap::new();
+	// using the priorities of `tie_break` as tiebreaker.
+	ready_to_run.clear();
 	// Time tracking variable
 	let mut t: Time = 0;
-	// The final sequence in which the jobs should be run
-	let mut schedule = Vec::new();
 
 	// Iterate over jobs in order of release time
 	while !jobs.is_empty() || !ready_to_run.is_empty() {
@@ -40,13 +468,13 @@ pub fn schrage(
 			let job = jobs.pop().unwrap();
 			// first and second tuple entry are just to determine the correct order
 			ready_to_run.push(
-				( -due_times[job], ptimes[job], job )
+				( -due_times[job], priorities[job], job )
 			);
 		}
 		// If there are jobs that are ready to run, schedule them
 		match ready_to_run.pop() {
 			Some((_, _, job)) => {
-				schedule.push(job);
+				on_run(JobRun{ time: t, job, duration: ptimes[job] });
 				t += ptimes[job];
 			},
 			None => {
@@ -57,7 +485,6 @@ pub fn schrage(
 			}
 		};
 	}
-	MachineSchedule::from_order_ptimes_releasetimes(schedule.into_iter(), ptimes, release_times)
 }
 
 
@@ -65,6 +492,7 @@ pub fn schrage(
 /// Carlier's algorithm for 1|r_j|L_max
 /// Uses Schrage's heuristic and a branch-and-bound approach to solve the problem.
 /// Note that the worst-case running time is exponential (the problem is strongly NP-hard).
+/// Runs a `CarlierSearch` to completion.
 /// 
 /// See [J. Carlier: "The one-machine sequencing problem" (1982); doi:10.1016/S0377-2217(82)80007-6]
 ///
@@ -73,50 +501,459 @@ pub fn schrage(
 /// * `jobs`: A list of jobs.
 ///
 pub fn carlier(ptimes: &[Time], release_times: &[Time], due_times: &[Time]) -> MachineSchedule {
-	if ptimes.is_empty() {
-		return MachineSchedule{ schedule: vec![] }
-	}
-	let mut subproblems = BinaryHeap::new();
-	subproblems.push( Reverse((
-		Time::MIN,
-		CarlierNode{
-			release_times: release_times.to_vec(),
-			due_times: due_times.to_vec(),
-		}
-	)));
-	let mut best_lateness = Time::MAX;
-	let mut best_schedule = None;
-	while let Some(Reverse((lower_bound, node))) = subproblems.pop() {
-		if lower_bound >= best_lateness {
-			continue;
+	let mut search = CarlierSearch::new(ptimes, release_times, due_times);
+	search.step(usize::MAX);
+	search.best_schedule().unwrap().clone()
+}
+
+
+/// `carlier` with a setup before and a teardown after every job, see `ScheduleBuildOptions`.
+/// Since the teardown does not count towards the lateness, the instance is solved with busy times
+/// including setup and teardown and due times extended by the teardown,
+/// so the result is optimal for the given options.
+///
+/// # Panics
+/// If a processing time, the setup time or the teardown time is negative.
+pub fn carlier_with_options(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	options: &ScheduleBuildOptions
+) -> MachineSchedule
+{
+	let busy_times = options.busy_times(ptimes);
+	let due_times: Vec<Time> = due_times.iter().map(|d| d + options.teardown).collect();
+	options.processing_runs(carlier(&busy_times, release_times, &due_times), ptimes)
+}
+
+
+/// `carlier` for the live jobs of `set`, with the jobs of the schedule being their ids.
+pub fn carlier_on(set: &JobSet) -> MachineSchedule {
+	set.to_ids(carlier(set.processing_times(), set.release_times(), set.due_times()))
+}
+
+/// The branch-and-bound search of `carlier`, which can be interrupted after any number of nodes
+/// and provides the best schedule found so far together with a lower bound.
+/// The state can be saved with `snapshot` and restored with `resume`.
+/// Optionally, explored subproblems are remembered so that identical subproblems
+/// reached through different branches are not explored again (see `set_memo_capacity`).
+#[derive(Debug, Clone)]
+pub struct CarlierSearch {
+	ptimes: Vec<Time>,
+	due_times: Vec<Time>,
+	strategy: SearchStrategy,
+	tie_break: TieBreak,
+	// open nodes ordered by lower bound, used by best-first search
+	open_nodes: BinaryHeap<Reverse<(Time, CarlierNode)>>,
+	// open nodes in the order they are explored by depth-first search, the next one last
+	open_stack: Vec<(Time, CarlierNode)>,
+	peak_open_nodes: usize,
+	best_lateness: Time,
+	best_schedule: Option<MachineSchedule>,
+	nodes_explored: usize,
+	memo: Memo,
+	scratch: SchrageScratch,
+}
+
+
+/// The order in which a `CarlierSearch` explores the open subproblems.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum SearchStrategy {
+	/// Explores the subproblem with the smallest lower bound first.
+	/// Needs the fewest nodes, but may keep many subproblems open.
+	#[default]
+	BestFirst,
+	/// Explores the most recently created subproblem first, the more promising child before the other.
+	/// Keeps few subproblems open, but may explore more nodes.
+	DepthFirst,
+	/// Searches depth-first until `switch_after_nodes` subproblems have been explored, then best-first.
+	DepthFirstThenBest {
+		switch_after_nodes: usize,
+	},
+}
+
+
+/// Counters of a `CarlierSearch`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct SearchStats {
+	/// The number of explored subproblems
+	pub nodes_explored: usize,
+	/// The largest number of open subproblems at any time since the search was started or resumed
+	pub peak_open_nodes: usize,
+	/// The number of subproblems skipped because an identical subproblem had already been explored
+	pub memo_hits: usize,
+	/// The number of subproblems looked up in the memo without success
+	pub memo_misses: usize,
+}
+
+
+/// Transposition table of explored subproblems, keyed by the hash of their tightened release and due times.
+/// Each entry stores the subproblem and the upper bound it was explored with.
+/// Holds at most `capacity` entries and evicts the oldest entry first.
+#[derive(Debug, Clone, Default)]
+struct Memo {
+	capacity: usize,
+	entries: HashMap<u64, (CarlierNode, Time)>,
+	insertion_order: VecDeque<u64>,
+	hits: usize,
+	misses: usize,
+}
+
+impl Memo {
+	/// Returns whether `node` has already been explored with an upper bound of at least `upper_bound`,
+	/// in which case exploring it again cannot find anything new.
+	/// Otherwise, records `node` as explored with `upper_bound`.
+	fn check_and_insert(&mut self, node: &CarlierNode, upper_bound: Time) -> bool {
+		if self.capacity == 0 {
+			return false;
 		}
-		let result = carlier_iteration(
-			ptimes,
-			node.release_times,
-			node.due_times,
-			best_lateness
-		);
-		let lateness = result.schedule.max_lateness(due_times);
-		if lateness < best_lateness {
-			best_lateness = lateness;
-			best_schedule = Some(result.schedule);
-		}
-		if result.lower_bound < best_lateness && result.subproblems.is_some() {
-			let new_lower_bound = max(result.lower_bound, lower_bound);
-			let children = result.subproblems.unwrap();
-			for child in children.into_iter() {
-				subproblems.push( Reverse((
-					new_lower_bound,
-					child
-				)));
+		let mut hasher = DefaultHasher::new();
+		node.hash(&mut hasher);
+		let key = hasher.finish();
+		match self.entries.get_mut(&key) {
+			Some((stored, stored_bound)) if stored == node && *stored_bound >= upper_bound => {
+				self.hits += 1;
+				true
+			},
+			Some(entry) => {
+				// hash collision or an entry with a smaller bound: replace it
+				self.misses += 1;
+				*entry = (node.clone(), upper_bound);
+				false
+			},
+			None => {
+				self.misses += 1;
+				while self.entries.len() >= self.capacity {
+					let oldest = self.insertion_order.pop_front().unwrap();
+					self.entries.remove(&oldest);
+				}
+				self.entries.insert(key, (node.clone(), upper_bound));
+				self.insertion_order.push_back(key);
+				false
+			},
+		}
+	}
+}
+
+
+/// The state of a `CarlierSearch`, from which it can be resumed.
+/// With the `serde` feature, it can be serialized.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchSnapshot {
+	/// The due times of the instance
+	pub due_times: Vec<Time>,
+	/// The unexplored subproblems as `(lower_bound, release_times, due_times)`
+	pub open_nodes: Vec<(Time, Vec<Time>, Vec<Time>)>,
+	/// The best schedule found so far
+	pub incumbent: Option<MachineSchedule>,
+	/// The maximum lateness of `incumbent`, or `Time::MAX` if there is none
+	pub best_lateness: Time,
+	/// The number of explored subproblems
+	pub nodes_explored: usize,
+}
+
+
+impl CarlierSearch {
+	/// Starts the search for the given instance without exploring any subproblem yet.
+	pub fn new(ptimes: &[Time], release_times: &[Time], due_times: &[Time]) -> CarlierSearch {
+		let mut search = CarlierSearch{
+			ptimes: Vec::new(),
+			due_times: Vec::new(),
+			strategy: SearchStrategy::BestFirst,
+			tie_break: TieBreak::default(),
+			peak_open_nodes: 0,
+			open_nodes: BinaryHeap::new(),
+			open_stack: Vec::new(),
+			best_lateness: Time::MAX,
+			best_schedule: None,
+			nodes_explored: 0,
+			memo: Memo::default(),
+			scratch: SchrageScratch::default(),
+		};
+		search.reset(ptimes, release_times, due_times);
+		search
+	}
+
+	/// Starts over with another instance, keeping the strategy, the tie-breaking policy,
+	/// the memo capacity and allocated buffers.
+	pub(crate) fn reset(&mut self, ptimes: &[Time], release_times: &[Time], due_times: &[Time]) {
+		self.ptimes.clear();
+		self.ptimes.extend_from_slice(ptimes);
+		self.due_times.clear();
+		self.due_times.extend_from_slice(due_times);
+		self.open_nodes.clear();
+		self.open_stack.clear();
+		self.best_lateness = Time::MAX;
+		self.best_schedule = None;
+		self.nodes_explored = 0;
+		self.memo.entries.clear();
+		self.memo.insertion_order.clear();
+		self.memo.hits = 0;
+		self.memo.misses = 0;
+		if ptimes.is_empty() {
+			self.best_schedule = Some(MachineSchedule{ schedule: vec![] });
+		} else {
+			self.open_nodes.push(Reverse((
+				Time::MIN,
+				CarlierNode{
+					release_times: release_times.to_vec(),
+					due_times: due_times.to_vec(),
+				}
+			)));
+		}
+		self.peak_open_nodes = self.open_nodes.len();
+	}
+
+	/// Sets the order in which the remaining subproblems are explored.
+	/// The search finds an optimal schedule with any strategy.
+	pub fn set_strategy(&mut self, strategy: SearchStrategy) {
+		self.strategy = strategy;
+	}
+
+	/// Sets how the remaining subproblems are scheduled by Schrage's heuristic among jobs with the same due time,
+	/// `TieBreak::EarliestDueLongestProcessing` by default.
+	/// The maximum lateness of the final schedule is optimal with any policy,
+	/// but the policy determines which of the optimal schedules is found.
+	/// The policy is not part of a snapshot.
+	pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+		self.tie_break = tie_break;
+	}
+
+	/// Remembers up to `capacity` explored subproblems, so that identical subproblems
+	/// are skipped instead of being explored again.
+	/// When the memo is full, the oldest subproblem is forgotten.
+	/// A capacity of 0, which is the default, disables the memo and clears it.
+	/// The memo is not part of a snapshot.
+	pub fn set_memo_capacity(&mut self, capacity: usize) {
+		self.memo.capacity = capacity;
+		while self.memo.entries.len() > capacity {
+			let oldest = self.memo.insertion_order.pop_front().unwrap();
+			self.memo.entries.remove(&oldest);
+		}
+	}
+
+	/// Explores at most `max_nodes` further subproblems.
+	/// Subproblems that are pruned by their lower bound without exploring them do not count.
+	///
+	/// # Returns
+	/// Whether the search is finished, i.e. the best schedule is optimal.
+	pub fn step(&mut self, max_nodes: usize) -> bool {
+		let mut explored = 0;
+		while explored < max_nodes {
+			let Some((lower_bound, mut node)) = self.pop_node() else { break; };
+			if lower_bound >= self.best_lateness {
+				continue;
+			}
+			// nodes decided by the dominance rules are not counted
+			let Some(node_bound) = tighten_node(&self.ptimes, &mut node, self.best_lateness) else { continue; };
+			let lower_bound = max(lower_bound, node_bound);
+			if lower_bound >= self.best_lateness {
+				continue;
+			}
+			if self.memo.check_and_insert(&node, self.best_lateness) {
+				continue;
+			}
+			explored += 1;
+			self.nodes_explored += 1;
+			let result = carlier_iteration(
+				&self.ptimes,
+				node.release_times,
+				node.due_times,
+				self.best_lateness,
+				self.tie_break,
+				&mut self.scratch
+			);
+			let lateness = result.schedule.max_lateness(&self.due_times);
+			if lateness < self.best_lateness {
+				self.best_lateness = lateness;
+				self.best_schedule = Some(result.schedule);
+			}
+			if result.lower_bound >= self.best_lateness {
+				continue;
+			}
+			if let Some(children) = result.subproblems {
+				let new_lower_bound = max(result.lower_bound, lower_bound);
+				self.push_nodes(new_lower_bound, children);
+			}
+		}
+		self.is_finished()
+	}
+
+	fn is_depth_first(&self) -> bool {
+		match self.strategy {
+			SearchStrategy::BestFirst => false,
+			SearchStrategy::DepthFirst => true,
+			SearchStrategy::DepthFirstThenBest{ switch_after_nodes } => self.nodes_explored < switch_after_nodes,
+		}
+	}
+
+	fn pop_node(&mut self) -> Option<(Time, CarlierNode)> {
+		if self.is_depth_first() {
+			// nodes in the heap come from `new`, `resume` or an earlier best-first phase
+			self.open_stack.pop().or_else(|| self.open_nodes.pop().map(|Reverse(entry)| entry))
+		} else {
+			self.open_nodes.extend(self.open_stack.drain(..).map(Reverse));
+			self.open_nodes.pop().map(|Reverse(entry)| entry)
+		}
+	}
+
+	fn push_nodes(&mut self, lower_bound: Time, children: [CarlierNode; 2]) {
+		if self.is_depth_first() {
+			// the child with the smaller preemptive bound is pushed last to be explored first
+			let mut children = children.map(|child| {
+				let bound = edd_preemptive(self.ptimes.clone(), &child.release_times, &child.due_times)
+					.max_lateness(&child.due_times);
+				(bound, child)
+			});
+			children.sort_unstable_by_key(|(bound, _)| Reverse(*bound));
+			self.open_stack.extend(children.into_iter().map(|(_, child)| (lower_bound, child)));
+		} else {
+			self.open_nodes.extend(children.into_iter().map(|child| Reverse((lower_bound, child))));
+		}
+		self.peak_open_nodes = max(self.peak_open_nodes, self.open_nodes.len() + self.open_stack.len());
+	}
 
+	/// The lower bounds of all open subproblems
+	fn open_bounds(&self) -> impl Iterator<Item = Time> + '_ {
+		self.open_nodes.iter().map(|Reverse((lower_bound, _))| *lower_bound)
+			.chain(self.open_stack.iter().map(|(lower_bound, _)| *lower_bound))
+	}
+
+	/// Returns whether all subproblems have been explored or pruned.
+	pub fn is_finished(&self) -> bool {
+		self.open_bounds().all(|lower_bound| lower_bound >= self.best_lateness)
+	}
+
+	/// Returns the best schedule found so far, which is optimal once the search is finished.
+	/// Returns None if no subproblem has been explored yet.
+	pub fn best_schedule(&self) -> Option<&MachineSchedule> {
+		self.best_schedule.as_ref()
+	}
+
+	/// Returns a lower bound on the optimal maximum lateness,
+	/// which is the maximum lateness of the best schedule once the search is finished.
+	pub fn lower_bound(&self) -> Time {
+		self.open_bounds()
+			.filter(|&lower_bound| lower_bound < self.best_lateness)
+			.min()
+			.unwrap_or(self.best_lateness)
+	}
+
+	/// Returns the number of subproblems explored so far.
+	pub fn nodes_explored(&self) -> usize {
+		self.nodes_explored
+	}
+
+	/// Returns the number of explored subproblems, the peak number of open subproblems
+	/// and the hit and miss counts of the memo.
+	pub fn stats(&self) -> SearchStats {
+		SearchStats{
+			nodes_explored: self.nodes_explored,
+			peak_open_nodes: self.peak_open_nodes,
+			memo_hits: self.memo.hits,
+			memo_misses: self.memo.misses,
+		}
+	}
+
+	/// Returns the state of the search.
+	pub fn snapshot(&self) -> SearchSnapshot {
+		SearchSnapshot{
+			due_times: self.due_times.clone(),
+			open_nodes: self.open_nodes.iter()
+				.map(|Reverse(entry)| entry)
+				.chain(self.open_stack.iter())
+				.map(|(lower_bound, node)| (*lower_bound, node.release_times.clone(), node.due_times.clone()))
+				.collect(),
+			incumbent: self.best_schedule.clone(),
+			best_lateness: self.best_lateness,
+			nodes_explored: self.nodes_explored,
+		}
+	}
+
+	/// Continues a search from a snapshot.
+	/// The resumed search is best-first unless changed with `set_strategy`.
+	///
+	/// # Arguments
+	/// * `snapshot`: The state of the search
+	/// * `ptimes`: The processing times of the instance, which are not part of the snapshot
+	///
+	/// # Panics
+	/// If the number of processing times does not match the snapshot.
+	pub fn resume(snapshot: SearchSnapshot, ptimes: &[Time]) -> CarlierSearch {
+		assert_eq!(ptimes.len(), snapshot.due_times.len(), "Snapshot belongs to an instance with a different number of jobs");
+		let open_nodes: BinaryHeap<_> = snapshot.open_nodes.into_iter()
+			.map(|(lower_bound, release_times, due_times)| Reverse((lower_bound, CarlierNode{ release_times, due_times })))
+			.collect();
+		CarlierSearch{
+			ptimes: ptimes.to_vec(),
+			due_times: snapshot.due_times,
+			strategy: SearchStrategy::BestFirst,
+			tie_break: TieBreak::default(),
+			peak_open_nodes: open_nodes.len(),
+			open_nodes,
+			open_stack: Vec::new(),
+			scratch: SchrageScratch::default(),
+			best_lateness: snapshot.best_lateness,
+			best_schedule: snapshot.incumbent,
+			nodes_explored: snapshot.nodes_explored,
+			memo: Memo::default(),
+		}
+	}
+}
+
+/// Dominance rules applied to every node of `CarlierSearch` before it is explored,
+/// which only keep schedules with a maximum lateness below `upper_bound`:
+/// * If job j cannot be first, because some other job would be too late after it,
+///   some job precedes j and j cannot start before the earliest completion of any other job.
+/// * If job j cannot be last, because it would be too late even when completing at the earliest makespan,
+///   some job k follows j and j must complete p_k before the due time of k.
+///
+/// Returns the preemptive EDD bound on the maximum lateness of the tightened node,
+/// or None if the node cannot contain a schedule below `upper_bound`.
+fn tighten_node(ptimes: &[Time], node: &mut CarlierNode, upper_bound: Time) -> Option<Time> {
+	let n = ptimes.len();
+	if n >= 2 && upper_bound < Time::MAX {
+		let release_times = &node.release_times;
+		let due_times = &node.due_times;
+		// the best value over all jobs other than j from the two best values over all jobs
+		let best_two = |mut values: Vec<(Time, Job)>| {
+			values.sort_unstable();
+			(values[0], values[1])
+		};
+		let other = |(first, second): ((Time, Job), (Time, Job)), j: Job| if first.1 == j { second.0 } else { first.0 };
+		let earliest_completion = best_two((0..n).map(|k| (release_times[k] + ptimes[k], k)).collect());
+		let smallest_latest_start = best_two((0..n).map(|k| (due_times[k] - ptimes[k], k)).collect());
+		// negated to get the maximum
+		let largest_latest_start = best_two((0..n).map(|k| (ptimes[k] - due_times[k], k)).collect());
+		// the earliest time at which all jobs can be completed
+		let mut by_release: Vec<Job> = (0..n).collect();
+		by_release.sort_unstable_by_key(|&k| release_times[k]);
+		let makespan = by_release.iter().fold(Time::MIN, |t, &k| max(t, release_times[k]) + ptimes[k]);
+		let mut new_release_times = release_times.clone();
+		let mut new_due_times = due_times.clone();
+		for j in 0..n {
+			// with j first, every other job k completes at r_j + p_j + p_k or later
+			if release_times[j] + ptimes[j] - other(smallest_latest_start, j) >= upper_bound {
+				new_release_times[j] = max(release_times[j], other(earliest_completion, j));
+			}
+			if makespan - due_times[j] >= upper_bound {
+				new_due_times[j] = min(due_times[j], -other(largest_latest_start, j));
 			}
 		}
+		node.release_times = new_release_times;
+		node.due_times = new_due_times;
+	}
+	let bound = edd_preemptive(ptimes.to_vec(), &node.release_times, &node.due_times).max_lateness(&node.due_times);
+	if bound >= upper_bound {
+		None
+	} else {
+		Some(bound)
 	}
-	best_schedule.unwrap()
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct CarlierNode {
 	release_times: Vec<Time>,
 	due_times: Vec<Time>,
@@ -133,10 +970,12 @@ fn carlier_iteration(
 	ptimes: &[Time],
 	mut release_times: Vec<Time>,
 	mut due_times: Vec<Time>,
-	upper_bound: Time
+	upper_bound: Time,
+	tie_break: TieBreak,
+	scratch: &mut SchrageScratch
 ) -> CarlierResult
 {
-	let schedule = schrage(ptimes, &release_times, &due_times);
+	let schedule = schrage_with_scratch(ptimes, &release_times, &due_times, tie_break, scratch);
 	let (a, p) = critical_path(&schedule, &due_times);
 	let sched = &schedule.schedule;
 	let pjob = sched[p].job;
@@ -167,7 +1006,8 @@ fn carlier_iteration(
 
 	for i in (a..=c).chain(p+1..sched.len()) {
 		let job = sched[i].job;
-		if ptimes[job] > upper_bound - crit_bound {
+		// upper_bound is Time::MAX as long as no schedule is known
+		if ptimes[job] > upper_bound.saturating_sub(crit_bound) {
 			// this job cannot be scheduled inside the critical set
 
 			if release_times[job] + ptimes[job] + crit_duration 
@@ -223,9 +1063,14 @@ fn carlier_iteration(
 }
 
 
-/// Returns (a, b) such that the critical path is formed 
-/// by schedule[a] up to (including) schedule[b]
-fn critical_path(schedule: &MachineSchedule, due_times: &[Time]) -> (usize, usize) {
+/// Returns the critical path of a non-preemptive schedule whose runs are sorted by start time,
+/// as `(a, p)` such that it is formed by `schedule.schedule[a]` up to (including) `schedule.schedule[p]`:
+/// run p completes the job of maximum lateness (the last one in case of ties),
+/// and runs a to p are processed without idle time, with idle time before run a unless a is 0.
+///
+/// # Panics
+/// If the schedule is empty.
+pub fn critical_path(schedule: &MachineSchedule, due_times: &[Time]) -> (usize, usize) {
 	let schedule = &schedule.schedule;
 	let latenesses = schedule.iter().enumerate().map(
 		|(i, JobRun{ time: t, job, duration: d })|
@@ -247,6 +1092,15 @@ fn critical_path(schedule: &MachineSchedule, due_times: &[Time]) -> (usize, usiz
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::objective::MaxLateness;
+	use crate::reference::brute_force_single_machine;
+	use crate::generators::random_single_machine;
+	use crate::instances::benchmark_200a;
+	use crate::ScheduleStats;
+	use crate::{MultiMachineSchedule, SingleMachineInstance};
+	use crate::reference::for_each_permutation;
+	use crate::testing::{precedence_dag, assert_respects_precedence, assert_valid_schedule};
+	use proptest::prelude::*;
 
 	fn example_1() -> (Vec<Time>, Vec<Time>, Vec<Time>) {
 		(
@@ -270,6 +1124,20 @@ mod tests {
 	}
 
 
+	#[test]
+	fn test_schrage_streaming() {
+		for (p, r, d) in [example_1(), example_2(), benchmark_200a()] {
+			let schedule = schrage(&p, &r, &d);
+			let mut runs = Vec::new();
+			schrage_streaming(&p, &r, &d, |run| runs.push(run));
+			assert_eq!(runs, schedule.schedule);
+			let mut stats = ScheduleStats::new(&d);
+			schrage_streaming(&p, &r, &d, |run| stats.record(run));
+			assert_eq!(stats.max_lateness, Some(schedule.max_lateness(&d)));
+			assert_eq!(stats.makespan, schedule.makespan());
+		}
+	}
+
 	#[test]
 	fn test_critical_path() {
 		let (p, r, d) = example_1();
@@ -309,6 +1177,170 @@ mod tests {
 		assert_eq!(schedule, expected_result);
 	}
 
+	#[test]
+	fn test_carlier_search_resume() {
+		let instance = random_single_machine(50, 50, 2);
+		let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+		let mut fresh = CarlierSearch::new(p, r, d);
+		assert!(fresh.step(usize::MAX));
+		let mut search = CarlierSearch::new(p, r, d);
+		assert!(!search.step(100));
+		assert_eq!(search.nodes_explored(), 100);
+		let incumbent = search.best_schedule().unwrap().max_lateness(d);
+		assert!(search.lower_bound() <= fresh.best_schedule().unwrap().max_lateness(d));
+		assert!(incumbent >= fresh.best_schedule().unwrap().max_lateness(d));
+		let mut resumed = CarlierSearch::resume(search.snapshot(), p);
+		assert!(resumed.step(usize::MAX));
+		assert_eq!(resumed.best_schedule().unwrap().max_lateness(d), fresh.best_schedule().unwrap().max_lateness(d));
+		assert_eq!(resumed.nodes_explored(), fresh.nodes_explored());
+		assert_eq!(resumed.lower_bound(), resumed.best_schedule().unwrap().max_lateness(d));
+	}
+
+	#[test]
+	fn test_carlier_search_node_counts() {
+		// without the dominance rules, these take 30 and 367 nodes
+		let (p, r, d) = benchmark_200a();
+		let mut search = CarlierSearch::new(&p, &r, &d);
+		search.step(usize::MAX);
+		assert!(search.nodes_explored() <= 28);
+		let instance = random_single_machine(50, 10, 12);
+		let mut search = CarlierSearch::new(&instance.ptimes, &instance.release_times, &instance.due_times);
+		search.step(usize::MAX);
+		assert!(search.nodes_explored() <= 25);
+	}
+
+	#[test]
+	fn test_carlier_search_memo() {
+		let instance = random_single_machine(50, 50, 3);
+		let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+		let mut fresh = CarlierSearch::new(p, r, d);
+		fresh.step(usize::MAX);
+		let optimum = fresh.best_schedule().unwrap().max_lateness(d);
+		// a copy of the root node produces a duplicate subproblem
+		let mut search = CarlierSearch::new(p, r, d);
+		let root = search.open_nodes.peek().unwrap().clone();
+		search.open_nodes.push(root);
+		search.set_memo_capacity(1000);
+		assert!(search.step(usize::MAX));
+		let stats = search.stats();
+		assert_eq!(stats.memo_hits, 1);
+		assert_eq!(stats.memo_misses, fresh.nodes_explored());
+		assert_eq!(stats.nodes_explored, fresh.nodes_explored());
+		assert_eq!(search.best_schedule().unwrap().max_lateness(d), optimum);
+		// the copy is popped right after the root, so a single entry suffices
+		let mut search = CarlierSearch::new(p, r, d);
+		let root = search.open_nodes.peek().unwrap().clone();
+		search.open_nodes.push(root);
+		search.set_memo_capacity(1);
+		assert!(search.step(usize::MAX));
+		assert_eq!(search.memo.entries.len(), 1);
+		assert_eq!(search.stats().memo_hits, 1);
+		assert_eq!(search.best_schedule().unwrap().max_lateness(d), optimum);
+	}
+
+	#[test]
+	fn test_carlier_search_strategies() {
+		let strategies = [
+			SearchStrategy::BestFirst,
+			SearchStrategy::DepthFirst,
+			SearchStrategy::DepthFirstThenBest{ switch_after_nodes: 5 },
+		];
+		let mut instances = vec![example_1(), example_2(), example_3(), benchmark_200a()];
+		for seed in [2, 3, 9] {
+			let instance = random_single_machine(50, 50, seed);
+			instances.push((instance.ptimes, instance.release_times, instance.due_times));
+		}
+		for (p, r, d) in instances.iter() {
+			let optimum = carlier(p, r, d).max_lateness(d);
+			for strategy in strategies {
+				let mut search = CarlierSearch::new(p, r, d);
+				search.set_strategy(strategy);
+				assert!(search.step(usize::MAX));
+				assert_eq!(search.best_schedule().unwrap().max_lateness(d), optimum, "{:?}", strategy);
+				assert_eq!(search.lower_bound(), optimum);
+			}
+		}
+		let (p, r, d) = &instances[4];
+		let peak = |strategy| {
+			let mut search = CarlierSearch::new(p, r, d);
+			search.set_strategy(strategy);
+			search.step(usize::MAX);
+			search.stats().peak_open_nodes
+		};
+		// 9 and 399 open nodes
+		assert!(peak(SearchStrategy::DepthFirst) < peak(SearchStrategy::BestFirst));
+	}
+
+	#[test]
+	fn test_grasp_lmax() {
+		for (p, r, d) in [example_1(), example_2(), example_3()] {
+			let optimum = carlier(&p, &r, &d).max_lateness(&d);
+			// the optimum of example 2 keeps the machine idle while a job is released,
+			// which only the local search does, starting from any released job
+			let schedule = grasp_lmax(&p, &r, &d, 20, 1.0, 1);
+			assert_eq!(schedule.max_lateness(&d), optimum);
+			assert_valid_schedule(&schedule, &SingleMachineInstance{ ptimes: p, release_times: r, due_times: d });
+		}
+		let (p, r, d) = benchmark_200a();
+		let schrage_lateness = schrage(&p, &r, &d).max_lateness(&d);
+		let lateness = grasp_lmax(&p, &r, &d, 100, 0.3, 7).max_lateness(&d);
+		assert!(lateness < schrage_lateness, "{} >= {}", lateness, schrage_lateness);
+		assert!(lateness >= 1415);
+		// a single start is Schrage's schedule after the local search
+		assert!(grasp_lmax(&p, &r, &d, 1, 0.3, 7).max_lateness(&d) <= schrage_lateness);
+		assert_eq!(grasp_lmax(&p, &r, &d, 5, 1.0, 3), grasp_lmax(&p, &r, &d, 5, 1.0, 3));
+		assert!(grasp_lmax(&[], &[], &[], 5, 0.5, 0).schedule.is_empty());
+	}
+
+	#[test]
+	fn test_tie_break() {
+		// all due times are equal, so every choice is a tie
+		let (p, r, d) = (vec![3, 1, 2, 2], vec![0, 0, 1, 0], vec![6; 4]);
+		let solve = |tie_break| {
+			let mut search = CarlierSearch::new(&p, &r, &d);
+			search.set_tie_break(tie_break);
+			search.step(usize::MAX);
+			search.best_schedule().unwrap().clone()
+		};
+		let policies: [(TieBreak, Vec<Job>); 4] = [
+			(TieBreak::EarliestDueLongestProcessing, vec![0, 3, 2, 1]),
+			(TieBreak::EarliestDueSmallestIndex, vec![0, 1, 2, 3]),
+			(TieBreak::EarliestRelease, vec![0, 1, 3, 2]),
+			// job 2 first whenever it is released
+			(TieBreak::Custom(|a, b| (a != 2).cmp(&(b != 2)).then(a.cmp(&b))), vec![0, 2, 1, 3]),
+		];
+		for (tie_break, order) in policies.iter().cloned() {
+			let schedule = solve(tie_break);
+			assert_eq!(schedule.job_order(), order, "{:?}", tie_break);
+			assert_eq!(schedule.max_lateness(&d), 2);
+			assert_eq!(schrage_with_tie_break(&p, &r, &d, tie_break), schedule);
+		}
+		assert_eq!(solve(TieBreak::default()), carlier(&p, &r, &d));
+		// the optimal value does not depend on the policy
+		for seed in [4, 5, 6] {
+			let instance = random_single_machine(30, 5, seed);
+			let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+			let optimum = carlier(p, r, d).max_lateness(d);
+			for &(tie_break, _) in policies.iter() {
+				let mut search = CarlierSearch::new(p, r, d);
+				search.set_tie_break(tie_break);
+				search.step(usize::MAX);
+				assert_eq!(search.best_schedule().unwrap().max_lateness(d), optimum);
+			}
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_search_snapshot_serde() {
+		let instance = random_single_machine(50, 10, 12);
+		let mut search = CarlierSearch::new(&instance.ptimes, &instance.release_times, &instance.due_times);
+		search.step(20);
+		let snapshot = search.snapshot();
+		let json = serde_json::to_string(&snapshot).unwrap();
+		assert_eq!(serde_json::from_str::<SearchSnapshot>(&json).unwrap(), snapshot);
+	}
+
 	#[test]
 	fn test_carlier_example_2() {
 		let (p, r, d) = example_2();
@@ -321,6 +1353,34 @@ mod tests {
 		assert_eq!(schedule, expected_result);
 	}
 
+	#[test]
+	fn test_carlier_brute_force() {
+		for (p, r, d) in [example_1(), example_2()] {
+			let (_, optimum) = brute_force_single_machine(&p, &r, &d, &MaxLateness).unwrap();
+			assert_eq!(carlier(&p, &r, &d).max_lateness(&d), optimum);
+		}
+	}
+
+	#[test]
+	fn test_schrage_zero_duration() {
+		//           0  1  2
+		let p = vec![3, 0, 2];
+		let r = vec![0, 1, 0];
+		let d = vec![10, 1, 4];
+		let schedule = schrage(&p, &r, &d);
+		let expected_result = MachineSchedule::from_order_ptimes_releasetimes(
+			vec![2, 1, 0].into_iter(),
+			&p,
+			&r
+		);
+		assert_eq!(schedule, expected_result);
+		assert_eq!(schedule.max_lateness(&d), 1);
+		// waiting for the zero-duration job is optimal
+		let (_, optimum) = brute_force_single_machine(&p, &r, &d, &MaxLateness).unwrap();
+		assert_eq!(optimum, 0);
+		assert_eq!(carlier(&p, &r, &d).max_lateness(&d), 0);
+	}
+
 	fn example_3() -> (Vec<Time>, Vec<Time>, Vec<Time>) {
 		(
 			//    0    1    2    3    4    5    6    7    8    9
@@ -337,6 +1397,144 @@ mod tests {
 		println!("{}", schedule);
 		assert_eq!(schedule.max_lateness(&d), 0);
 	}
+
+	#[test]
+	fn test_setup_teardown() {
+		let p = vec![3, 2];
+		let r = vec![0, 0];
+		// job 0 is processed in [1, 4) and meets its due time only because its teardown does not count
+		let d = vec![4, 9];
+		let options = ScheduleBuildOptions{ setup: 1, teardown: 2 };
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes_with_options(0..2, &p, &r, &options);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 1, job: 0, duration: 3 },
+			JobRun{ time: 7, job: 1, duration: 2 },
+		]);
+		assert_eq!(schedule.max_lateness(&d), 0);
+		assert_eq!(options.makespan(&schedule), 11);
+		assert_eq!(schrage_with_options(&p, &r, &d, &options), schedule);
+		assert_eq!(carlier_with_options(&p, &r, &d, &options).max_lateness(&d), 0);
+		// with the teardown counted, job 0 would be late
+		assert_eq!(schrage(&[6, 5], &r, &d).max_lateness(&d), 2);
+	}
+
+	#[test]
+	fn test_zero_setup_teardown() {
+		let options = ScheduleBuildOptions::default();
+		for seed in 0..10 {
+			let instance = random_single_machine(30, 20, seed);
+			let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+			assert_eq!(schrage_with_options(p, r, d, &options), schrage(p, r, d));
+			assert_eq!(carlier_with_options(p, r, d, &options), carlier(p, r, d));
+			let schedule = MachineSchedule::from_order_ptimes_releasetimes(0..30, p, r);
+			assert_eq!(MachineSchedule::from_order_ptimes_releasetimes_with_options(0..30, p, r, &options), schedule);
+			assert_eq!(options.makespan(&schedule), schedule.makespan());
+		}
+	}
+
+	#[test]
+	fn test_schrage_calendar() {
+		for seed in 0..10 {
+			let instance = random_single_machine(30, 20, seed);
+			let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+			assert_eq!(schrage_calendar(p, r, d, &Calendar::always()), schrage(p, r, d));
+			// working from 0 to 15 every 20 time units, but not from 100 to 140
+			let calendar = Calendar::new(20, &[(0, 15)]).with_exception(100, 140);
+			let schedule = schrage_calendar(p, r, d, &calendar);
+			assert!(schedule.respects_releases(r));
+			let mut processed = vec![0; p.len()];
+			let mut previous_end = Time::MIN;
+			for run in schedule.schedule.iter() {
+				assert!(run.time >= previous_end);
+				assert!(run.duration == 0 || calendar.working_intervals(run.time, run.duration) == vec![(run.time, run.time + run.duration)]);
+				processed[run.job] += run.duration;
+				previous_end = run.time + run.duration;
+			}
+			assert_eq!(&processed, p);
+			let order = schedule.distinct_job_order();
+			assert_eq!(MachineSchedule::from_order_ptimes_releasetimes_calendar(order.into_iter(), p, r, &calendar), schedule);
+		}
+		// a job without processing time released after closing is placed at the next opening
+		let calendar = Calendar::new(10, &[(0, 8)]);
+		let (p, r, d) = (vec![2, 0], vec![0, 9], vec![5, 5]);
+		let schedule = schrage_calendar(&p, &r, &d, &calendar);
+		assert_eq!(schedule.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 10, job: 1, duration: 0 },
+		]);
+		assert_eq!(MachineSchedule::from_order_ptimes_releasetimes_calendar([0, 1].into_iter(), &p, &r, &calendar), schedule);
+	}
+
+	#[test]
+	fn test_objective_of_order() {
+		let (p, r, d) = example_1();
+		let order = vec![5, 0, 1, 3, 2, 6, 4];
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes(order.iter().copied(), &p, &r);
+		assert_eq!(lmax_of_order(&order, &p, &r, &d), schedule.max_lateness(&d));
+		assert_eq!(makespan_of_order(&order, &p, &r), schedule.makespan());
+		let instance = random_single_machine(50, 100, 7);
+		let (p, r, d) = (&instance.ptimes, &instance.release_times, &instance.due_times);
+		let order: Vec<Job> = (0..50).rev().collect();
+		let schedule = MachineSchedule::from_order_ptimes_releasetimes(order.iter().copied(), p, r);
+		assert_eq!(lmax_of_order(&order, p, r, d), schedule.max_lateness(d));
+		assert_eq!(makespan_of_order(&order, p, r), schedule.makespan());
+		assert_eq!(makespan_of_order(&[], p, r), 0);
+	}
+
+	#[test]
+	fn test_schrage_precedence() {
+		let p = vec![3, 2, 4, 1];
+		let r = vec![0, 0, 1, 0];
+		let d = vec![20, 6, 7, 30];
+		// job 3 must precede job 2, so job 3 inherits the due time 7 - 4 = 3 and goes first
+		let predecessor = vec![vec![], vec![], vec![3], vec![]];
+		let schedule = schrage_precedence(&p, &r, &d, &predecessor);
+		assert_eq!(schedule.job_order(), vec![3, 1, 2, 0]);
+		assert_eq!(schedule.max_lateness(&d), 0);
+		assert_eq!(schrage(&p, &r, &d).job_order(), vec![1, 2, 0, 3]);
+		assert_eq!(schrage_precedence(&p, &r, &d, &[vec![], vec![], vec![], vec![]]).job_order(), vec![1, 2, 0, 3]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_schrage_precedence_cycle() {
+		schrage_precedence(&[1, 1], &[0, 0], &[0, 0], &[vec![1], vec![0]]);
+	}
+
+	proptest! {
+		#[test]
+		fn test_schrage_precedence_valid(
+			(predecessor, p, r, d) in precedence_dag(1..=7, 0.3).prop_flat_map(|predecessor| {
+				let n = predecessor.len();
+				(
+					Just(predecessor),
+					proptest::collection::vec(0..10 as Time, n),
+					proptest::collection::vec(0..20 as Time, n),
+					proptest::collection::vec(0..40 as Time, n),
+				)
+			})
+		) {
+			let schedule = schrage_precedence(&p, &r, &d, &predecessor);
+			assert_valid_schedule(&schedule, &SingleMachineInstance{ ptimes: p.clone(), release_times: r, due_times: d.clone() });
+			let lmax = schedule.max_lateness(&d);
+			assert_respects_precedence(&MultiMachineSchedule{ machine_schedules: vec![schedule] }, &predecessor);
+			// without release times, the modified due times make the heuristic optimal
+			let zero = vec![0; p.len()];
+			let schedule = schrage_precedence(&p, &zero, &d, &predecessor);
+			let mut optimum = Time::MAX;
+			for_each_permutation(p.len(), |order| {
+				let schedule = MachineSchedule::from_order_ptimes_releasetimes(order.iter().copied(), &p, &zero);
+				let respects = order.iter().enumerate().all(|(i, &job)| {
+					predecessor[job].iter().all(|pred| order[..i].contains(pred))
+				});
+				if respects {
+					optimum = optimum.min(schedule.max_lateness(&d));
+				}
+			});
+			prop_assert_eq!(schedule.max_lateness(&d), optimum);
+			prop_assert!(lmax >= optimum);
+		}
+	}
 }
  
  
@@ -1,4 +1,6 @@
-use crate::{Time, Job, MachineSchedule, JobRun};
+use crate::{Time, Job, MachineSchedule, JobRun, EventClock};
+use std::cmp::max;
+use crate::schedule::assert_valid_ptimes;
 use std::collections::BinaryHeap;
 
 
@@ -13,36 +15,32 @@ use std::collections::BinaryHeap;
 /// * `release_times`: The release times of the jobs
 /// * `due_times`: due times of the jobs
 ///
+/// # Panics
+/// If a processing time is negative.
+///
 pub fn edd_preemptive(
 	mut ptimes: Vec<Time>,
 	release_times: &[Time],
 	due_times: &[Time]
 ) -> MachineSchedule
 {
-	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
-	// sort by descending release time
-	// because we want to pop the jobs with lowest release time first
-	jobs.sort_unstable_by_key(|&job| -release_times[job]);
+	assert_valid_ptimes(&ptimes);
+	let mut clock = EventClock::with_releases(0, release_times);
 	// A list of jobs that in a current moment are ready to run,
 	// sorted by "earliest due time first",
 	let mut ready_to_run = BinaryHeap::new();
-	// Time tracking variable
-	let mut t: Time = 0;
 	// The final schedule
 	let mut schedule: Vec<JobRun> = Vec::new();
-	// Iterate over jobs in order of release time
-	while !jobs.is_empty() || !ready_to_run.is_empty() {
+	loop {
 		// Find all jobs that are available
-		while !jobs.is_empty()
-			&& release_times[*jobs.last().unwrap()] <= t
-		{
-			let job = jobs.pop().unwrap();
+		while let Some(job) = clock.pop_release() {
 			// the first tuple entry is just to determine the order
-			ready_to_run.push((	-due_times[job], job ));
+			ready_to_run.push(( -due_times[job], job ));
 		}
 		// If there are jobs that are ready to run schedule them
 		match ready_to_run.pop() {
 			Some((_, job)) => {
+				let t = clock.time();
 				// If that job is alread scheduled, just extend its duration
 				if !schedule.is_empty() && schedule.last().unwrap().job == job {
 					schedule.last_mut().unwrap().duration += ptimes[job];
@@ -53,34 +51,233 @@ pub fn edd_preemptive(
 						duration: ptimes[job]
 					});
 				}
-				t += ptimes[job];
-				// check if a new job arrives before this one is done
-				if !jobs.is_empty() {
-					let next_delivery = release_times[*jobs.last().unwrap()];
-					if next_delivery < t {
+				let end = t + ptimes[job];
+				match clock.next_event_time() {
+					// a new job arrives before this one is done
+					Some(next_delivery) if next_delivery < end => {
 						// add this job back to the heap with the remaining processing time:
-						ptimes[job] = t - next_delivery;
+						ptimes[job] = end - next_delivery;
 						ready_to_run.push(( -due_times[job], job ));
 						// shorten duration of the scheduled run accordingly:
 						schedule.last_mut().unwrap().duration -= ptimes[job];
-						t = next_delivery;
-					}
+						clock.advance_to(next_delivery);
+					},
+					_ => clock.advance_to(end),
 				}
 			},
-			None => {
-				// If there aren't any jobs that can be run,
-				// skip to when the nearest job is available
-				// Note that ready_to_run cannot be empty at this point.
-				t = release_times[*jobs.last().unwrap()];
+			// If there aren't any jobs that can be run,
+			// skip to when the nearest job is available
+			None => match clock.next_event_time() {
+				Some(next_delivery) => clock.advance_to(next_delivery),
+				None => break,
+			},
+		};
+	}
+	MachineSchedule{ schedule }
+}
+
+
+/// EDD scheduler with preemptions that cost time.
+/// Heuristic for 1|pmtn,r_j|L_max where resuming a preempted job takes an additional `preemption_cost`.
+/// The cost is modeled by lengthening the run that resumes the job,
+/// i.e. the total duration of a job's runs is its processing time plus `preemption_cost` per resumption.
+///
+/// Whenever a job is released while another job is running and has an earlier due date
+/// (the same priority as in `edd_preemptive`), the running job is only preempted if,
+/// considering just these two jobs, preempting does not increase their maximum lateness despite the cost.
+/// Jobs that have been preempted `max_preemptions` times are no longer preempted.
+/// With `preemption_cost` 0 and no limit on preemptions, the result is identical to `edd_preemptive`.
+/// Runs in O(n log n) time for n jobs.
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `due_times`: due times of the jobs
+/// * `preemption_cost`: The time needed to resume a preempted job
+/// * `max_preemptions`: The maximum number of times a job may be preempted, or None for no limit
+///
+/// # Panics
+/// If a processing time or the preemption cost is negative.
+///
+pub fn edd_preemptive_with_cost(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	preemption_cost: Time,
+	max_preemptions: Option<usize>
+) -> MachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	assert!(preemption_cost >= 0, "Preemption cost must not be negative");
+	let mut remaining = ptimes.to_vec();
+	let mut preemptions = vec![0; ptimes.len()];
+	let mut jobs: Vec<Job> = (0..ptimes.len()).collect();
+	// sort by descending release time
+	// because we want to pop the jobs with lowest release time first
+	jobs.sort_unstable_by_key(|&job| -release_times[job]);
+	// jobs that are ready to run, with the same priority as in edd_preemptive
+	let mut ready_to_run = BinaryHeap::new();
+	let mut t: Time = 0;
+	let mut running: Option<Job> = None;
+	let mut schedule: Vec<JobRun> = Vec::new();
+	while !jobs.is_empty() || !ready_to_run.is_empty() || running.is_some() {
+		// Find all jobs that are available
+		while !jobs.is_empty()
+			&& release_times[*jobs.last().unwrap()] <= t
+		{
+			let job = jobs.pop().unwrap();
+			ready_to_run.push(( -due_times[job], job ));
+		}
+		let job = match running {
+			Some(job) => job,
+			None => match ready_to_run.pop() {
+				Some((_, job)) => {
+					if preemptions[job] > 0 {
+						// resuming a preempted job
+						remaining[job] += preemption_cost;
+					}
+					job
+				},
+				None => {
+					// If there aren't any jobs that can be run,
+					// skip to when the nearest job is available
+					t = release_times[*jobs.last().unwrap()];
+					continue;
+				}
 			}
 		};
+		// run the job until it completes or the next job is released
+		let end = match jobs.last() {
+			Some(&next) => t + remaining[job].min(max(release_times[next] - t, 0)),
+			None => t + remaining[job],
+		};
+		match schedule.last_mut() {
+			Some(last) if last.job == job && last.time + last.duration == t => {
+				last.duration += end - t;
+			},
+			_ => schedule.push(JobRun{ time: t, job, duration: end - t }),
+		}
+		remaining[job] -= end - t;
+		t = end;
+		running = None;
+		if remaining[job] == 0 {
+			continue;
+		}
+		// a job is released before this one is done
+		while !jobs.is_empty()
+			&& release_times[*jobs.last().unwrap()] <= t
+		{
+			let job = jobs.pop().unwrap();
+			ready_to_run.push(( -due_times[job], job ));
+		}
+		let preempt = match ready_to_run.peek() {
+			Some(&(neg_due, other)) if (neg_due, other) > (-due_times[job], job) => {
+				max_preemptions.is_none_or(|limit| preemptions[job] < limit)
+				&& preemption_pays_off(
+					t,
+					remaining[job],
+					due_times[job],
+					remaining[other] + if preemptions[other] > 0 { preemption_cost } else { 0 },
+					due_times[other],
+					preemption_cost
+				)
+			},
+			_ => false,
+		};
+		if preempt {
+			preemptions[job] += 1;
+			ready_to_run.push(( -due_times[job], job ));
+		} else {
+			running = Some(job);
+		}
 	}
 	MachineSchedule{ schedule }
 }
 
+/// Decides whether preempting the running job for another job at time `t`
+/// does not increase the maximum lateness of these two jobs.
+fn preemption_pays_off(
+	t: Time,
+	remaining: Time,
+	due_time: Time,
+	other_remaining: Time,
+	other_due_time: Time,
+	preemption_cost: Time
+) -> bool
+{
+	let continue_lateness = max(
+		t + remaining - due_time,
+		t + remaining + other_remaining - other_due_time
+	);
+	let preempt_lateness = max(
+		t + other_remaining - other_due_time,
+		t + other_remaining + remaining + preemption_cost - due_time
+	);
+	preempt_lateness <= continue_lateness
+}
+
+
+/// Earliest-Deadline-First feasibility test for 1|pmtn,r_j| with deadlines.
+/// Decides whether there is a preemptive schedule in which no job completes after its deadline.
+/// Since the EDF schedule is optimal for 1|pmtn,r_j|L_max, such a schedule exists
+/// if and only if the EDF schedule meets all deadlines.
+/// Runs in O(n log n) time for n jobs.
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `deadlines`: The deadlines of the jobs
+///
+/// # Returns
+/// `Ok(())` if all deadlines can be met,
+/// otherwise `Err(job)` where `job` is the first job to miss its deadline in the EDF schedule.
+pub fn edf_feasible(
+	ptimes: &[Time],
+	release_times: &[Time],
+	deadlines: &[Time]
+) -> Result<(), Job>
+{
+	let schedule = edd_preemptive(ptimes.to_vec(), release_times, deadlines);
+	match schedule.completion_times().into_iter().find(|&(job, c)| c > deadlines[job]) {
+		Some((job, _)) => Err(job),
+		None => Ok(()),
+	}
+}
+
+/// Decides whether the maximum lateness can be at most `target` when preemption is allowed,
+/// by checking the deadlines `d_j + target` with `edf_feasible`.
+/// The answer is monotone in `target`, so a binary search over integer targets yields the optimum of 1|pmtn,r_j|L_max.
+/// Without preemption, this is only a relaxation: if it returns false, no non-preemptive schedule reaches `target`
+/// either, but if it returns true, the optimum of 1|r_j|L_max (see `carlier`) may still be larger.
+/// Runs in O(n log n) time for n jobs.
+///
+/// # Arguments
+///
+/// * `ptimes`: The processing times of the jobs
+/// * `release_times`: The release times of the jobs
+/// * `due_times`: The due times of the jobs
+/// * `target`: The maximum lateness to reach
+///
+/// # Panics
+/// If a processing time is negative.
+pub fn lmax_feasible(
+	ptimes: &[Time],
+	release_times: &[Time],
+	due_times: &[Time],
+	target: Time
+) -> bool
+{
+	let deadlines: Vec<Time> = due_times.iter().map(|&d| d.saturating_add(target)).collect();
+	edf_feasible(ptimes, release_times, &deadlines).is_ok()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::SingleMachineInstance;
+	use proptest::prelude::*;
 
 	fn example_1() -> (Vec<Time>, Vec<Time>, Vec<Time>) {
 		(
@@ -109,4 +306,130 @@ mod tests {
 		let result = edd_preemptive(p, &r, &d);
 		assert_eq!(result, expected_result);
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_edd_preemptive_zero_duration() {
+		//           0  1
+		let p = vec![4, 0];
+		let r = vec![0, 2];
+		let d = vec![10, 2];
+		let mut result = edd_preemptive(p, &r, &d);
+		// job 1 preempts job 0 for an instant
+		assert_eq!(result.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 0 },
+			JobRun{ time: 2, job: 0, duration: 2 },
+		]);
+		assert_eq!(result.max_lateness(&d), 0);
+		result.normalize(true);
+		assert_eq!(result.schedule, vec![JobRun{ time: 0, job: 0, duration: 4 }]);
+	}
+
+	#[test]
+	fn test_edd_preemptive_with_cost_0() {
+		let (p, r, d) = example_1();
+		let result = edd_preemptive_with_cost(&p, &r, &d, 0, None);
+		assert_eq!(result, edd_preemptive(p, &r, &d));
+	}
+
+	proptest! {
+		#[test]
+		fn test_edd_preemptive_with_cost_0_random(instance in any::<SingleMachineInstance>()) {
+			let SingleMachineInstance{ ptimes, release_times, due_times } = instance;
+			prop_assert_eq!(
+				edd_preemptive_with_cost(&ptimes, &release_times, &due_times, 0, None),
+				edd_preemptive(ptimes, &release_times, &due_times)
+			);
+		}
+	}
+
+	#[test]
+	fn test_edd_preemptive_with_cost_avoids_split() {
+		//           0  1
+		let p = vec![5, 1];
+		let r = vec![0, 2];
+		let d = vec![10, 8];
+		// preempting job 0 at time 2 would complete it at 9 (lateness -1)
+		let result = edd_preemptive_with_cost(&p, &r, &d, 3, None);
+		assert_eq!(result.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 5 },
+			JobRun{ time: 5, job: 1, duration: 1 },
+		]);
+		assert_eq!(result.max_lateness(&d), -2);
+	}
+
+	#[test]
+	fn test_edd_preemptive_with_cost_splits() {
+		//           0  1
+		let p = vec![5, 1];
+		let r = vec![0, 2];
+		let d = vec![10, 3];
+		let result = edd_preemptive_with_cost(&p, &r, &d, 1, None);
+		assert_eq!(result.schedule, vec![
+			JobRun{ time: 0, job: 0, duration: 2 },
+			JobRun{ time: 2, job: 1, duration: 1 },
+			// remaining 3 time units plus the preemption cost
+			JobRun{ time: 3, job: 0, duration: 4 },
+		]);
+		assert_eq!(result.max_lateness(&d), 0);
+	}
+
+	#[test]
+	fn test_edd_preemptive_with_cost_no_preemptions() {
+		let (p, r, d) = example_1();
+		let mut result = edd_preemptive_with_cost(&p, &r, &d, 0, Some(0));
+		result.normalize(false);
+		// every job is processed in a single run
+		assert_eq!(result.schedule.len(), p.len());
+		assert!(result.schedule.iter().all(|run| run.duration == p[run.job]));
+	}
+
+	#[test]
+	fn test_edf_feasible_1() {
+		let (p, r, mut d) = example_1();
+		assert_eq!(edf_feasible(&p, &r, &d), Ok(()));
+		// job 2 cannot be completed any earlier
+		d[2] -= 1;
+		assert_eq!(edf_feasible(&p, &r, &d), Err(2));
+	}
+
+	#[test]
+	fn test_edf_feasible_reports_first_miss() {
+		//              0   1   2
+		let p = vec![   4,  4,  4];
+		let r = vec![   0,  0,  0];
+		let d = vec![  12,  3, 12];
+		// job 1 is scheduled first but still misses its deadline
+		assert_eq!(edf_feasible(&p, &r, &d), Err(1));
+	}
+
+	#[test]
+	fn test_lmax_feasible() {
+		let (p, r, d) = example_1();
+		let optimum = edd_preemptive(p.clone(), &r, &d).max_lateness(&d);
+		assert!(lmax_feasible(&p, &r, &d, optimum));
+		assert!(!lmax_feasible(&p, &r, &d, optimum - 1));
+		assert!(lmax_feasible(&p, &r, &d, Time::MAX));
+	}
+
+	proptest! {
+		#[test]
+		fn test_lmax_feasible_binary_search(instance in any::<SingleMachineInstance>()) {
+			let SingleMachineInstance{ ptimes: p, release_times: r, due_times: d } = instance;
+			if p.is_empty() {
+				return Ok(());
+			}
+			// the lateness of the schedule that processes the jobs by release time is an upper bound
+			let (mut low, mut high) = (Time::MIN / 2, MachineSchedule::from_ptimes_releasetimes(&p, &r).max_lateness(&d));
+			while low < high {
+				let mid = low + (high - low).div_euclid(2);
+				if lmax_feasible(&p, &r, &d, mid) {
+					high = mid;
+				} else {
+					low = mid + 1;
+				}
+			}
+			prop_assert_eq!(high, edd_preemptive(p.clone(), &r, &d).max_lateness(&d));
+		}
+	}
+}
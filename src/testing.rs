@@ -0,0 +1,456 @@
+use std::ops::RangeInclusive;
+
+use proptest::prelude::*;
+use proptest::{bool, collection};
+
+use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, SingleMachineInstance, JobShopInstance};
+use crate::single_machine::{schrage, carlier, edd_preemptive};
+
+
+/// Parameters for generating random single machine instances.
+#[derive(Debug, Clone)]
+pub struct SingleMachineParams {
+	/// Range for the number of jobs
+	pub num_jobs: RangeInclusive<usize>,
+	/// Minimum processing time of a job.
+	/// Processing times of 0 are allowed, such jobs complete instantly at their start time.
+	pub min_ptime: Time,
+	/// Maximum processing time of a job
+	pub max_ptime: Time,
+	/// Range for the release times
+	pub release_times: RangeInclusive<Time>,
+	/// Range for the slack of the due times.
+	/// Job j gets the due time r_j + p_j + s_j, where s_j is drawn from this range.
+	/// Smaller values yield tighter due times, negative values make jobs late in any schedule.
+	pub due_slack: RangeInclusive<Time>,
+}
+
+impl Default for SingleMachineParams {
+	fn default() -> Self {
+		SingleMachineParams {
+			num_jobs: 1..=8,
+			min_ptime: 0,
+			max_ptime: 20,
+			release_times: -20..=60,
+			due_slack: -10..=40,
+		}
+	}
+}
+
+impl Arbitrary for SingleMachineInstance {
+	type Parameters = SingleMachineParams;
+	type Strategy = BoxedStrategy<SingleMachineInstance>;
+
+	fn arbitrary_with(params: SingleMachineParams) -> Self::Strategy {
+		let SingleMachineParams{ num_jobs, min_ptime, max_ptime, release_times, due_slack } = params;
+		collection::vec((min_ptime..=max_ptime, release_times, due_slack), num_jobs)
+			.prop_map(|jobs| SingleMachineInstance {
+				ptimes: jobs.iter().map(|&(p, _, _)| p).collect(),
+				release_times: jobs.iter().map(|&(_, r, _)| r).collect(),
+				due_times: jobs.iter().map(|&(p, r, s)| r + p + s).collect(),
+			})
+			.boxed()
+	}
+}
+
+
+/// Strategy generating processing time matrices for flow shops,
+/// where `ptimes[i][j]` is the time needed by machine i for job j.
+///
+/// # Arguments
+/// * `num_machines`: Range for the number of machines
+/// * `num_jobs`: Range for the number of jobs
+/// * `max_ptime`: Maximum processing time of an operation (processing times are at least 1)
+pub fn flow_shop_ptimes(
+	num_machines: RangeInclusive<usize>,
+	num_jobs: RangeInclusive<usize>,
+	max_ptime: Time
+) -> impl Strategy<Value = Vec<Vec<Time>>>
+{
+	(num_machines, num_jobs).prop_flat_map(move |(m, n)| {
+		collection::vec(collection::vec(1..=max_ptime, n), m)
+	})
+}
+
+
+/// Strategy generating acyclic precedence constraints.
+/// The result `predecessor` lists in `predecessor[i]` the jobs that need to be completed before job `i`,
+/// as expected by `unrelated_machines::serial_schedule_heuristic`.
+///
+/// # Arguments
+/// * `num_jobs`: Range for the number of jobs
+/// * `edge_probability`: Probability with which each possible precedence relation is present
+pub fn precedence_dag(
+	num_jobs: RangeInclusive<usize>,
+	edge_probability: f64
+) -> impl Strategy<Value = Vec<Vec<Job>>>
+{
+	num_jobs.prop_flat_map(move |n| {
+		(
+			collection::vec(bool::weighted(edge_probability), n*n),
+			Just((0..n).collect::<Vec<Job>>()).prop_shuffle(),
+		)
+	}).prop_map(|(edges, labels)| {
+		// edges only point from earlier to later positions in `labels`,
+		// hence `labels` is a topological order of the resulting graph
+		let n = labels.len();
+		let mut predecessor = vec![Vec::new(); n];
+		for i in 0..n {
+			for j in 0..i {
+				if edges[i*n + j] {
+					predecessor[labels[i]].push(labels[j]);
+				}
+			}
+		}
+		predecessor
+	})
+}
+
+
+/// Panics if the given schedule is not valid for the given instance.
+/// A schedule is valid if its runs are sorted by time and do not overlap,
+/// no run starts before the release time of its job,
+/// and every job is processed for exactly its processing time.
+/// Jobs may be preempted.
+pub fn assert_valid_schedule(schedule: &MachineSchedule, instance: &SingleMachineInstance) {
+	let n = instance.num_jobs();
+	let mut processed = vec![0; n];
+	let mut previous_end = Time::MIN;
+	for run in schedule.schedule.iter() {
+		assert!(run.job < n, "Schedule contains unknown job #{}", run.job);
+		assert!(run.duration >= 0, "Job #{} has negative duration {}", run.job, run.duration);
+		assert!(run.time >= previous_end,
+			"Job #{} starts at {} before the previous run ends at {}", run.job, run.time, previous_end
+		);
+		assert!(run.time >= instance.release_times[run.job],
+			"Job #{} starts at {} before its release time {}",
+			run.job, run.time, instance.release_times[run.job]
+		);
+		processed[run.job] += run.duration;
+		previous_end = run.time + run.duration;
+	}
+	for (job, (&done, &p)) in processed.iter().zip(instance.ptimes.iter()).enumerate() {
+		assert_eq!(done, p, "Job #{} is processed for {} instead of {} time units", job, done, p);
+	}
+}
+
+
+/// Panics if the given schedule on parallel machines is not valid:
+/// every job has to be processed exactly once, on any machine, for its full processing time,
+/// and the runs of each machine have to be sorted by time and must not overlap.
+///
+/// # Arguments
+/// * `schedule`: The schedule to check
+/// * `ptimes`: `ptimes[i]` is the processing time of job `i` on any machine
+pub fn assert_valid_parallel_schedule(schedule: &MultiMachineSchedule, ptimes: &[Time]) {
+	let n = ptimes.len();
+	let mut scheduled = vec![false; n];
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		let mut previous_end = Time::MIN;
+		for run in s.schedule.iter() {
+			assert!(run.job < n, "Schedule contains unknown job #{}", run.job);
+			assert!(!scheduled[run.job], "Job #{} is scheduled more than once", run.job);
+			assert_eq!(run.duration, ptimes[run.job], "Job #{} has the wrong duration", run.job);
+			assert!(run.time >= previous_end,
+				"Job #{} starts at {} on machine {} before the previous run ends at {}",
+				run.job, run.time, machine, previous_end
+			);
+			scheduled[run.job] = true;
+			previous_end = run.time + run.duration;
+		}
+	}
+	if let Some(job) = scheduled.iter().position(|&s| !s) {
+		panic!("Job #{} is not scheduled", job);
+	}
+}
+
+
+/// Panics if the given flow shop schedule is not valid:
+/// every machine has to process every job exactly once for its processing time on that machine
+/// without overlapping runs, and no job may start on a machine before it is completed on the previous machine.
+///
+/// # Arguments
+/// * `schedule`: The schedule to check
+/// * `ptimes`: `ptimes[i][j]` is the time needed by machine i for job j
+pub fn assert_valid_flow_shop_schedule(schedule: &MultiMachineSchedule, ptimes: &[Vec<Time>]) {
+	assert_eq!(schedule.machine_schedules.len(), ptimes.len(), "Schedule has the wrong number of machines");
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let mut ready_times = vec![Time::MIN; n];
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		let mut completion: Vec<Option<Time>> = vec![None; n];
+		let mut previous_end = Time::MIN;
+		for run in s.schedule.iter() {
+			assert!(run.job < n, "Schedule contains unknown job #{}", run.job);
+			assert!(completion[run.job].is_none(), "Job #{} is scheduled more than once on machine {}", run.job, machine);
+			assert_eq!(run.duration, ptimes[machine][run.job], "Job #{} has the wrong duration on machine {}", run.job, machine);
+			assert!(run.time >= previous_end,
+				"Job #{} starts at {} on machine {} before the previous run ends at {}",
+				run.job, run.time, machine, previous_end
+			);
+			assert!(run.time >= ready_times[run.job],
+				"Job #{} starts at {} on machine {} before it is completed on the previous machine at {}",
+				run.job, run.time, machine, ready_times[run.job]
+			);
+			completion[run.job] = Some(run.time + run.duration);
+			previous_end = run.time + run.duration;
+		}
+		for (job, c) in completion.into_iter().enumerate() {
+			ready_times[job] = c.unwrap_or_else(|| panic!("Job #{} is not scheduled on machine {}", job, machine));
+		}
+	}
+}
+
+
+/// Panics if the given job shop schedule is not valid:
+/// every job has to be processed exactly once by each machine of its route, for the operation's processing time,
+/// and not before its previous operation is completed.
+/// The runs of each machine have to be sorted by time and must not overlap, and no machine may process other jobs.
+///
+/// # Arguments
+/// * `schedule`: The schedule to check
+/// * `instance`: The job shop instance
+pub fn assert_valid_job_shop_schedule(schedule: &MultiMachineSchedule, instance: &JobShopInstance) {
+	let n = instance.num_jobs();
+	let mut runs = vec![vec![None; schedule.machine_schedules.len()]; n];
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		let mut previous_end = Time::MIN;
+		for run in s.schedule.iter() {
+			assert!(run.job < n, "Schedule contains unknown job #{}", run.job);
+			assert!(runs[run.job][machine].is_none(), "Job #{} is scheduled more than once on machine {}", run.job, machine);
+			assert!(run.time >= previous_end,
+				"Job #{} starts at {} on machine {} before the previous run ends at {}",
+				run.job, run.time, machine, previous_end
+			);
+			runs[run.job][machine] = Some(*run);
+			previous_end = run.time + run.duration;
+		}
+	}
+	for (job, route) in instance.routes.iter().enumerate() {
+		let mut ready_time = Time::MIN;
+		for &(machine, ptime) in route.iter() {
+			let run = runs[job].get_mut(machine).and_then(|r| r.take())
+				.unwrap_or_else(|| panic!("Job #{} is not scheduled on machine {}", job, machine));
+			assert_eq!(run.duration, ptime, "Job #{} has the wrong duration on machine {}", job, machine);
+			assert!(run.time >= ready_time,
+				"Job #{} starts at {} on machine {} before its previous operation is completed at {}",
+				job, run.time, machine, ready_time
+			);
+			ready_time = run.time + run.duration;
+		}
+		if let Some(machine) = runs[job].iter().position(|r| r.is_some()) {
+			panic!("Job #{} is scheduled on machine {}, which is not on its route", job, machine);
+		}
+	}
+}
+
+
+/// Panics if the given order is not a permutation of the jobs `0..num_jobs`.
+pub fn assert_permutation(order: &[Job], num_jobs: usize) {
+	let mut sorted = order.to_vec();
+	sorted.sort_unstable();
+	assert_eq!(sorted, (0..num_jobs).collect::<Vec<Job>>(), "{:?} is not a permutation of the jobs", order);
+}
+
+
+/// Panics if the given multi-machine schedule does not process every job exactly once,
+/// has overlapping runs on some machine, or starts a job before all of its predecessors are completed.
+///
+/// # Arguments
+/// * `schedule`: The schedule to check
+/// * `predecessor`: `predecessor[i]` are the jobs that need to be completed before job `i` can be started
+pub fn assert_respects_precedence(schedule: &MultiMachineSchedule, predecessor: &[Vec<Job>]) {
+	let n = predecessor.len();
+	let mut start_times = vec![None; n];
+	let mut completion_times = vec![0; n];
+	for (machine, s) in schedule.machine_schedules.iter().enumerate() {
+		let mut previous_end = Time::MIN;
+		for run in s.schedule.iter() {
+			assert!(run.job < n, "Schedule contains unknown job #{}", run.job);
+			assert!(start_times[run.job].is_none(), "Job #{} is scheduled more than once", run.job);
+			assert!(run.time >= previous_end,
+				"Job #{} starts at {} on machine {} before the previous run ends at {}",
+				run.job, run.time, machine, previous_end
+			);
+			start_times[run.job] = Some(run.time);
+			completion_times[run.job] = run.time + run.duration;
+			previous_end = run.time + run.duration;
+		}
+	}
+	for (job, preds) in predecessor.iter().enumerate() {
+		let start = start_times[job].unwrap_or_else(|| panic!("Job #{} is not scheduled", job));
+		for &pred in preds {
+			assert!(start >= completion_times[pred],
+				"Job #{} starts at {} before its predecessor #{} is completed at {}",
+				job, start, pred, completion_times[pred]
+			);
+		}
+	}
+}
+
+
+/// Panics if `carlier` yields a larger maximum lateness than `schrage` on the given non-empty instance.
+pub fn assert_carlier_dominates_schrage(instance: &SingleMachineInstance) {
+	let SingleMachineInstance{ ptimes, release_times, due_times } = instance;
+	let heuristic = schrage(ptimes, release_times, due_times).max_lateness(due_times);
+	let exact = carlier(ptimes, release_times, due_times).max_lateness(due_times);
+	assert!(exact <= heuristic,
+		"carlier has maximum lateness {} but schrage achieves {}", exact, heuristic
+	);
+}
+
+
+/// Panics if the optimal preemptive schedule computed by `edd_preemptive`
+/// has a larger maximum lateness than the non-preemptive schedule of `carlier` on the given non-empty instance.
+pub fn assert_preemption_dominates_carlier(instance: &SingleMachineInstance) {
+	let SingleMachineInstance{ ptimes, release_times, due_times } = instance;
+	let preemptive = edd_preemptive(ptimes.clone(), release_times, due_times).max_lateness(due_times);
+	let exact = carlier(ptimes, release_times, due_times).max_lateness(due_times);
+	assert!(preemptive <= exact,
+		"edd_preemptive has maximum lateness {} but carlier achieves {}", preemptive, exact
+	);
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::single_machine::{schedule_hodgson, srpt};
+	use crate::flow_shop::{johnson, dannenbring, neh, total_completion_heuristic, solve_johnson, solve_dannenbring};
+	use crate::identical_machines::{edd_parallel, first_fit_decreasing};
+	use crate::unrelated_machines::serial_schedule_heuristic;
+	use crate::objective::{Objective, MaxLateness, NumTardy};
+	use crate::reference::{brute_force_single_machine, brute_force_parallel, brute_force_permutation_flowshop, brute_force_flow_shop_2};
+
+	fn small_instance() -> impl Strategy<Value = SingleMachineInstance> {
+		SingleMachineInstance::arbitrary_with(SingleMachineParams{
+			num_jobs: 1..=6,
+			..SingleMachineParams::default()
+		})
+	}
+
+	proptest! {
+		#[test]
+		fn test_schrage_valid(instance in any::<SingleMachineInstance>()) {
+			let schedule = schrage(&instance.ptimes, &instance.release_times, &instance.due_times);
+			assert_valid_schedule(&schedule, &instance);
+		}
+
+		#[test]
+		fn test_carlier_valid(instance in any::<SingleMachineInstance>()) {
+			let schedule = carlier(&instance.ptimes, &instance.release_times, &instance.due_times);
+			assert_valid_schedule(&schedule, &instance);
+		}
+
+		#[test]
+		fn test_edd_preemptive_valid(instance in any::<SingleMachineInstance>()) {
+			let schedule = edd_preemptive(
+				instance.ptimes.clone(),
+				&instance.release_times,
+				&instance.due_times
+			);
+			assert_valid_schedule(&schedule, &instance);
+		}
+
+		#[test]
+		fn test_srpt_valid(instance in any::<SingleMachineInstance>()) {
+			let schedule = srpt(&instance.ptimes, &instance.release_times);
+			assert_valid_schedule(&schedule, &instance);
+		}
+
+		#[test]
+		fn test_hodgson_optimal(mut instance in small_instance()) {
+			instance.release_times = vec![0; instance.num_jobs()];
+			let SingleMachineInstance{ ptimes, release_times, due_times } = &instance;
+			let (_, optimum) = brute_force_single_machine(ptimes, release_times, due_times, &NumTardy).unwrap();
+			let schedule = schedule_hodgson(ptimes, due_times);
+			prop_assert_eq!(NumTardy.evaluate_schedule(&schedule, due_times), optimum);
+		}
+
+		#[test]
+		fn test_edd_parallel_valid(instance in small_instance(), num_machines in 1..=3usize) {
+			let SingleMachineInstance{ ptimes, due_times, .. } = &instance;
+			let schedule = edd_parallel(ptimes, due_times, num_machines);
+			assert_valid_parallel_schedule(&schedule, ptimes);
+			let (_, optimum) = brute_force_parallel(ptimes, num_machines, due_times, &MaxLateness).unwrap();
+			prop_assert!(optimum <= schedule.max_lateness(due_times));
+		}
+
+		#[test]
+		fn test_first_fit_decreasing_valid(instance in any::<SingleMachineInstance>(), num_machines in 1..=4usize) {
+			let ptimes = &instance.ptimes;
+			// every job fits on the first machine
+			let target: Time = ptimes.iter().sum();
+			let schedule = first_fit_decreasing(ptimes, num_machines, target).unwrap();
+			assert_valid_parallel_schedule(&schedule, ptimes);
+			prop_assert!(schedule.makespan() <= target);
+		}
+
+		#[test]
+		fn test_flow_shop_heuristics_valid(ptimes in flow_shop_ptimes(1..=4, 0..=6, 20)) {
+			let n = ptimes[0].len();
+			let (_, optimum) = brute_force_permutation_flowshop(&ptimes).unwrap();
+			for order in [dannenbring(&ptimes), neh(&ptimes), total_completion_heuristic(&ptimes)] {
+				assert_permutation(&order, n);
+				let schedule = MultiMachineSchedule::from_order_ptimes(&order, &ptimes);
+				assert_valid_flow_shop_schedule(&schedule, &ptimes);
+				prop_assert!(optimum <= schedule.makespan());
+			}
+		}
+
+		#[test]
+		fn test_hodgson_valid(mut instance in any::<SingleMachineInstance>()) {
+			// hodgson ignores release times
+			instance.release_times = vec![0; instance.num_jobs()];
+			let schedule = schedule_hodgson(&instance.ptimes, &instance.due_times);
+			assert_valid_schedule(&schedule, &instance);
+		}
+
+		#[test]
+		fn test_carlier_dominates_schrage(instance in any::<SingleMachineInstance>()) {
+			assert_carlier_dominates_schrage(&instance);
+		}
+
+		#[test]
+		fn test_preemption_dominates_carlier(instance in any::<SingleMachineInstance>()) {
+			assert_preemption_dominates_carlier(&instance);
+		}
+
+		#[test]
+		fn test_carlier_optimal(instance in small_instance()) {
+			let SingleMachineInstance{ ptimes, release_times, due_times } = &instance;
+			let (_, optimum) = brute_force_single_machine(ptimes, release_times, due_times, &MaxLateness).unwrap();
+			prop_assert_eq!(carlier(ptimes, release_times, due_times).max_lateness(due_times), optimum);
+		}
+
+		#[test]
+		fn test_johnson_matches_brute_force(ptimes in flow_shop_ptimes(2..=2, 0..=7, 20)) {
+			let order = johnson(&ptimes);
+			assert_permutation(&order, ptimes[0].len());
+			let optimal_order = brute_force_flow_shop_2(&ptimes);
+			prop_assert_eq!(
+				MultiMachineSchedule::from_order_ptimes(&order, &ptimes).makespan(),
+				MultiMachineSchedule::from_order_ptimes(&optimal_order, &ptimes).makespan()
+			);
+		}
+
+		#[test]
+		fn test_johnson_dominates_dannenbring(ptimes in flow_shop_ptimes(2..=2, 0..=8, 20)) {
+			let johnson = solve_johnson(&ptimes);
+			assert_permutation(&johnson.order, ptimes[0].len());
+			prop_assert!(johnson.makespan <= solve_dannenbring(&ptimes).makespan);
+		}
+
+		#[test]
+		fn test_serial_schedule_heuristic_valid(
+			(ptimes, predecessor) in (1..=3usize).prop_flat_map(|m|
+				precedence_dag(1..=8, 0.3).prop_flat_map(move |prec| {
+					let n = prec.len();
+					(collection::vec(collection::vec(1..=20 as Time, n), m), Just(prec))
+				})
+			)
+		) {
+			let schedule = serial_schedule_heuristic(&ptimes, predecessor.clone());
+			assert_respects_precedence(&schedule, &predecessor);
+		}
+	}
+}
@@ -0,0 +1,41 @@
+use crate::Time;
+
+
+/// The result of an iterative heuristic together with its convergence history,
+/// as returned by the `*_tracked` variants such as `job_shop::tabu_search_tracked`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TrackedRun<T> {
+	/// The result of the heuristic
+	pub result: T,
+	/// `best_per_iter[k]` is the objective value of the best solution found after iteration k
+	pub best_per_iter: Vec<Time>,
+}
+
+impl<T> TrackedRun<T> {
+	/// Returns the objective value after the last iteration, or None if no iteration was performed.
+	pub fn final_value(&self) -> Option<Time> {
+		self.best_per_iter.last().copied()
+	}
+
+	/// Returns the first iteration after which the final objective value was reached,
+	/// or None if no iteration was performed.
+	pub fn iterations_to_best(&self) -> Option<usize> {
+		let best = self.final_value()?;
+		self.best_per_iter.iter().position(|&v| v == best)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_iterations_to_best() {
+		let run = TrackedRun{ result: (), best_per_iter: vec![9, 7, 7, 4, 4] };
+		assert_eq!(run.final_value(), Some(4));
+		assert_eq!(run.iterations_to_best(), Some(3));
+		let empty = TrackedRun{ result: (), best_per_iter: vec![] };
+		assert_eq!((empty.final_value(), empty.iterations_to_best()), (None, None));
+	}
+}
@@ -0,0 +1,196 @@
+use crate::{Time, Job, MultiMachineSchedule, JobRun};
+use crate::schedule::assert_valid_ptimes;
+
+
+/// Solves Q||sum C_j, minimizing the total completion time on uniform machines, optimally.
+/// A job with processing time p takes ceil(p / s) time units on a machine of speed s.
+/// If a job is processed k-th to last on machine i with duration d, it delays k completions by d,
+/// so the total completion time is the sum of these contributions k * d over all jobs.
+/// Every job is assigned to such a position slot (i, k) by a minimum cost bipartite matching,
+/// which is the assignment of the longest jobs to the cheapest coefficients k / s_i
+/// when speeds divide all processing times.
+/// Runs in O(n^3 m) time for n jobs and m machines.
+///
+/// See Horn: "Minimizing average flow time with parallel machines" (1973); doi:10.1287/opre.21.3.846
+///
+/// # Arguments
+/// * `ptimes`: ptimes[j] is the processing time of job j on a machine of speed 1.
+/// * `speeds`: speeds[i] is the speed of machine i.
+/// * `num_machines`: The number of machines.
+///
+/// # Returns
+/// An optimal schedule, in which every machine processes its jobs without idle time from time 0.
+///
+/// # Panics
+/// If a processing time is negative, a speed is not positive,
+/// or the number of speeds differs from `num_machines`.
+pub fn uniform_total_completion(
+	ptimes: &[Time],
+	speeds: &[Time],
+	num_machines: usize
+) -> MultiMachineSchedule
+{
+	assert_valid_ptimes(ptimes);
+	assert_eq!(speeds.len(), num_machines, "Every machine needs a speed");
+	assert!(speeds.iter().all(|&s| s > 0), "Speeds must be positive");
+	let n = ptimes.len();
+	let mut schedule = MultiMachineSchedule::with_machines(num_machines);
+	if n == 0 {
+		return schedule;
+	}
+	assert!(num_machines > 0, "Instance must have at least one machine");
+	let duration = |job: Job, machine: usize| (ptimes[job] + speeds[machine] - 1) / speeds[machine];
+	// slot machine * n + k - 1 is the k-th to last position on the machine
+	let cost: Vec<Vec<Time>> = (0..n).map(|job| {
+		(0..num_machines)
+			.flat_map(|machine| (1..=n as Time).map(move |k| k * duration(job, machine)))
+			.collect()
+	}).collect();
+	let slots = min_cost_assignment(&cost);
+	for machine in 0..num_machines {
+		let mut jobs: Vec<(usize, Job)> = (0..n)
+			.filter(|&job| slots[job] / n == machine)
+			.map(|job| (slots[job] % n, job))
+			.collect();
+		// the job with the largest k comes first
+		jobs.sort_unstable_by(|a, b| b.cmp(a));
+		let mut time = 0;
+		for (_, job) in jobs {
+			schedule.append_run(machine, JobRun{ time, job, duration: duration(job, machine) });
+			time += duration(job, machine);
+		}
+	}
+	schedule
+}
+
+
+/// Hungarian algorithm for the assignment problem with at most as many rows as columns.
+/// Returns the column of every row in an assignment of minimum total cost.
+/// Runs in O(r^2 c) time for r rows and c columns.
+fn min_cost_assignment(cost: &[Vec<Time>]) -> Vec<usize> {
+	let rows = cost.len();
+	let cols = cost.first().map_or(0, |c| c.len());
+	assert!(rows <= cols, "Every row needs a column");
+	// potentials of rows and columns, and the row matched to each column, with index 0 as a sentinel
+	let mut u = vec![0; rows + 1];
+	let mut v = vec![0; cols + 1];
+	let mut row_of = vec![0; cols + 1];
+	let mut previous = vec![0; cols + 1];
+	for row in 1..=rows {
+		row_of[0] = row;
+		let mut col = 0;
+		let mut min_slack = vec![Time::MAX; cols + 1];
+		let mut used = vec![false; cols + 1];
+		// find a shortest augmenting path from the new row
+		while row_of[col] != 0 {
+			used[col] = true;
+			let r = row_of[col];
+			let mut delta = Time::MAX;
+			let mut next = 0;
+			for c in 1..=cols {
+				if !used[c] {
+					let slack = cost[r - 1][c - 1] - u[r] - v[c];
+					if slack < min_slack[c] {
+						min_slack[c] = slack;
+						previous[c] = col;
+					}
+					if min_slack[c] < delta {
+						delta = min_slack[c];
+						next = c;
+					}
+				}
+			}
+			for c in 0..=cols {
+				if used[c] {
+					u[row_of[c]] += delta;
+					v[c] -= delta;
+				} else {
+					min_slack[c] -= delta;
+				}
+			}
+			col = next;
+		}
+		// augment along the path
+		while col != 0 {
+			let p = previous[col];
+			row_of[col] = row_of[p];
+			col = p;
+		}
+	}
+	let mut result = vec![0; rows];
+	for c in 1..=cols {
+		if row_of[c] != 0 {
+			result[row_of[c] - 1] = c - 1;
+		}
+	}
+	result
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	/// Minimum total completion time over all assignments of jobs to machines,
+	/// with every machine processing its jobs in SPT order.
+	fn brute_force(ptimes: &[Time], speeds: &[Time]) -> Time {
+		let n = ptimes.len();
+		let m = speeds.len();
+		let mut best = Time::MAX;
+		for code in 0..m.pow(n as u32) {
+			let mut durations = vec![Vec::new(); m];
+			let mut c = code;
+			for &p in ptimes.iter() {
+				let machine = c % m;
+				c /= m;
+				durations[machine].push((p + speeds[machine] - 1) / speeds[machine]);
+			}
+			let total = durations.iter_mut().map(|d| {
+				d.sort_unstable();
+				d.iter().rev().enumerate().map(|(k, &d)| (k as Time + 1) * d).sum::<Time>()
+			}).sum();
+			best = best.min(total);
+		}
+		best
+	}
+
+	#[test]
+	fn test_min_cost_assignment() {
+		let cost = vec![
+			vec![4, 1, 3],
+			vec![2, 0, 5],
+			vec![3, 2, 2],
+		];
+		assert_eq!(min_cost_assignment(&cost), vec![1, 0, 2]);
+		assert_eq!(min_cost_assignment(&[vec![5, 3, 4]]), vec![1]);
+	}
+
+	#[test]
+	fn test_uniform_total_completion_example() {
+		// machine 1 is twice as fast
+		let ptimes = vec![2, 4, 6, 8, 10];
+		let schedule = uniform_total_completion(&ptimes, &[1, 2], 2);
+		// the cheapest coefficients are 1/2, 2/2 and 3/2 on the fast machine and 1 and 2 on the slow one,
+		// with the longest jobs taking the cheapest ones: 10 * 1/2 + 8 * 1 + 6 * 1 + 4 * 3/2 + 2 * 2
+		assert_eq!(schedule.total_completion_time(), 29);
+		assert_eq!(schedule.total_completion_time(), brute_force(&ptimes, &[1, 2]));
+		assert_eq!(schedule.machine_schedules[1].schedule.len(), 3);
+		assert_eq!(schedule.machine_schedules[1].schedule.last().unwrap().job, 4);
+		assert!(uniform_total_completion(&[], &[1], 1).machine_schedules[0].schedule.is_empty());
+	}
+
+	proptest! {
+		#[test]
+		fn test_uniform_total_completion_brute_force(
+			ptimes in proptest::collection::vec(0..=20 as Time, 0..=6),
+			speeds in proptest::collection::vec(1..=4 as Time, 1..=3),
+		) {
+			let schedule = uniform_total_completion(&ptimes, &speeds, speeds.len());
+			let mut jobs: Vec<Job> = schedule.machine_schedules.iter().flat_map(|s| s.job_order()).collect();
+			jobs.sort_unstable();
+			prop_assert_eq!(jobs, (0..ptimes.len()).collect::<Vec<Job>>());
+			prop_assert_eq!(schedule.total_completion_time(), brute_force(&ptimes, &speeds));
+		}
+	}
+}
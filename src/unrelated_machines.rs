@@ -1,12 +1,17 @@
-use std::cmp::max;
+use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, JobRun, Machine, EventClock};
+use crate::schedule::assert_valid_ptimes;
+use crate::cost::CostModel;
 
-use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, JobRun, Machine};
+use std::cmp::max;
 
 
 /// Makespan-minimization heuristic for scheduling on multiple unrelated machines with precedence constraints,
 /// i.e. for R|prec|C_max.
 /// The heuristic always selects the available job whose processing time has the highest variance 
 /// among the machines. This job is then greedily scheduled on the fastest machine currently avaiable.
+/// A processing time of `Time::MAX` marks a machine that cannot process the job at all.
+/// Such machines are ignored in the variance and never chosen for the job,
+/// and a job that cannot be processed by any idle machine waits for a machine that can.
 /// The running time is in O(jobs^2).
 ///
 /// See Liu & Yang "A heuristic serial schedule algorithm for unrelated parallel machine scheduling with
@@ -19,114 +24,198 @@ use crate::{Time, Job, MachineSchedule, MultiMachineSchedule, JobRun, Machine};
 /// # Returns
 /// The resulting schedule.
 ///
+/// # Panics
+/// If a processing time is negative, or a job cannot be processed by any machine.
+///
 pub fn serial_schedule_heuristic(
 	ptimes: &[Vec<Time>],
 	predecessor: Vec<Vec<Job>>
 ) -> MultiMachineSchedule
 {
+	let n = ptimes.first().map_or(0, |p| p.len());
+	serial_schedule_heuristic_releasetimes(ptimes, predecessor, &vec![0; n])
+}
+
+
+/// `serial_schedule_heuristic` for jobs with release times, i.e. for R|r_j,prec|C_max.
+/// A job is available once it is released and its predecessors are completed.
+/// Whenever no available job can be started, the heuristic waits for the next completion or release.
+///
+/// # Arguments
+/// * `ptimes`: Job processing times, where `ptimes[i][j]` is the time taken by machine `i` to process job `j`.
+/// * `predecessor`: Job predecessors, where `predecessor[i]` are the jobs that need to be completed before job `i` can be started.
+/// * `release_times`: `release_times[j]` is the earliest time at which job `j` can be started.
+///
+/// # Returns
+/// The resulting schedule.
+///
+/// # Panics
+/// If a processing time is negative, or a job cannot be processed by any machine.
+pub fn serial_schedule_heuristic_releasetimes(
+	ptimes: &[Vec<Time>],
+	predecessor: Vec<Vec<Job>>,
+	release_times: &[Time]
+) -> MultiMachineSchedule
+{
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
 	let m = ptimes.len(); // number of machines
 	if m == 0 {
 		return MultiMachineSchedule::new();
 	}
 	let n = ptimes[0].len(); // number of jobs
-	let mut schedules = vec![MachineSchedule::new(); m];
+	for job in 0..n {
+		assert!(ptimes.iter().any(|p| p[job] != Time::MAX), "Job #{} cannot be processed by any machine", job);
+	}
+	let mut schedule = MultiMachineSchedule::with_machines(m);
 	if n == 0 {
-		return MultiMachineSchedule{ machine_schedules: schedules }
+		return schedule;
 	}
-	let mut time = 0;
+	let mut clock = EventClock::with_releases(0, release_times);
+	let mut released = vec![false; n];
 	let mut pg = PrecedenceGraph::new(predecessor);
 	let mut machines_busy_until : Vec<Time> = vec![0; m];
-	let mut completion_times : Vec<(Time, Job)> = Vec::new();
-	for counter in 0.. {
+	let mut num_scheduled = 0;
+	loop {
+		while let Some(job) = clock.pop_release() {
+			released[job] = true;
+		}
+		for job in clock.completions_until(clock.time()) {
+			pg.mark_job_completed(job);
+		}
+		let time = clock.time();
 		let idle_machines : Vec<_> = machines_busy_until.iter().enumerate()
 			.filter(|&(_, &t)| t <= time)
 			.map(|(i, _)| i)
 			.collect();
-		let (machine, job, duration) = serial_schedule_heuristic_pick_next(
-			ptimes,
-			&idle_machines,
-			pg.available_jobs()
-		);
-		schedules[machine].schedule.push(
-			JobRun{
-				time,
-				job,
-				duration
-			}
-		);
-		if counter == n-1 {
-			break; // all jobs scheduled
+		if idle_machines.is_empty() {
+			// all machines are busy, so wait for the next completion
+			clock.advance_to(*machines_busy_until.iter().min().unwrap());
+			continue;
 		}
-		pg.mark_job_running(job);
-		completion_times.push((time + duration, job));
-		machines_busy_until[machine] = time + duration;
-		// wait for next avaiable machine
-		time = max(time, *machines_busy_until.iter().min().unwrap());
-		// mark completed jobs
-		completion_times.retain(|&(t, j)| {
-			if t <= time {
-				pg.mark_job_completed(j);
-			}
-			t > time
-		});
-		while pg.available_jobs().is_empty() {
-			// wait for next avaiable machine
-			time = *machines_busy_until.iter().filter(|&&t| t > time).min().unwrap();
-			// mark completed jobs
-			completion_times.retain(|&(t, j)| {
-				if t <= time {
-					pg.mark_job_completed(j);
+		let available: Vec<Job> = pg.available_jobs().iter().copied().filter(|&j| released[j]).collect();
+		match serial_schedule_heuristic_pick_next(ptimes, &idle_machines, &available) {
+			Some((machine, job, duration)) => {
+				schedule.append_run(
+					machine,
+					JobRun{
+						time,
+						job,
+						duration
+					}
+				);
+				num_scheduled += 1;
+				if num_scheduled == n {
+					break; // all jobs scheduled
 				}
-				t > time
-			});
+				pg.mark_job_running(job);
+				clock.add_completion(job, time + duration);
+				machines_busy_until[machine] = time + duration;
+			},
+			None => {
+				// no available job can be processed by an idle machine,
+				// so a job must be completed or released first
+				clock.advance_to(clock.next_event_time().unwrap());
+			},
 		}
 	}
-	MultiMachineSchedule{
-		machine_schedules: schedules
+	schedule
+}
+
+/// `serial_schedule_heuristic` together with a lower bound on the optimal makespan,
+/// which bounds how far the schedule can be from optimal.
+/// The bound is the larger of two bounds based on the minimum processing time of every job over the machines:
+/// the length of the longest chain of jobs in the precedence graph,
+/// and the total minimum processing time divided by the number of machines, rounded up.
+/// Computing the bound takes O(jobs^2 + machines * jobs) time.
+///
+/// # Arguments
+/// * `ptimes`: Job processing times, where `ptimes[i][j]` is the time taken by machine `i` to process job `j`.
+/// * `predecessor`: Job predecessors, where `predecessor[i]` are the jobs that need to be completed before job `i` can be started.
+///
+/// # Returns
+/// The resulting schedule and the lower bound.
+///
+/// # Panics
+/// If a processing time is negative, or a job cannot be processed by any machine.
+pub fn serial_schedule_heuristic_with_bound(
+	ptimes: &[Vec<Time>],
+	predecessor: Vec<Vec<Job>>
+) -> (MultiMachineSchedule, Time)
+{
+	let schedule = serial_schedule_heuristic(ptimes, predecessor.clone());
+	let m = ptimes.len();
+	if m == 0 {
+		return (schedule, 0);
+	}
+	let n = ptimes[0].len();
+	let min_ptimes: Vec<Time> = (0..n)
+		.map(|job| ptimes.iter().map(|p| p[job]).filter(|&p| p != Time::MAX).min().unwrap())
+		.collect();
+	// earliest completion times, computed in a topological order
+	let mut completion: Vec<Time> = vec![0; n];
+	let mut pg = PrecedenceGraph::new(predecessor.clone());
+	while let Some(&job) = pg.available_jobs().last() {
+		let start = predecessor[job].iter().map(|&k| completion[k]).max().unwrap_or(0);
+		completion[job] = start + min_ptimes[job];
+		pg.mark_job_completed(job);
 	}
+	let critical_path = completion.into_iter().max().unwrap_or(0);
+	let total: Time = min_ptimes.iter().sum();
+	let load = (total + m as Time - 1) / m as Time;
+	(schedule, max(critical_path, load))
 }
 
+/// Returns the next job to schedule together with its machine and processing time,
+/// or None if no available job can be processed by any of the idle machines.
 fn serial_schedule_heuristic_pick_next(
 	ptimes: &[Vec<Time>],
 	idle_machines: &[Machine],
 	available_jobs: &[Job],
-) -> (Machine, Job, Time)
+) -> Option<(Machine, Job, Time)>
 {
 	let machine;
 	let job;
 	let duration : Time;
 	assert!(!idle_machines.is_empty());
+	// jobs that some idle machine can process
+	let mut candidates = available_jobs.iter().copied()
+		.filter(|&j| idle_machines.iter().any(|&i| ptimes[i][j] != Time::MAX))
+		.peekable();
+	candidates.peek()?;
 	if idle_machines.len() == 1 {
 		// schedule the shortest job
 		machine = idle_machines[0];
-		(duration, job) = available_jobs.iter().map(|&j|
+		(duration, job) = candidates.map(|j|
 			(ptimes[machine][j], j)
 		).min().unwrap();
 	} else {
-		// select the job with the highest processing time variance:
-		(job, _) = available_jobs.iter().map(|&j| {
+		// select the job with the highest processing time variance
+		// among the machines that can process it:
+		(job, _) = candidates.map(|j| {
+				let eligible = || ptimes.iter().map(|p| p[j]).filter(|&p| p != Time::MAX);
+				let count = eligible().count() as f32;
 				// mean processing time:
-				let mean = 
-					ptimes.iter().map(|p| p[j] as f32).sum::<f32>()
-					/ idle_machines.len() as f32;
-				let variance = ptimes.iter().map(|p| 
-					(p[j] as f32 - mean)*(p[j] as f32 - mean)
-				).sum::<f32>() / idle_machines.len() as f32;
+				let mean = eligible().map(|p| p as f32).sum::<f32>() / count;
+				let variance = eligible().map(|p|
+					(p as f32 - mean)*(p as f32 - mean)
+				).sum::<f32>() / count;
 				(j, variance)
 		}).max_by(
 			|(_, v1), (_, v2)| v1.partial_cmp(v2).unwrap()
 		).unwrap();
-		// select the machine that's fastest for that job:
+		// select the idle machine that's fastest for that job:
 		(machine, duration) = idle_machines.iter()
 			.map(|&i| (i, ptimes[i][job]) )
+			.filter(|&(_, p)| p != Time::MAX)
 			.min_by_key(|&(_, p)| p)
 			.unwrap();
 	}
-	(machine, job, duration)
+	Some((machine, job, duration))
 }
 
 
-struct PrecedenceGraph {
+/// The jobs whose predecessors are all completed, updated as jobs are started and completed.
+pub(crate) struct PrecedenceGraph {
 	available: Vec<Job>,
 	predecessor: Vec<Vec<Job>>,
 }
@@ -176,6 +265,89 @@ impl PrecedenceGraph {
 }
 
 
+
+/// Heuristic minimizing the total cost of a `CostModel` on unrelated machines.
+/// Jobs are considered in order of their due times (the lowest job id in case of ties)
+/// and appended to the machine where this increases the cost the least.
+/// Afterwards, as long as the cost decreases, single jobs are moved to another position on any machine.
+/// Every machine processes its jobs without idle time from time 0,
+/// so inserting idle time may still reduce holding costs.
+///
+/// # Arguments
+/// * `ptimes`: Job processing times, where `ptimes[i][j]` is the time taken by machine `i` to process job `j`.
+/// * `model`: The cost model
+/// * `due_times`: `due_times[j]` is the due time of job j.
+///
+/// # Returns
+/// The resulting schedule.
+///
+/// # Panics
+/// If a processing time or a rate is negative, or the model has a different number of machines.
+pub fn min_cost_heuristic(ptimes: &[Vec<Time>], model: &CostModel, due_times: &[Time]) -> MultiMachineSchedule {
+	ptimes.iter().for_each(|p| assert_valid_ptimes(p));
+	assert_eq!(ptimes.len(), model.machine_rates.len(), "Every machine needs a rate");
+	assert!(
+		model.machine_rates.iter().chain(model.tardiness_rates.iter()).chain(model.holding_rates.iter()).all(|&r| r >= 0),
+		"Rates must not be negative"
+	);
+	let m = ptimes.len();
+	let n = ptimes.first().map_or(0, |p| p.len());
+	let cost = |sequences: &[Vec<Job>]| model.evaluate(&sequences_to_schedule(sequences, ptimes), due_times).total;
+	let mut order: Vec<Job> = (0..n).collect();
+	order.sort_by_key(|&j| (due_times[j], j));
+	let mut sequences: Vec<Vec<Job>> = vec![Vec::new(); m];
+	for job in order {
+		let machine = (0..m).min_by_key(|&i| {
+			sequences[i].push(job);
+			let c = cost(&sequences);
+			sequences[i].pop();
+			c
+		}).expect("Jobs need at least one machine");
+		sequences[machine].push(job);
+	}
+	// move single jobs until no move improves the cost
+	let mut current = cost(&sequences);
+	let mut improved = true;
+	while improved {
+		improved = false;
+		for from in 0..m {
+			let mut k = 0;
+			while k < sequences[from].len() {
+				let job = sequences[from].remove(k);
+				let positions: Vec<(Machine, usize)> = (0..m)
+					.flat_map(|to| (0..=sequences[to].len()).map(move |position| (to, position)))
+					.collect();
+				let best = positions.into_iter()
+					.map(|(to, position)| {
+						sequences[to].insert(position, job);
+						let c = cost(&sequences);
+						sequences[to].remove(position);
+						(c, to, position)
+					})
+					.min()
+					.unwrap();
+				if best.0 < current {
+					current = best.0;
+					sequences[best.1].insert(best.2, job);
+					improved = true;
+				} else {
+					sequences[from].insert(k, job);
+					k += 1;
+				}
+			}
+		}
+	}
+	sequences_to_schedule(&sequences, ptimes)
+}
+
+
+fn sequences_to_schedule(sequences: &[Vec<Job>], ptimes: &[Vec<Time>]) -> MultiMachineSchedule {
+	MultiMachineSchedule{ machine_schedules: sequences.iter().zip(ptimes.iter())
+		.map(|(sequence, p)| MachineSchedule::from_order_ptimes(sequence.iter().copied(), p))
+		.collect()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -222,10 +394,81 @@ mod tests {
 			vec![],
 			vec![],
 		];
-		let schedule = serial_schedule_heuristic(&p, prec);
+		let schedule = serial_schedule_heuristic(&p, prec.clone());
 		// optimal makespan is actually 12 
 		// (run jobs 3, 5, 4, 1 on machine 0)
 		assert!(schedule.makespan() <= 13);
+		let (with_bound, bound) = serial_schedule_heuristic_with_bound(&p, prec);
+		assert_eq!(with_bound, schedule);
+		// the chain 3, 0, 1 takes at least 2 + 4 + 4
+		assert_eq!(bound, 10);
+	}
+
+	#[test]
+	fn test_serial_schedule_heuristic_ineligible() {
+		let x = Time::MAX;
+		let p = vec![
+			vec![x,  1, x],
+			vec![2, 10, 6],
+		];
+		// job 0 can only run on machine 1 and has to wait for job 2 there,
+		// although machine 0 is idle from time 1
+		let prec = vec![vec![1], vec![], vec![]];
+		let schedule = serial_schedule_heuristic(&p, prec);
+		assert_eq!(schedule.machine_schedules[0].job_order(), vec![1]);
+		assert_eq!(schedule.machine_schedules[1].schedule, vec![
+			JobRun{ time: 0, job: 2, duration: 6 },
+			JobRun{ time: 6, job: 0, duration: 2 },
+		]);
+		assert_eq!(schedule.makespan(), 8);
+	}
+
+	#[test]
+	fn test_serial_schedule_heuristic_releasetimes() {
+		let p = vec![
+			vec![2, 3, 4],
+			vec![2, 3, 4],
+		];
+		let prec = vec![vec![], vec![0], vec![]];
+		// job 1 is ready when job 0 completes at 2, but released at 5
+		let schedule = serial_schedule_heuristic_releasetimes(&p, prec.clone(), &[0, 5, 0]);
+		assert_eq!(schedule.job_timeline(1)[0].1.time, 5);
+		assert_eq!(schedule.makespan(), 8);
+		// a late release of job 0 delays its successor
+		let schedule = serial_schedule_heuristic_releasetimes(&p, prec.clone(), &[3, 0, 0]);
+		assert_eq!(schedule.job_timeline(1)[0].1.time, 5);
+		assert_eq!(serial_schedule_heuristic_releasetimes(&p, prec.clone(), &[0; 3]), serial_schedule_heuristic(&p, prec));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_serial_schedule_heuristic_never_eligible() {
+		let x = Time::MAX;
+		serial_schedule_heuristic(&[vec![1, x], vec![2, x]], vec![vec![], vec![]]);
+	}
+
+	#[test]
+	fn test_min_cost_heuristic() {
+		let p = vec![
+			vec![2, 2, 2, 2],
+			vec![1, 1, 1, 1],
+		];
+		let due_times = vec![2, 4, 6, 8];
+		// machine 1 is faster, but expensive
+		let model = CostModel{
+			machine_rates: vec![1, 10],
+			tardiness_rates: vec![1, 1, 1, 1],
+			holding_rates: vec![0, 0, 0, 0],
+		};
+		let schedule = min_cost_heuristic(&p, &model, &due_times);
+		assert_eq!(schedule.machine_schedules[0].job_order(), vec![0, 1, 2, 3]);
+		assert_eq!(model.evaluate(&schedule, &due_times).total, 8);
+		// with high tardiness rates, the fast machine pays off
+		let model = CostModel{ tardiness_rates: vec![100; 4], ..model };
+		let schedule = min_cost_heuristic(&p, &model, &due_times);
+		assert_eq!(model.evaluate(&schedule, &due_times).tardiness_cost, 0);
+		let model = CostModel::zero(2, 4);
+		assert_eq!(model.evaluate(&min_cost_heuristic(&p, &model, &due_times), &due_times).total, 0);
 	}
 
 	#[test]
@@ -244,7 +487,10 @@ mod tests {
 			vec![1],
 			vec![2],
 		];
-		let schedule = serial_schedule_heuristic(&p, prec);
+		let schedule = serial_schedule_heuristic(&p, prec.clone());
 		assert_eq!(schedule.makespan(), 13);
+		// the minimum processing times sum up to 23 on 2 machines
+		assert_eq!(serial_schedule_heuristic_with_bound(&p, prec).1, 12);
+		assert_eq!(serial_schedule_heuristic_with_bound(&[], vec![]).1, 0);
 	}
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{MachineSchedule, ScheduleError, SingleMachineInstance, json};
+use crate::instances::from_json;
+use crate::single_machine::{schrage, carlier, edd_preemptive, schedule_hodgson};
+
+
+/// Solves a single machine instance given in the JSON exchange format of `instances::to_json`
+/// with one of the algorithms
+/// * `"schrage"`: `single_machine::schrage` for 1|r_j|L_max
+/// * `"carlier"`: `single_machine::carlier` for 1|r_j|L_max
+/// * `"edd_preemptive"`: `single_machine::edd_preemptive` for 1|r_j,pmtn|L_max
+/// * `"hodgson"`: `single_machine::schedule_hodgson` for 1||sum U_j, ignoring the release times
+///
+/// With the `wasm` feature, this function is exported to JavaScript, e.g. for planning tools running in a browser.
+///
+/// # Returns
+/// The schedule in the JSON exchange format of `MachineSchedule::to_json`,
+/// or `{"error": "<message>"}` if the instance is invalid or the algorithm is unknown.
+#[wasm_bindgen]
+pub fn solve_single_machine(json_instance: &str, algorithm: &str) -> String {
+	match solve(json_instance, algorithm) {
+		Ok(schedule) => schedule.to_json(),
+		Err(message) => {
+			let mut out = String::from("{\"error\": ");
+			json::write_string(&mut out, &message);
+			out.push_str("}\n");
+			out
+		},
+	}
+}
+
+fn solve(json_instance: &str, algorithm: &str) -> Result<MachineSchedule, String> {
+	let SingleMachineInstance{ ptimes, release_times, due_times } = from_json(json_instance)
+		.map_err(|e: ScheduleError| e.to_string())?;
+	match algorithm {
+		"schrage" => Ok(schrage(&ptimes, &release_times, &due_times)),
+		"carlier" => Ok(carlier(&ptimes, &release_times, &due_times)),
+		"edd_preemptive" => Ok(edd_preemptive(ptimes, &release_times, &due_times)),
+		"hodgson" => Ok(schedule_hodgson(&ptimes, &due_times)),
+		_ => Err(format!("Unknown algorithm \"{}\"", algorithm)),
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const INSTANCE: &str = "{\"processing_times\": [3, 2, 4], \"release_times\": [0, 1, 1], \"due_times\": [5, 4, 9]}";
+
+	#[test]
+	fn test_solve_single_machine() {
+		for algorithm in ["schrage", "carlier", "edd_preemptive", "hodgson"] {
+			let schedule = MachineSchedule::from_json(&solve_single_machine(INSTANCE, algorithm)).unwrap();
+			let mut jobs = schedule.job_order();
+			jobs.sort_unstable();
+			jobs.dedup();
+			assert_eq!(jobs, vec![0, 1, 2], "{}", algorithm);
+		}
+		let carlier = MachineSchedule::from_json(&solve_single_machine(INSTANCE, "carlier")).unwrap();
+		assert_eq!(carlier.max_lateness(&[5, 4, 9]), 1);
+	}
+
+	#[test]
+	fn test_errors() {
+		assert_eq!(solve_single_machine(INSTANCE, "\"fifo\""), "{\"error\": \"Unknown algorithm \\\"\\\"fifo\\\"\\\"\"}\n");
+		assert!(solve_single_machine("{\"processing_times\": [1]}", "schrage").starts_with("{\"error\": "));
+	}
+}
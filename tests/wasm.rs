@@ -0,0 +1,16 @@
+// Runs in a headless browser with `wasm-pack test --headless --firefox -- --features wasm`.
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use schedulib::MachineSchedule;
+use schedulib::wasm::solve_single_machine;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn test_solve_single_machine() {
+	let instance = "{\"processing_times\": [3, 2, 4], \"release_times\": [0, 1, 1], \"due_times\": [5, 4, 9]}";
+	let schedule = MachineSchedule::from_json(&solve_single_machine(instance, "schrage")).unwrap();
+	assert_eq!(schedule.max_lateness(&[5, 4, 9]), 1);
+	assert!(solve_single_machine(instance, "unknown").starts_with("{\"error\": "));
+}